@@ -0,0 +1,165 @@
+use super::provider::{CiCheck, CiContext, CiProvider};
+use crate::types::CiStatus;
+use crate::util::rate_limit;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Bitbucket Server/Data Center-specific configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct BitbucketCiConfig {
+    pub project_key: String,
+    pub repo_slug: String,
+    pub web_base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BuildStatusPage {
+    #[serde(default)]
+    values: Vec<BuildStatus>,
+}
+
+/// A build result posted to the commit by an external CI server (Bamboo, Jenkins, ...)
+#[derive(Debug, Deserialize, Serialize)]
+struct BuildStatus {
+    state: String,
+    key: String,
+    name: Option<String>,
+    url: Option<String>,
+    description: Option<String>,
+}
+
+/// Build statuses fetched per commit; more than this is unusual
+const BUILD_STATUS_LIMIT: usize = 100;
+
+#[derive(Clone)]
+pub struct BitbucketCiProvider {
+    http_client: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl BitbucketCiProvider {
+    pub fn new(token: String, base_url: String) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http_client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    async fn get_build_statuses(&self, commit_sha: &str) -> Result<Vec<BuildStatus>> {
+        let url = format!(
+            "{}/rest/build-status/1.0/commits/{}?limit={}",
+            self.base_url, commit_sha, BUILD_STATUS_LIMIT
+        );
+        let response =
+            rate_limit::send(self.http_client.get(&url).bearer_auth(&self.token)).await?;
+        if !response.status().is_success() {
+            anyhow::bail!("Bitbucket returned {} for {}", response.status(), url);
+        }
+        let page: BuildStatusPage = response.json().await?;
+        Ok(page.values)
+    }
+}
+
+#[async_trait::async_trait]
+impl CiProvider for BitbucketCiProvider {
+    fn id(&self) -> &'static str {
+        "bitbucket"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Bitbucket"
+    }
+
+    async fn poll(&self, ctx: &CiContext) -> Result<Vec<CiCheck>> {
+        let statuses = self.get_build_statuses(&ctx.commit_sha).await?;
+
+        // Newest first; a key reported more than once keeps its latest state
+        let mut checks: Vec<CiCheck> = Vec::new();
+        for status in statuses {
+            let context = format!("build:{}", status.key);
+            if checks.iter().any(|check| check.context == context) {
+                continue;
+            }
+            checks.push(CiCheck {
+                provider: "bitbucket".to_string(),
+                context,
+                name: status.name.clone().unwrap_or_else(|| status.key.clone()),
+                state: map_build_state(&status.state),
+                target_url: status.url.clone(),
+                description: status.description.clone(),
+                external_id: Some(status.key.clone()),
+                raw_json: serde_json::to_value(&status).ok(),
+            });
+        }
+
+        Ok(checks)
+    }
+
+    fn get_commit_url(&self, ctx: &CiContext) -> Result<String> {
+        let cfg: BitbucketCiConfig = ctx.cfg()?;
+        Ok(format!(
+            "{}/projects/{}/repos/{}/commits/{}",
+            cfg.web_base_url.trim_end_matches('/'),
+            cfg.project_key,
+            cfg.repo_slug,
+            ctx.commit_sha
+        ))
+    }
+}
+
+/// Map a Bitbucket build state to a CI status
+fn map_build_state(state: &str) -> CiStatus {
+    match state {
+        "SUCCESSFUL" => CiStatus::Passed,
+        "FAILED" | "CANCELLED" => CiStatus::Failed,
+        // INPROGRESS, UNKNOWN
+        _ => CiStatus::Pending,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::serve_routes;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_poll_build_statuses() {
+        let (base_url, _) = serve_routes(HashMap::from([(
+            "/rest/build-status/1.0/commits/abc123",
+            r#"{"size": 3, "isLastPage": true, "values": [
+                {"state": "FAILED", "key": "WID-BUILD", "name": "Build", "url": "https://bamboo/WID-BUILD-8"},
+                {"state": "SUCCESSFUL", "key": "WID-BUILD", "name": "Build", "url": "https://bamboo/WID-BUILD-7"},
+                {"state": "INPROGRESS", "key": "deploy-preview"}
+            ]}"#,
+        )]));
+
+        let provider = BitbucketCiProvider::new("bb-token".to_string(), base_url).unwrap();
+        let ctx = CiContext {
+            commit_sha: "abc123".to_string(),
+            branch: "maestro/abc".to_string(),
+            provider_cfg: serde_json::json!({
+                "project_key": "WID",
+                "repo_slug": "widgets",
+                "web_base_url": "https://bb.example.com",
+            }),
+        };
+
+        let checks = provider.poll(&ctx).await.unwrap();
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].context, "build:WID-BUILD");
+        assert_eq!(checks[0].state, CiStatus::Failed);
+        assert_eq!(checks[1].name, "deploy-preview");
+        assert_eq!(checks[1].state, CiStatus::Pending);
+
+        assert_eq!(
+            provider.get_commit_url(&ctx).unwrap(),
+            "https://bb.example.com/projects/WID/repos/widgets/commits/abc123"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use super::provider::{CiCheck, CiContext, CiProvider};
+use crate::types::CiStatus;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+
+/// Command provider configuration, stored per repository
+/// The whole config is also passed to the command as `provider_cfg`, so extra keys
+/// (pipeline slugs, dashboard ids, ...) are available to the script
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandCiConfig {
+    /// Executable to run (absolute path or name on PATH)
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Link shown for the commit; `{commit_sha}` and `{branch}` are substituted
+    pub commit_url: String,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+/// A check as printed by the command
+#[derive(Debug, Deserialize)]
+struct CommandCheck {
+    context: String,
+    name: Option<String>,
+    /// pending | passed | failed | skipped
+    state: CiStatus,
+    target_url: Option<String>,
+    description: Option<String>,
+    external_id: Option<String>,
+}
+
+/// Stderr kept in error messages when the command fails
+const MAX_STDERR_BYTES: usize = 2000;
+
+#[derive(Clone, Default)]
+pub struct CommandCiProvider;
+
+impl CommandCiProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Run the command with the context as JSON on stdin and return its stdout
+    async fn run(&self, cfg: &CommandCiConfig, ctx: &CiContext) -> Result<Vec<u8>> {
+        let input = serde_json::to_vec(ctx)?;
+
+        let mut child = tokio::process::Command::new(&cfg.command)
+            .args(&cfg.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to run CI command '{}'", cfg.command))?;
+
+        // The command may exit without reading stdin; a broken pipe isn't an error
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(&input).await;
+        }
+
+        let output = tokio::time::timeout(
+            std::time::Duration::from_secs(cfg.timeout_secs),
+            child.wait_with_output(),
+        )
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "CI command '{}' timed out after {}s",
+                cfg.command,
+                cfg.timeout_secs
+            )
+        })??;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            let start = stderr.len().saturating_sub(MAX_STDERR_BYTES);
+            let start = (start..stderr.len())
+                .find(|i| stderr.is_char_boundary(*i))
+                .unwrap_or(stderr.len());
+            anyhow::bail!(
+                "CI command '{}' exited with {}: {}",
+                cfg.command,
+                output.status,
+                &stderr[start..]
+            );
+        }
+
+        Ok(output.stdout)
+    }
+}
+
+#[async_trait::async_trait]
+impl CiProvider for CommandCiProvider {
+    fn id(&self) -> &'static str {
+        "command"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Custom command"
+    }
+
+    async fn poll(&self, ctx: &CiContext) -> Result<Vec<CiCheck>> {
+        let cfg: CommandCiConfig = ctx.cfg()?;
+        let stdout = self.run(&cfg, ctx).await?;
+        parse_checks(&stdout)
+            .with_context(|| format!("CI command '{}' printed invalid checks", cfg.command))
+    }
+
+    fn get_commit_url(&self, ctx: &CiContext) -> Result<String> {
+        let cfg: CommandCiConfig = ctx.cfg()?;
+        Ok(cfg
+            .commit_url
+            .replace("{commit_sha}", &ctx.commit_sha)
+            .replace("{branch}", &ctx.branch))
+    }
+}
+
+/// Parse the JSON array of checks printed by the command
+fn parse_checks(stdout: &[u8]) -> Result<Vec<CiCheck>> {
+    let raw: Vec<serde_json::Value> = serde_json::from_slice(stdout)?;
+
+    raw.into_iter()
+        .map(|value| {
+            let check: CommandCheck = serde_json::from_value(value.clone())?;
+            Ok(CiCheck {
+                provider: "command".to_string(),
+                name: check.name.unwrap_or_else(|| check.context.clone()),
+                context: format!("command:{}", check.context),
+                state: check.state,
+                target_url: check.target_url,
+                description: check.description,
+                external_id: check.external_id,
+                raw_json: Some(value),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(script: &str) -> CiContext {
+        CiContext {
+            commit_sha: "abc123".to_string(),
+            branch: "maestro/abc".to_string(),
+            provider_cfg: serde_json::json!({
+                "command": "sh",
+                "args": ["-c", script],
+                "commit_url": "https://ci.example.com/builds?commit={commit_sha}",
+                "pipeline": "widgets",
+            }),
+        }
+    }
+
+    #[test]
+    fn test_parse_checks() {
+        let stdout = br#"[
+            {"context": "build", "state": "passed", "target_url": "https://ci.example.com/1"},
+            {"context": "deploy", "name": "Deploy preview", "state": "pending"}
+        ]"#;
+        let checks = parse_checks(stdout).unwrap();
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].context, "command:build");
+        assert_eq!(checks[0].name, "build");
+        assert_eq!(checks[0].state, CiStatus::Passed);
+        assert_eq!(checks[1].name, "Deploy preview");
+
+        assert!(parse_checks(br#"[{"context": "build", "state": "green"}]"#).is_err());
+        assert!(parse_checks(b"not json").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_poll_passes_context_on_stdin() {
+        // Reports a failure only when the commit SHA and provider_cfg arrive on stdin
+        let script = r#"input=$(cat)
+            case "$input" in
+              *abc123*widgets*|*widgets*abc123*) echo '[{"context": "ci", "state": "failed"}]' ;;
+              *) echo '[]' ;;
+            esac"#;
+        let provider = CommandCiProvider::new();
+
+        let checks = provider.poll(&ctx(script)).await.unwrap();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].state, CiStatus::Failed);
+        assert_eq!(
+            provider.get_commit_url(&ctx(script)).unwrap(),
+            "https://ci.example.com/builds?commit=abc123"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_poll_reports_command_failure() {
+        let provider = CommandCiProvider::new();
+        let err = provider
+            .poll(&ctx("echo 'no token' >&2; exit 3"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no token"));
+    }
+}
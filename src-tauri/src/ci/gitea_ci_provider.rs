@@ -0,0 +1,465 @@
+use super::provider::{CiCheck, CiContext, CiProvider};
+use crate::git::git_provider::parse_timestamp;
+use crate::types::CiStatus;
+use crate::util::rate_limit;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Gitea/Forgejo-specific configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct GiteaCiConfig {
+    pub owner: String,
+    pub repo: String,
+    pub web_base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CombinedStatus {
+    #[serde(default)]
+    statuses: Vec<CommitStatus>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CommitStatus {
+    id: u64,
+    /// Older Gitea releases call this field `state`
+    #[serde(alias = "state")]
+    status: String,
+    context: String,
+    target_url: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActionTaskList {
+    #[serde(default)]
+    workflow_runs: Vec<ActionTask>,
+}
+
+/// One job of an Actions workflow run
+#[derive(Debug, Deserialize, Serialize)]
+struct ActionTask {
+    id: u64,
+    name: String,
+    head_sha: String,
+    status: String,
+    url: Option<String>,
+    display_title: Option<String>,
+    created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitCommit {
+    commit: GitCommitDetails,
+    #[serde(default)]
+    parents: Vec<GitCommitParent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitCommitDetails {
+    committer: GitCommitPerson,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitCommitPerson {
+    date: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitCommitParent {
+    sha: String,
+}
+
+/// How many recent Actions tasks to search for the commit
+const TASK_HISTORY_DEPTH: usize = 50;
+
+/// How many pages of Actions tasks to go back through before giving up on confirming no CI
+const MAX_TASK_PAGES: usize = 10;
+
+/// Slack for a committer clock running ahead of the Gitea server's
+const CLOCK_SKEW_MS: i64 = 15 * 60 * 1000;
+
+#[derive(Clone)]
+pub struct GiteaCiProvider {
+    http_client: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl GiteaCiProvider {
+    pub fn new(token: String, base_url: String) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http_client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    /// GET a repository API path, returning None on 404 (e.g. Actions disabled or unsupported)
+    async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        cfg: &GiteaCiConfig,
+        path: &str,
+    ) -> Result<Option<T>> {
+        let url = format!(
+            "{}/api/v1/repos/{}/{}/{}",
+            self.base_url, cfg.owner, cfg.repo, path
+        );
+        let response = rate_limit::send(
+            self.http_client
+                .get(&url)
+                .header("Authorization", format!("token {}", self.token)),
+        )
+        .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("Gitea returned {} for {}", response.status(), url);
+        }
+        Ok(Some(response.json().await?))
+    }
+
+    /// Actions jobs that ran for the commit, latest attempt per job name
+    /// None when the instance has no Actions API for the repository
+    async fn get_action_tasks(
+        &self,
+        cfg: &GiteaCiConfig,
+        commit_sha: &str,
+    ) -> Result<Option<Vec<ActionTask>>> {
+        let Some(list) = self
+            .get::<ActionTaskList>(
+                cfg,
+                &format!("actions/tasks?page=1&limit={}", TASK_HISTORY_DEPTH),
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // Tasks are listed newest first, so the first task per name is the latest attempt
+        let mut tasks: Vec<ActionTask> = Vec::new();
+        for task in list.workflow_runs {
+            if task.head_sha.eq_ignore_ascii_case(commit_sha)
+                && !tasks.iter().any(|seen| seen.name == task.name)
+            {
+                tasks.push(task);
+            }
+        }
+        Ok(Some(tasks))
+    }
+
+    /// Whether no Actions task ran for the commit, going back through the repository's tasks
+    /// until ones created before `since` (ms since epoch); false when that can't be told
+    async fn no_tasks_since(
+        &self,
+        cfg: &GiteaCiConfig,
+        commit_sha: &str,
+        since: i64,
+    ) -> Result<bool> {
+        for page in 1..=MAX_TASK_PAGES {
+            let Some(list) = self
+                .get::<ActionTaskList>(
+                    cfg,
+                    &format!("actions/tasks?page={}&limit={}", page, TASK_HISTORY_DEPTH),
+                )
+                .await?
+            else {
+                return Ok(false);
+            };
+            let tasks = list.workflow_runs;
+            if tasks
+                .iter()
+                .any(|task| task.head_sha.eq_ignore_ascii_case(commit_sha))
+            {
+                return Ok(false);
+            }
+            // Tasks are listed newest first, so older pages only hold tasks from before the push
+            let reached_since = tasks
+                .last()
+                .and_then(|task| task.created_at.as_deref())
+                .and_then(parse_timestamp)
+                .is_some_and(|created_at| created_at < since);
+            if tasks.len() < TASK_HISTORY_DEPTH || reached_since {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Whether a commit status was published by an Actions job rather than external CI
+fn is_actions_status(status: &CommitStatus) -> bool {
+    status
+        .target_url
+        .as_deref()
+        .is_some_and(|url| url.contains("/actions/runs/"))
+}
+
+#[async_trait::async_trait]
+impl CiProvider for GiteaCiProvider {
+    fn id(&self) -> &'static str {
+        "gitea"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Gitea"
+    }
+
+    async fn poll(&self, ctx: &CiContext) -> Result<Vec<CiCheck>> {
+        let cfg: GiteaCiConfig = ctx.cfg()?;
+
+        let tasks = self
+            .get_action_tasks(&cfg, &ctx.commit_sha)
+            .await?
+            .unwrap_or_default();
+        let combined: CombinedStatus = self
+            .get(&cfg, &format!("commits/{}/status", ctx.commit_sha))
+            .await?
+            .unwrap_or(CombinedStatus {
+                statuses: Vec::new(),
+            });
+
+        let mut checks: Vec<CiCheck> = tasks
+            .iter()
+            .map(|task| CiCheck {
+                provider: "gitea".to_string(),
+                context: format!("actions:{}", task.name),
+                name: task.name.clone(),
+                state: map_action_status(&task.status),
+                target_url: task.url.clone(),
+                description: task.display_title.clone(),
+                external_id: Some(task.id.to_string()),
+                raw_json: serde_json::to_value(task).ok(),
+            })
+            .collect();
+
+        // Actions also publish a commit status per job; skip those when the jobs were found above
+        let skip_actions_statuses = !tasks.is_empty();
+        checks.extend(
+            combined
+                .statuses
+                .iter()
+                .filter(|status| !(skip_actions_statuses && is_actions_status(status)))
+                .map(|status| CiCheck {
+                    provider: "gitea".to_string(),
+                    context: format!("status:{}", status.context),
+                    name: status.context.clone(),
+                    state: map_gitea_status_state(&status.status),
+                    target_url: status.target_url.clone(),
+                    description: status.description.clone(),
+                    external_id: Some(status.id.to_string()),
+                    raw_json: serde_json::to_value(status).ok(),
+                }),
+        );
+
+        Ok(checks)
+    }
+
+    fn get_commit_url(&self, ctx: &CiContext) -> Result<String> {
+        let cfg: GiteaCiConfig = ctx.cfg()?;
+        Ok(format!(
+            "{}/{}/{}/commit/{}",
+            cfg.web_base_url.trim_end_matches('/'),
+            cfg.owner,
+            cfg.repo,
+            ctx.commit_sha
+        ))
+    }
+
+    async fn confirms_no_ci(&self, ctx: &CiContext) -> Result<bool> {
+        let cfg: GiteaCiConfig = ctx.cfg()?;
+        let Some(commit) = self
+            .get::<GitCommit>(&cfg, &format!("git/commits/{}", ctx.commit_sha))
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        // External CI reports whenever it gets to the commit; if it reported on the parent,
+        // it's likely still to report here
+        for parent in &commit.parents {
+            let combined = self
+                .get::<CombinedStatus>(&cfg, &format!("commits/{}/status", parent.sha))
+                .await?;
+            if combined.is_some_and(|combined| {
+                combined
+                    .statuses
+                    .iter()
+                    .any(|status| !is_actions_status(status))
+            }) {
+                return Ok(false);
+            }
+        }
+
+        // Actions runs are created when the push is received, which is after the commit was
+        // made, so once the grace period has passed, no task since then means no workflow matched
+        let Some(committed_at) = parse_timestamp(&commit.commit.committer.date) else {
+            return Ok(false);
+        };
+        self.no_tasks_since(&cfg, &ctx.commit_sha, committed_at - CLOCK_SKEW_MS)
+            .await
+    }
+}
+
+/// Map a Gitea commit status state to a CI status
+fn map_gitea_status_state(state: &str) -> CiStatus {
+    match state {
+        "success" | "warning" => CiStatus::Passed,
+        "failure" | "error" => CiStatus::Failed,
+        _ => CiStatus::Pending,
+    }
+}
+
+/// Map a Gitea Actions job status to a CI status
+fn map_action_status(status: &str) -> CiStatus {
+    match status {
+        "success" => CiStatus::Passed,
+        "failure" | "cancelled" => CiStatus::Failed,
+        "skipped" => CiStatus::Skipped,
+        // running, waiting, blocked, unknown
+        _ => CiStatus::Pending,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{serve, serve_routes, StubResponse};
+    use std::collections::HashMap;
+
+    fn ctx(base_url: &str) -> CiContext {
+        CiContext {
+            commit_sha: "abc123".to_string(),
+            branch: "maestro/abc".to_string(),
+            provider_cfg: serde_json::json!({
+                "owner": "acme",
+                "repo": "widgets",
+                "web_base_url": base_url,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_actions_and_statuses() {
+        let (base_url, _) = serve_routes(HashMap::from([
+            (
+                "/api/v1/repos/acme/widgets/actions/tasks",
+                r#"{"total_count": 3, "workflow_runs": [
+                    {"id": 12, "name": "test", "head_sha": "abc123", "status": "failure", "url": "http://git/acme/widgets/actions/runs/4"},
+                    {"id": 11, "name": "test", "head_sha": "abc123", "status": "success"},
+                    {"id": 10, "name": "lint", "head_sha": "fff999", "status": "success"}
+                ]}"#,
+            ),
+            (
+                "/api/v1/repos/acme/widgets/commits/abc123/status",
+                r#"{"state": "failure", "statuses": [
+                    {"id": 7, "status": "failure", "context": "CI / test (push)", "target_url": "http://git/acme/widgets/actions/runs/4/jobs/0"},
+                    {"id": 8, "status": "pending", "context": "ci/woodpecker", "target_url": "http://woodpecker/1"}
+                ]}"#,
+            ),
+        ]));
+
+        let provider = GiteaCiProvider::new("gitea-token".to_string(), base_url.clone()).unwrap();
+        let ctx = ctx(&base_url);
+
+        let checks = provider.poll(&ctx).await.unwrap();
+        let contexts: Vec<&str> = checks.iter().map(|c| c.context.as_str()).collect();
+        assert_eq!(contexts, vec!["actions:test", "status:ci/woodpecker"]);
+        assert_eq!(checks[0].state, CiStatus::Failed);
+        assert_eq!(checks[1].state, CiStatus::Pending);
+
+        assert_eq!(
+            provider.get_commit_url(&ctx).unwrap(),
+            format!("{}/acme/widgets/commit/abc123", base_url)
+        );
+        assert!(!provider.confirms_no_ci(&ctx).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_poll_without_actions_api() {
+        let (base_url, _) = serve_routes(HashMap::from([(
+            "/api/v1/repos/acme/widgets/commits/abc123/status",
+            r#"{"state": "success", "statuses": [
+                {"id": 1, "state": "success", "context": "CI / build (push)", "target_url": "http://git/acme/widgets/actions/runs/1/jobs/0"}
+            ]}"#,
+        )]));
+
+        let provider = GiteaCiProvider::new("gitea-token".to_string(), base_url.clone()).unwrap();
+        let ctx = ctx(&base_url);
+
+        // Actions statuses are kept when the jobs themselves can't be listed
+        let checks = provider.poll(&ctx).await.unwrap();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].context, "status:CI / build (push)");
+        assert_eq!(checks[0].state, CiStatus::Passed);
+        assert!(!provider.confirms_no_ci(&ctx).await.unwrap());
+    }
+
+    #[test]
+    fn test_map_action_status() {
+        assert_eq!(map_action_status("success"), CiStatus::Passed);
+        assert_eq!(map_action_status("cancelled"), CiStatus::Failed);
+        assert_eq!(map_action_status("skipped"), CiStatus::Skipped);
+        assert_eq!(map_action_status("waiting"), CiStatus::Pending);
+        assert_eq!(map_gitea_status_state("error"), CiStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_confirms_no_ci() {
+        // A full page of newer tasks for other commits, then one from before the commits
+        let newer: Vec<String> = (0..TASK_HISTORY_DEPTH)
+            .map(|id| {
+                format!(
+                    r#"{{"id": {}, "name": "test", "head_sha": "fff999", "status": "success", "created_at": "2026-03-02T12:00:00Z"}}"#,
+                    100 - id
+                )
+            })
+            .collect();
+        let first_page = format!(r#"{{"workflow_runs": [{}]}}"#, newer.join(","));
+        let (base_url, requests) = serve(move |request| match request.url.as_str() {
+            "/api/v1/repos/acme/widgets/git/commits/abc123" => StubResponse::ok(
+                r#"{"commit": {"committer": {"date": "2026-03-01T12:00:00Z"}}, "parents": [{"sha": "aaa000"}]}"#,
+            ),
+            "/api/v1/repos/acme/widgets/git/commits/def456" => StubResponse::ok(
+                r#"{"commit": {"committer": {"date": "2026-03-01T12:00:00Z"}}, "parents": [{"sha": "ddd000"}]}"#,
+            ),
+            // Only Actions reported on one parent, external CI on the other
+            "/api/v1/repos/acme/widgets/commits/aaa000/status" => StubResponse::ok(
+                r#"{"statuses": [{"id": 1, "status": "success", "context": "CI / test (push)", "target_url": "http://git/acme/widgets/actions/runs/1/jobs/0"}]}"#,
+            ),
+            "/api/v1/repos/acme/widgets/commits/ddd000/status" => StubResponse::ok(
+                r#"{"statuses": [{"id": 2, "status": "success", "context": "ci/woodpecker", "target_url": "http://woodpecker/1"}]}"#,
+            ),
+            "/api/v1/repos/acme/widgets/actions/tasks?page=1&limit=50" => {
+                StubResponse::ok(first_page.clone())
+            }
+            "/api/v1/repos/acme/widgets/actions/tasks?page=2&limit=50" => StubResponse::ok(
+                r#"{"workflow_runs": [{"id": 50, "name": "test", "head_sha": "eee000", "status": "success", "created_at": "2026-02-28T12:00:00Z"}]}"#,
+            ),
+            _ => StubResponse::not_found(),
+        });
+        let provider = GiteaCiProvider::new("gitea-token".to_string(), base_url.clone()).unwrap();
+        let ctx = |sha: &str| CiContext {
+            commit_sha: sha.to_string(),
+            ..ctx(&base_url)
+        };
+
+        // No task for the commit on the page of newer tasks nor the one reaching back past it
+        assert!(provider.confirms_no_ci(&ctx("abc123")).await.unwrap());
+        let pages = requests
+            .try_iter()
+            .filter(|request| request.path().ends_with("/actions/tasks"))
+            .count();
+        assert_eq!(pages, 2);
+
+        // External CI reported on the parent and may yet report here
+        assert!(!provider.confirms_no_ci(&ctx("def456")).await.unwrap());
+        // The commit can't be looked up
+        assert!(!provider.confirms_no_ci(&ctx("missing")).await.unwrap());
+    }
+}
@@ -1,8 +1,7 @@
 use super::provider::{CiCheck, CiContext, CiProvider};
 use crate::types::CiStatus;
+use crate::util::rate_limit;
 use anyhow::Result;
-use octocrab::models::checks::CheckRun;
-use octocrab::Octocrab;
 
 /// GitHub-specific configuration
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -17,15 +16,60 @@ fn default_github_web_base() -> String {
     "https://github.com".to_string()
 }
 
+/// The fields of a check run this provider reads
+#[derive(Debug, Clone, serde::Deserialize)]
+struct CheckRun {
+    id: u64,
+    name: String,
+    html_url: Option<String>,
+    /// Unset while the run is queued or in progress
+    conclusion: Option<String>,
+}
+
 #[derive(Clone)]
 pub struct GitHubCiProvider {
-    octocrab: Octocrab,
+    http_client: reqwest::Client,
+    api_base_url: String,
+    token: String,
 }
 
 impl GitHubCiProvider {
-    pub fn new(token: String) -> Result<Self> {
-        let octocrab = Octocrab::builder().personal_token(token).build()?;
-        Ok(Self { octocrab })
+    pub fn new(token: String, api_base_url: String) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http_client,
+            api_base_url: api_base_url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    /// GET an API route within the host's rate limit budget, returning None on 404
+    async fn get<T: serde::de::DeserializeOwned>(&self, route: &str) -> Result<Option<T>> {
+        let url = format!("{}{}", self.api_base_url, route);
+        let response = rate_limit::send(
+            self.http_client
+                .get(&url)
+                .bearer_auth(&self.token)
+                .header("Accept", "application/vnd.github+json"),
+        )
+        .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("GitHub returned {} for {}", response.status(), url);
+        }
+        Ok(Some(response.json().await?))
+    }
+
+    /// GET an API route that must exist
+    async fn get_required<T: serde::de::DeserializeOwned>(&self, route: &str) -> Result<T> {
+        self.get(route)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("GitHub returned 404 Not Found for {}", route))
     }
 
     async fn get_all_checks(&self, ctx: &CiContext) -> Result<Vec<CiCheck>> {
@@ -57,6 +101,15 @@ impl GitHubCiProvider {
             }
         }
 
+        // Half the checks would misreport the commit, so wait for the budget to recover
+        if let Some(limited) = [&statuses_err, &runs_err]
+            .into_iter()
+            .flatten()
+            .find_map(rate_limit::as_rate_limited)
+        {
+            return Err(limited.clone().into());
+        }
+
         if checks.is_empty() && statuses_err.is_some() && runs_err.is_some() {
             return Err(anyhow::anyhow!("Both status and checks API calls failed"));
         }
@@ -87,7 +140,7 @@ impl GitHubCiProvider {
             target_url: Option<String>,
         }
 
-        let combined: CombinedStatus = self.octocrab.get(route, None::<&()>).await?;
+        let combined: CombinedStatus = self.get_required(&route).await?;
 
         let mut checks = Vec::new();
         for status in combined.statuses {
@@ -95,12 +148,7 @@ impl GitHubCiProvider {
                 .context
                 .clone()
                 .unwrap_or_else(|| "unknown".to_string());
-            let state = match status.state.as_str() {
-                "success" => CiStatus::Passed,
-                "failure" | "error" => CiStatus::Failed,
-                "pending" => CiStatus::Pending,
-                _ => CiStatus::Pending,
-            };
+            let state = map_commit_status_state(&status.state);
 
             checks.push(CiCheck {
                 provider: "github".to_string(),
@@ -131,7 +179,7 @@ impl GitHubCiProvider {
             check_runs: Vec<CheckRun>,
         }
 
-        let check_runs: CheckRunsResponse = self.octocrab.get(route, None::<&()>).await?;
+        let check_runs: CheckRunsResponse = self.get_required(&route).await?;
 
         let mut checks = Vec::new();
         for run in check_runs.check_runs {
@@ -175,10 +223,120 @@ impl CiProvider for GitHubCiProvider {
             ctx.commit_sha
         ))
     }
+
+    async fn fetch_logs(&self, ctx: &CiContext, check: &CiCheck) -> Result<Option<String>> {
+        // Only GitHub Actions check runs expose logs; the check run id doubles as the job id.
+        // Commit statuses come from external systems and only link out via target_url.
+        if !check.context.starts_with("check:") {
+            return Ok(None);
+        }
+        let Some(job_id) = check.external_id.as_deref() else {
+            return Ok(None);
+        };
+
+        let cfg: GitHubCiConfig = ctx.cfg()?;
+        let url = format!(
+            "{}/repos/{}/{}/actions/jobs/{}/logs",
+            self.api_base_url, cfg.owner, cfg.repo, job_id
+        );
+
+        // The API answers with a redirect to a short-lived download URL, which reqwest follows
+        let response = rate_limit::send(
+            self.http_client
+                .get(&url)
+                .bearer_auth(&self.token)
+                .header("Accept", "application/vnd.github+json"),
+        )
+        .await?;
+
+        // Check runs created by third-party apps have no Actions job behind them
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "GitHub returned {} fetching logs for job {}",
+                response.status(),
+                job_id
+            );
+        }
+
+        Ok(Some(response.text().await?))
+    }
+
+    async fn fetch_required_checks(
+        &self,
+        ctx: &CiContext,
+        base_branch: &str,
+    ) -> Result<Option<Vec<String>>> {
+        let cfg: GitHubCiConfig = ctx.cfg()?;
+        let route = format!(
+            "/repos/{}/{}/branches/{}/protection/required_status_checks",
+            cfg.owner, cfg.repo, base_branch
+        );
+
+        #[derive(serde::Deserialize)]
+        struct RequiredStatusChecks {
+            #[serde(default)]
+            contexts: Vec<String>,
+            #[serde(default)]
+            checks: Vec<RequiredCheck>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct RequiredCheck {
+            context: String,
+        }
+
+        // 404 means the branch is unprotected (or the token can't read protection rules)
+        let Some(required) = self.get::<RequiredStatusChecks>(&route).await? else {
+            return Ok(None);
+        };
+
+        let mut names = required.contexts;
+        for check in required.checks {
+            if !names.contains(&check.context) {
+                names.push(check.context);
+            }
+        }
+
+        Ok(Some(names))
+    }
+
+    async fn confirms_no_ci(&self, ctx: &CiContext) -> Result<bool> {
+        let cfg: GitHubCiConfig = ctx.cfg()?;
+        let route = format!(
+            "/repos/{}/{}/commits/{}/check-suites",
+            cfg.owner, cfg.repo, ctx.commit_sha
+        );
+
+        #[derive(serde::Deserialize)]
+        struct CheckSuitesResponse {
+            check_suites: Vec<CheckSuite>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct CheckSuite {
+            status: Option<String>,
+        }
+
+        // Actions and other apps open a check suite as soon as the push arrives; a suite
+        // that is still queued or in progress may yet create check runs
+        let suites: CheckSuitesResponse = self.get_required(&route).await?;
+        Ok(suites
+            .check_suites
+            .iter()
+            .all(|suite| suite.status.as_deref() == Some("completed")))
+    }
 }
 
 fn map_check_run_to_state(run: &CheckRun) -> CiStatus {
-    match run.conclusion.as_deref() {
+    map_check_conclusion(run.conclusion.as_deref())
+}
+
+/// Map a check run conclusion (None while queued/in progress) to a CI status
+pub(crate) fn map_check_conclusion(conclusion: Option<&str>) -> CiStatus {
+    match conclusion {
         Some("success") | Some("neutral") => CiStatus::Passed,
         Some("skipped") | Some("stale") => CiStatus::Skipped,
         Some("failure") | Some("cancelled") | Some("timed_out") | Some("action_required") => {
@@ -188,3 +346,13 @@ fn map_check_run_to_state(run: &CheckRun) -> CiStatus {
         Some(_) => CiStatus::Pending,
     }
 }
+
+/// Map a commit status state to a CI status
+pub(crate) fn map_commit_status_state(state: &str) -> CiStatus {
+    match state {
+        "success" => CiStatus::Passed,
+        "failure" | "error" => CiStatus::Failed,
+        "pending" => CiStatus::Pending,
+        _ => CiStatus::Pending,
+    }
+}
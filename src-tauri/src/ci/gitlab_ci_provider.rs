@@ -1,9 +1,7 @@
 use super::provider::{CiCheck, CiContext, CiProvider};
 use crate::types::CiStatus;
+use crate::util::rate_limit;
 use anyhow::Result;
-use gitlab::api::projects::pipelines::Pipelines;
-use gitlab::api::AsyncQuery;
-use gitlab::{AsyncGitlab, GitlabBuilder};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -27,33 +25,60 @@ struct Pipeline {
     ref_name: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct PipelineJob {
+    id: u64,
+    name: String,
+    stage: String,
+    status: String,
+    #[serde(default)]
+    allow_failure: bool,
+}
+
 #[derive(Clone)]
 pub struct GitLabCiProvider {
-    client: AsyncGitlab,
+    http_client: reqwest::Client,
+    api_base_url: String,
+    token: String,
 }
 
 impl GitLabCiProvider {
-    pub async fn new(token: String, base_url: Option<String>) -> Result<Self> {
-        let url = base_url.unwrap_or_else(|| "https://gitlab.com".to_string());
-        // GitlabBuilder expects URL without protocol prefix
-        let url = url
-            .strip_prefix("https://")
-            .or_else(|| url.strip_prefix("http://"))
-            .unwrap_or(&url)
-            .to_string();
-        let client = GitlabBuilder::new(url, token).build_async().await?;
-        Ok(Self { client })
+    pub fn new(token: String, base_url: Option<String>) -> Result<Self> {
+        let base_url = base_url.unwrap_or_else(|| "https://gitlab.com".to_string());
+        let http_client = reqwest::Client::builder()
+            .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http_client,
+            api_base_url: format!("{}/api/v4", base_url.trim_end_matches('/')),
+            token,
+        })
+    }
+
+    /// GET an API route within the host's rate limit budget
+    async fn get(&self, route: &str) -> Result<reqwest::Response> {
+        let url = format!("{}/{}", self.api_base_url, route);
+        let response = rate_limit::send(
+            self.http_client
+                .get(&url)
+                .header("PRIVATE-TOKEN", &self.token),
+        )
+        .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("GitLab returned {} for {}", response.status(), url);
+        }
+        Ok(response)
     }
 
     async fn get_pipeline_status(&self, ctx: &CiContext) -> Result<Vec<CiCheck>> {
         let cfg: GitLabCiConfig = ctx.cfg()?;
 
-        let endpoint = Pipelines::builder()
-            .project(cfg.project_id)
-            .sha(&ctx.commit_sha)
-            .build()?;
-
-        let pipelines: Vec<Pipeline> = endpoint.query_async(&self.client).await?;
+        let route = format!(
+            "projects/{}/pipelines?sha={}",
+            encode_project_id(&cfg.project_id),
+            ctx.commit_sha
+        );
+        let pipelines: Vec<Pipeline> = self.get(&route).await?.json().await?;
 
         let checks: Vec<CiCheck> = pipelines
             .into_iter()
@@ -74,6 +99,53 @@ impl GitLabCiProvider {
 
         Ok(checks)
     }
+
+    async fn get_failed_job_traces(&self, project_id: &str, pipeline_id: u64) -> Result<String> {
+        let project = encode_project_id(project_id);
+
+        // Jobs are paged; x-next-page is empty on the last page
+        let mut jobs: Vec<PipelineJob> = Vec::new();
+        let mut page = "1".to_string();
+        loop {
+            let route = format!(
+                "projects/{}/pipelines/{}/jobs?per_page=100&page={}",
+                project, pipeline_id, page
+            );
+            let response = self.get(&route).await?;
+            let next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+            jobs.extend(response.json::<Vec<PipelineJob>>().await?);
+            match next_page {
+                Some(next) => page = next,
+                None => break,
+            }
+        }
+
+        let mut output = String::new();
+        for job in jobs
+            .into_iter()
+            .filter(|job| job.status == "failed" && !job.allow_failure)
+        {
+            let route = format!("projects/{}/jobs/{}/trace", project, job.id);
+            let trace = self.get(&route).await?.bytes().await?;
+
+            output.push_str(&format!("==> {} ({}) <==\n", job.name, job.stage));
+            output.push_str(&String::from_utf8_lossy(&trace));
+            output.push('\n');
+        }
+
+        Ok(output)
+    }
+}
+
+/// URL-encode a project path (`group/subgroup/project`) into a single path segment;
+/// numeric IDs pass through unchanged
+fn encode_project_id(project_id: &str) -> String {
+    project_id.replace('/', "%2F")
 }
 
 #[async_trait::async_trait]
@@ -106,9 +178,45 @@ impl CiProvider for GitLabCiProvider {
             ))
         }
     }
+
+    async fn fetch_logs(&self, ctx: &CiContext, check: &CiCheck) -> Result<Option<String>> {
+        let Some(pipeline_id) = check
+            .external_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+        else {
+            return Ok(None);
+        };
+
+        let cfg: GitLabCiConfig = ctx.cfg()?;
+        let traces = self
+            .get_failed_job_traces(&cfg.project_id, pipeline_id)
+            .await?;
+
+        if traces.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(traces))
+        }
+    }
+
+    async fn confirms_no_ci(&self, ctx: &CiContext) -> Result<bool> {
+        let cfg: GitLabCiConfig = ctx.cfg()?;
+        let route = format!(
+            "projects/{}/repository/commits/{}/statuses",
+            encode_project_id(&cfg.project_id),
+            ctx.commit_sha
+        );
+
+        // Pushes create pipelines synchronously, but external CI reports commit statuses
+        // whenever it gets to the commit; any status means CI is still to show up
+        let statuses: Vec<serde_json::Value> = self.get(&route).await?.json().await?;
+        Ok(statuses.is_empty())
+    }
 }
 
-fn map_gitlab_status(status: &str) -> CiStatus {
+/// Map a GitLab pipeline status to a CI status
+pub(crate) fn map_gitlab_status(status: &str) -> CiStatus {
     match status {
         "success" => CiStatus::Passed,
         "failed" => CiStatus::Failed,
@@ -118,3 +226,64 @@ fn map_gitlab_status(status: &str) -> CiStatus {
         _ => CiStatus::Pending,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::tokens::AllTokens;
+    use crate::util::test_server::{serve, StubResponse};
+
+    #[tokio::test]
+    async fn test_nested_project_path() {
+        let (base_url, requests) = serve(|_| StubResponse::ok("[]"));
+
+        let ctx = CiContext {
+            commit_sha: "abc123".to_string(),
+            branch: "maestro/abc".to_string(),
+            provider_cfg: crate::util::git::build_provider_cfg(
+                &AllTokens::default(),
+                "gitlab",
+                "gitlab.com/group/subgroup/team/project",
+            )
+            .unwrap(),
+        };
+        let cfg: GitLabCiConfig = ctx.cfg().unwrap();
+        assert_eq!(cfg.slug.as_deref(), Some("group/subgroup/team/project"));
+
+        // The full path is sent as a single URL-encoded project ID
+        let provider = GitLabCiProvider::new("token".to_string(), Some(base_url)).unwrap();
+        assert!(provider.poll(&ctx).await.unwrap().is_empty());
+        assert_eq!(
+            requests.recv().unwrap().url,
+            "/api/v4/projects/group%2Fsubgroup%2Fteam%2Fproject/pipelines?sha=abc123"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirms_no_ci_checks_commit_statuses() {
+        let (base_url, _) = serve(|request| match request.path() {
+            "/api/v4/projects/acme%2Fwidgets/repository/commits/abc123/statuses" => {
+                StubResponse::ok(r#"[{"id": 1, "name": "jenkins", "status": "running"}]"#)
+            }
+            "/api/v4/projects/acme%2Fwidgets/repository/commits/def456/statuses" => {
+                StubResponse::ok("[]")
+            }
+            _ => StubResponse::not_found(),
+        });
+        let provider = GitLabCiProvider::new("token".to_string(), Some(base_url)).unwrap();
+        let ctx = |sha: &str| CiContext {
+            commit_sha: sha.to_string(),
+            branch: "maestro/abc".to_string(),
+            provider_cfg: crate::util::git::build_provider_cfg(
+                &AllTokens::default(),
+                "gitlab",
+                "gitlab.com/acme/widgets",
+            )
+            .unwrap(),
+        };
+
+        assert!(!provider.confirms_no_ci(&ctx("abc123")).await.unwrap());
+        assert!(provider.confirms_no_ci(&ctx("def456")).await.unwrap());
+        assert!(provider.confirms_no_ci(&ctx("missing")).await.is_err());
+    }
+}
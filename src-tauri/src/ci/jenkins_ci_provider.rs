@@ -0,0 +1,340 @@
+use super::provider::{CiCheck, CiContext, CiProvider};
+use crate::types::CiStatus;
+use crate::util::rate_limit;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Jenkins-specific configuration, stored per repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JenkinsCiConfig {
+    /// Job paths relative to the Jenkins root, with folders separated by '/' (e.g. "team/widgets")
+    pub jobs: Vec<String>,
+    /// Jobs are multibranch pipelines, so builds live in a sub-job per branch
+    #[serde(default)]
+    pub multibranch: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobResponse {
+    #[serde(default)]
+    builds: Vec<Build>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Build {
+    number: u64,
+    url: String,
+    result: Option<String>,
+    #[serde(default)]
+    building: bool,
+    display_name: Option<String>,
+    #[serde(default, skip_serializing)]
+    actions: Vec<BuildAction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildAction {
+    last_built_revision: Option<Revision>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Revision {
+    #[serde(rename = "SHA1")]
+    sha1: String,
+}
+
+impl Build {
+    fn built_commit(&self, commit_sha: &str) -> bool {
+        // Pipelines that load shared libraries carry one git action per checkout
+        self.actions.iter().any(|action| {
+            action
+                .last_built_revision
+                .as_ref()
+                .is_some_and(|rev| rev.sha1.eq_ignore_ascii_case(commit_sha))
+        })
+    }
+}
+
+/// How many recent builds per job to search for the commit
+const BUILD_HISTORY_DEPTH: usize = 50;
+
+#[derive(Clone)]
+pub struct JenkinsCiProvider {
+    http_client: reqwest::Client,
+    base_url: String,
+    username: Option<String>,
+    api_token: Option<String>,
+}
+
+impl JenkinsCiProvider {
+    pub fn new(
+        base_url: String,
+        username: Option<String>,
+        api_token: Option<String>,
+    ) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http_client,
+            base_url,
+            username,
+            api_token,
+        })
+    }
+
+    /// URL of a job (or of the branch's sub-job for multibranch pipelines), with trailing slash
+    fn job_url(&self, job: &str, branch: Option<&str>) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(&self.base_url)
+            .map_err(|e| anyhow::anyhow!("Invalid Jenkins URL '{}': {}", self.base_url, e))?;
+        {
+            let mut segments = url
+                .path_segments_mut()
+                .map_err(|_| anyhow::anyhow!("Invalid Jenkins URL '{}'", self.base_url))?;
+            segments.pop_if_empty();
+            for name in job.split('/').filter(|name| !name.is_empty()) {
+                segments.push("job");
+                segments.push(name);
+            }
+            if let Some(branch) = branch {
+                segments.push("job");
+                segments.push(&encode_branch_name(branch));
+            }
+            segments.push("");
+        }
+        Ok(url)
+    }
+
+    fn branch_for(&self, cfg: &JenkinsCiConfig, ctx: &CiContext) -> Option<String> {
+        cfg.multibranch.then(|| ctx.branch.clone())
+    }
+
+    /// GET a Jenkins URL, returning None on 404 (job or branch not created yet)
+    async fn get(&self, url: reqwest::Url) -> Result<Option<reqwest::Response>> {
+        let mut request = self.http_client.get(url.clone());
+        if let Some(username) = &self.username {
+            request = request.basic_auth(username, self.api_token.as_deref());
+        }
+
+        let response = rate_limit::send(request).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("Jenkins returned {} for {}", response.status(), url);
+        }
+        Ok(Some(response))
+    }
+
+    /// Most recent build of a job that built the commit
+    async fn latest_build_for_commit(
+        &self,
+        job_url: &reqwest::Url,
+        commit_sha: &str,
+    ) -> Result<Option<Build>> {
+        let mut url = job_url.join("api/json")?;
+        url.query_pairs_mut().append_pair(
+            "tree",
+            &format!(
+                "builds[number,url,result,building,displayName,actions[lastBuiltRevision[SHA1]]]{{0,{}}}",
+                BUILD_HISTORY_DEPTH
+            ),
+        );
+
+        let Some(response) = self.get(url).await? else {
+            return Ok(None);
+        };
+        let job: JobResponse = response.json().await?;
+
+        // Jenkins lists builds newest first
+        Ok(job
+            .builds
+            .into_iter()
+            .find(|build| build.built_commit(commit_sha)))
+    }
+}
+
+#[async_trait::async_trait]
+impl CiProvider for JenkinsCiProvider {
+    fn id(&self) -> &'static str {
+        "jenkins"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Jenkins"
+    }
+
+    async fn poll(&self, ctx: &CiContext) -> Result<Vec<CiCheck>> {
+        let cfg: JenkinsCiConfig = ctx.cfg()?;
+        let branch = self.branch_for(&cfg, ctx);
+
+        let mut checks = Vec::new();
+        for job in &cfg.jobs {
+            let job_url = self.job_url(job, branch.as_deref())?;
+            let Some(build) = self
+                .latest_build_for_commit(&job_url, &ctx.commit_sha)
+                .await?
+            else {
+                continue;
+            };
+
+            checks.push(CiCheck {
+                provider: "jenkins".to_string(),
+                context: format!("jenkins:{}", job),
+                name: job.clone(),
+                state: map_build_result(build.building, build.result.as_deref()),
+                target_url: Some(format!("{}console", build.url)),
+                description: build.display_name.clone(),
+                external_id: Some(build.number.to_string()),
+                raw_json: serde_json::to_value(&build).ok(),
+            });
+        }
+
+        Ok(checks)
+    }
+
+    fn get_commit_url(&self, ctx: &CiContext) -> Result<String> {
+        // Jenkins has no per-commit page; link to the (branch) job instead
+        let cfg: JenkinsCiConfig = ctx.cfg()?;
+        let job = cfg
+            .jobs
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("No Jenkins jobs configured"))?;
+        Ok(self
+            .job_url(job, self.branch_for(&cfg, ctx).as_deref())?
+            .to_string())
+    }
+
+    async fn fetch_logs(&self, ctx: &CiContext, check: &CiCheck) -> Result<Option<String>> {
+        let (Some(job), Some(number)) = (
+            check.context.strip_prefix("jenkins:"),
+            check.external_id.as_deref(),
+        ) else {
+            return Ok(None);
+        };
+
+        let cfg: JenkinsCiConfig = ctx.cfg()?;
+        let url = self
+            .job_url(job, self.branch_for(&cfg, ctx).as_deref())?
+            .join(&format!("{}/consoleText", number))?;
+
+        match self.get(url).await? {
+            Some(response) => Ok(Some(response.text().await?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Encode a branch name the way multibranch pipelines name their branch jobs
+fn encode_branch_name(branch: &str) -> String {
+    branch.replace('%', "%25").replace('/', "%2F")
+}
+
+/// Map a Jenkins build result (None while running) to a CI status
+fn map_build_result(building: bool, result: Option<&str>) -> CiStatus {
+    if building {
+        return CiStatus::Pending;
+    }
+    match result {
+        Some("SUCCESS") => CiStatus::Passed,
+        Some("FAILURE") | Some("UNSTABLE") | Some("ABORTED") => CiStatus::Failed,
+        Some("NOT_BUILT") => CiStatus::Skipped,
+        _ => CiStatus::Pending,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{serve_routes, StubRequest};
+    use std::collections::HashMap;
+
+    fn ctx(cfg: serde_json::Value) -> CiContext {
+        CiContext {
+            commit_sha: "abc123".to_string(),
+            branch: "maestro/abc/def".to_string(),
+            provider_cfg: cfg,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_finds_build_for_commit() {
+        let (base_url, requests) = serve_routes(HashMap::from([
+            (
+                "/jenkins/job/team/job/widgets/api/json",
+                r##"{"builds": [
+                    {"number": 8, "url": "http://ci/job/team/job/widgets/8/", "building": false, "result": "SUCCESS",
+                     "actions": [{"lastBuiltRevision": {"SHA1": "fff999"}}]},
+                    {"number": 7, "url": "http://ci/job/team/job/widgets/7/", "building": false, "result": "UNSTABLE",
+                     "displayName": "#7", "actions": [{}, {"lastBuiltRevision": {"SHA1": "abc123"}}]}
+                ]}"##,
+            ),
+            (
+                "/jenkins/job/team/job/widgets/7/consoleText",
+                "Tests failed: 3",
+            ),
+        ]));
+
+        let provider = JenkinsCiProvider::new(
+            format!("{}/jenkins", base_url),
+            Some("maestro".to_string()),
+            Some("api-token".to_string()),
+        )
+        .unwrap();
+        let ctx = ctx(serde_json::json!({ "jobs": ["team/widgets", "team/missing"] }));
+
+        let checks = provider.poll(&ctx).await.unwrap();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].context, "jenkins:team/widgets");
+        assert_eq!(checks[0].state, CiStatus::Failed);
+        assert_eq!(checks[0].external_id.as_deref(), Some("7"));
+        assert_eq!(
+            checks[0].target_url.as_deref(),
+            Some("http://ci/job/team/job/widgets/7/console")
+        );
+
+        let logs = provider.fetch_logs(&ctx, &checks[0]).await.unwrap();
+        assert_eq!(logs.as_deref(), Some("Tests failed: 3"));
+
+        // Basic auth with the API token is sent on every request
+        let requests: Vec<StubRequest> = requests.try_iter().collect();
+        assert!(requests.iter().all(|r| r
+            .header("Authorization")
+            .is_some_and(|auth| auth.starts_with("Basic "))));
+    }
+
+    #[tokio::test]
+    async fn test_poll_multibranch_job() {
+        let (base_url, _) = serve_routes(HashMap::from([(
+            "/jenkins/job/widgets/job/maestro%252Fabc%252Fdef/api/json",
+            r#"{"builds": [
+                {"number": 1, "url": "http://ci/job/widgets/job/maestro%252Fabc%252Fdef/1/", "building": true,
+                 "actions": [{"lastBuiltRevision": {"SHA1": "abc123"}}]}
+            ]}"#,
+        )]));
+
+        let provider = JenkinsCiProvider::new(format!("{}/jenkins", base_url), None, None).unwrap();
+        let ctx = ctx(serde_json::json!({ "jobs": ["widgets"], "multibranch": true }));
+
+        let checks = provider.poll(&ctx).await.unwrap();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].state, CiStatus::Pending);
+        assert!(provider
+            .get_commit_url(&ctx)
+            .unwrap()
+            .ends_with("/jenkins/job/widgets/job/maestro%252Fabc%252Fdef/"));
+    }
+
+    #[test]
+    fn test_map_build_result() {
+        assert_eq!(map_build_result(true, None), CiStatus::Pending);
+        assert_eq!(map_build_result(false, Some("SUCCESS")), CiStatus::Passed);
+        assert_eq!(map_build_result(false, Some("ABORTED")), CiStatus::Failed);
+        assert_eq!(
+            map_build_result(false, Some("NOT_BUILT")),
+            CiStatus::Skipped
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use super::provider::{CiCheck, CiContext, CiProvider};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Maximum number of trailing lines kept from a CI log
+pub const MAX_LOG_LINES: usize = 500;
+
+/// Maximum number of trailing bytes kept from a CI log
+pub const MAX_LOG_BYTES: usize = 64 * 1024;
+
+/// Trimmed CI log for a single check, cached on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiLog {
+    pub provider: String,
+    pub context: String,
+    pub name: String,
+    pub target_url: Option<String>,
+    pub content: String,
+    pub truncated: bool,
+    pub path: String,
+    pub fetched_at: i64,
+}
+
+/// Remove ANSI escape sequences (colors, GitLab section markers) from log output
+pub fn strip_ansi(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            if chars.peek() == Some(&'[') {
+                chars.next();
+                // CSI sequence: parameters until a final byte in '@'..='~'
+                for next in chars.by_ref() {
+                    if ('@'..='~').contains(&next) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        output.push(c);
+    }
+
+    output
+}
+
+/// Keep only the tail of a log, bounded by line count and byte size
+/// Returns the trimmed log and whether anything was cut off
+pub fn tail_log(raw: &str, max_lines: usize, max_bytes: usize) -> (String, bool) {
+    let cleaned = strip_ansi(&raw.replace("\r\n", "\n"));
+    let lines: Vec<&str> = cleaned.trim_end().lines().map(|l| l.trim_end()).collect();
+
+    let mut truncated = lines.len() > max_lines;
+    let start = lines.len().saturating_sub(max_lines);
+    let mut tail = lines[start..].join("\n");
+
+    if tail.len() > max_bytes {
+        truncated = true;
+        let mut cut = tail.len() - max_bytes;
+        while !tail.is_char_boundary(cut) {
+            cut += 1;
+        }
+        // Drop the partial first line left behind by the byte cut
+        let rest = &tail[cut..];
+        tail = match rest.find('\n') {
+            Some(idx) => rest[idx + 1..].to_string(),
+            None => rest.to_string(),
+        };
+    }
+
+    (tail, truncated)
+}
+
+/// First line of a cached log file; the log itself follows
+const CACHE_HEADER: &str = "maestro-ci-log truncated=";
+
+/// Write a trimmed log to the cache, recording in its header whether it was truncated
+fn write_cached_log(path: &Path, content: &str, truncated: bool) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, format!("{}{}\n{}", CACHE_HEADER, truncated, content))?;
+    Ok(())
+}
+
+/// A cached log, whether it was truncated and when it was fetched (ms since epoch)
+fn read_cached_log(path: &Path) -> Result<(String, bool, i64)> {
+    let cached = std::fs::read_to_string(path)?;
+    let (header, content) = cached.split_once('\n').unwrap_or((cached.as_str(), ""));
+    let truncated = match header.strip_prefix(CACHE_HEADER) {
+        Some(flag) => flag == "true",
+        None => anyhow::bail!("Cached CI log {} has no header", path.display()),
+    };
+    let fetched_at = std::fs::metadata(path)?
+        .modified()
+        .ok()
+        .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp_millis())
+        .unwrap_or_default();
+    Ok((content.to_string(), truncated, fetched_at))
+}
+
+/// Return the cached log for a check, or fetch it from the provider and cache it
+pub async fn fetch_and_cache_logs(
+    provider: &dyn CiProvider,
+    ctx: &CiContext,
+    check: &CiCheck,
+    path: &Path,
+    refresh: bool,
+) -> Result<Option<CiLog>> {
+    if !refresh && path.exists() {
+        let (content, truncated, fetched_at) = read_cached_log(path)?;
+
+        return Ok(Some(CiLog {
+            provider: check.provider.clone(),
+            context: check.context.clone(),
+            name: check.name.clone(),
+            target_url: check.target_url.clone(),
+            truncated,
+            content,
+            path: path.to_string_lossy().to_string(),
+            fetched_at,
+        }));
+    }
+
+    let Some(raw) = provider.fetch_logs(ctx, check).await? else {
+        return Ok(None);
+    };
+
+    let (content, truncated) = tail_log(&raw, MAX_LOG_LINES, MAX_LOG_BYTES);
+
+    write_cached_log(path, &content, truncated)?;
+
+    Ok(Some(CiLog {
+        provider: check.provider.clone(),
+        context: check.context.clone(),
+        name: check.name.clone(),
+        target_url: check.target_url.clone(),
+        content,
+        truncated,
+        path: path.to_string_lossy().to_string(),
+        fetched_at: chrono::Utc::now().timestamp_millis(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        let raw = "\u{1b}[0Ksection_start:123:build\r\u{1b}[0K\u{1b}[32;1mRunning\u{1b}[0;m";
+        assert_eq!(strip_ansi(raw), "section_start:123:build\rRunning");
+    }
+
+    #[test]
+    fn test_tail_log_keeps_short_logs() {
+        let (tail, truncated) = tail_log("one\ntwo\n\n", 10, 1024);
+        assert_eq!(tail, "one\ntwo");
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn test_tail_log_limits_lines() {
+        let raw = (1..=10)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (tail, truncated) = tail_log(&raw, 3, 1024);
+        assert_eq!(tail, "8\n9\n10");
+        assert!(truncated);
+    }
+
+    #[test]
+    fn test_tail_log_limits_bytes_on_line_boundary() {
+        let raw = "first line\nsecond line\nthird";
+        let (tail, truncated) = tail_log(raw, 100, 12);
+        assert_eq!(tail, "third");
+        assert!(truncated);
+    }
+
+    #[test]
+    fn test_cached_log_keeps_truncated_flag() {
+        let dir = std::env::temp_dir().join(format!("maestro-ci-logs-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("sha").join("build.log");
+
+        write_cached_log(&path, "tail", true).unwrap();
+        let (content, truncated, _) = read_cached_log(&path).unwrap();
+        assert_eq!(content, "tail");
+        assert!(truncated);
+
+        // A refresh that fits clears the flag
+        write_cached_log(&path, "whole log", false).unwrap();
+        let (content, truncated, _) = read_cached_log(&path).unwrap();
+        assert_eq!(content, "whole log");
+        assert!(!truncated);
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,9 +1,28 @@
+mod bitbucket_ci_provider;
+mod command_ci_provider;
+mod gitea_ci_provider;
 mod github_ci_provider;
 mod gitlab_ci_provider;
+mod jenkins_ci_provider;
+mod logs;
+mod policy;
 pub(crate) mod provider;
 mod status_checker;
+mod watcher;
 
+pub use bitbucket_ci_provider::BitbucketCiProvider;
+pub use command_ci_provider::{CommandCiConfig, CommandCiProvider};
+pub use gitea_ci_provider::GiteaCiProvider;
 pub use github_ci_provider::GitHubCiProvider;
+pub(crate) use github_ci_provider::{map_check_conclusion, map_commit_status_state};
+pub(crate) use gitlab_ci_provider::map_gitlab_status;
 pub use gitlab_ci_provider::GitLabCiProvider;
+pub use jenkins_ci_provider::{JenkinsCiConfig, JenkinsCiProvider};
+pub use logs::{fetch_and_cache_logs, CiLog};
+pub use policy::CiCheckPolicy;
 pub use provider::{CiCheck, CiContext, CiProvider};
-pub use status_checker::{check_ci_once, poll_ci_until_terminal};
+pub use status_checker::{check_ci_once, record_checks};
+pub use watcher::{
+    parse_poll_schedule, rewatch_execution, start_ci_watcher, watch_execution,
+    DEFAULT_POLL_SCHEDULE,
+};
@@ -0,0 +1,230 @@
+use super::provider::CiCheck;
+use crate::types::CiStatus;
+use anyhow::Result;
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+/// Which CI checks gate an execution
+/// Patterns are globs matched against both `CiCheck.context` and `CiCheck.name`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiCheckPolicy {
+    /// When non-empty, only checks matching these patterns affect the overall status,
+    /// and a pattern with no matching check keeps the status pending
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Checks matching these patterns never affect the overall status, even when they also
+    /// match a required pattern
+    #[serde(default)]
+    pub ignored: Vec<String>,
+    /// Add the provider's branch protection required checks to `required`
+    #[serde(default)]
+    pub discover_required: bool,
+}
+
+impl CiCheckPolicy {
+    /// Ensure every pattern is a valid glob
+    pub fn validate(&self) -> Result<()> {
+        for pattern in self.required.iter().chain(self.ignored.iter()) {
+            Pattern::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid check pattern '{}': {}", pattern, e))?;
+        }
+        Ok(())
+    }
+
+    /// Add exact check names (e.g. discovered from branch protection) to the required list
+    pub fn require_exact<I: IntoIterator<Item = String>>(&mut self, names: I) {
+        for name in names {
+            let pattern = Pattern::escape(&name);
+            if !self.required.contains(&pattern) {
+                self.required.push(pattern);
+            }
+        }
+    }
+
+    /// Aggregate checks into an overall status (None when the provider reported no checks)
+    pub fn aggregate(&self, checks: &[CiCheck]) -> Option<CiStatus> {
+        if checks.is_empty() {
+            return None;
+        }
+
+        let required = compile(&self.required);
+        let ignored = compile(&self.ignored);
+
+        let mut has_pending = false;
+        let mut has_failed = false;
+        let mut has_passed = false;
+
+        let mut tally = |state: CiStatus| match state {
+            CiStatus::Pending => has_pending = true,
+            CiStatus::Failed => has_failed = true,
+            CiStatus::Passed => has_passed = true,
+            CiStatus::Skipped => {} // Skipped doesn't affect overall status
+            CiStatus::NotConfigured => {} // Not configured doesn't affect overall status
+            CiStatus::NotPushed => {} // Not pushed doesn't affect overall status
+        };
+
+        if required.is_empty() {
+            for check in checks.iter().filter(|c| !matches_any(&ignored, c)) {
+                tally(check.state);
+            }
+        } else {
+            for pattern in &required {
+                let matching: Vec<&CiCheck> =
+                    checks.iter().filter(|c| matches(pattern, c)).collect();
+
+                if matching.is_empty() {
+                    // Required check hasn't been reported yet
+                    tally(CiStatus::Pending);
+                }
+                for check in matching.into_iter().filter(|c| !matches_any(&ignored, c)) {
+                    tally(check.state);
+                }
+            }
+        }
+
+        let overall_status = if has_failed {
+            CiStatus::Failed
+        } else if has_pending {
+            CiStatus::Pending
+        } else if has_passed {
+            CiStatus::Passed
+        } else {
+            CiStatus::Skipped
+        };
+
+        Some(overall_status)
+    }
+}
+
+fn compile(patterns: &[String]) -> Vec<Pattern> {
+    patterns
+        .iter()
+        .filter_map(|p| match Pattern::new(p) {
+            Ok(pattern) => Some(pattern),
+            Err(e) => {
+                log::warn!("Ignoring invalid check pattern '{}': {}", p, e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn matches(pattern: &Pattern, check: &CiCheck) -> bool {
+    pattern.matches(&check.context) || pattern.matches(&check.name)
+}
+
+fn matches_any(patterns: &[Pattern], check: &CiCheck) -> bool {
+    patterns.iter().any(|p| matches(p, check))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, state: CiStatus) -> CiCheck {
+        CiCheck {
+            provider: "github".to_string(),
+            context: format!("check:{}", name),
+            name: name.to_string(),
+            state,
+            target_url: None,
+            description: None,
+            external_id: None,
+            raw_json: None,
+        }
+    }
+
+    #[test]
+    fn test_default_policy_fails_on_any_failure() {
+        let checks = vec![
+            check("build", CiStatus::Passed),
+            check("lint", CiStatus::Failed),
+        ];
+        assert_eq!(
+            CiCheckPolicy::default().aggregate(&checks),
+            Some(CiStatus::Failed)
+        );
+        assert_eq!(CiCheckPolicy::default().aggregate(&[]), None);
+    }
+
+    #[test]
+    fn test_ignored_checks_do_not_block() {
+        let policy = CiCheckPolicy {
+            ignored: vec!["deploy-preview*".to_string(), "check:flaky-*".to_string()],
+            ..Default::default()
+        };
+        let checks = vec![
+            check("build", CiStatus::Passed),
+            check("deploy-preview/netlify", CiStatus::Pending),
+            check("flaky-e2e", CiStatus::Failed),
+        ];
+        assert_eq!(policy.aggregate(&checks), Some(CiStatus::Passed));
+    }
+
+    #[test]
+    fn test_required_checks_gate_status() {
+        let policy = CiCheckPolicy {
+            required: vec!["build".to_string(), "test-*".to_string()],
+            ..Default::default()
+        };
+
+        // Optional failures are ignored once a required list exists
+        let checks = vec![
+            check("build", CiStatus::Passed),
+            check("test-unit", CiStatus::Passed),
+            check("optional-lint", CiStatus::Failed),
+        ];
+        assert_eq!(policy.aggregate(&checks), Some(CiStatus::Passed));
+
+        // Missing required checks keep the status pending
+        let checks = vec![check("build", CiStatus::Passed)];
+        assert_eq!(policy.aggregate(&checks), Some(CiStatus::Pending));
+
+        let checks = vec![
+            check("build", CiStatus::Passed),
+            check("test-integration", CiStatus::Failed),
+        ];
+        assert_eq!(policy.aggregate(&checks), Some(CiStatus::Failed));
+    }
+
+    #[test]
+    fn test_ignored_checks_apply_to_required_ones() {
+        let policy = CiCheckPolicy {
+            required: vec!["test-*".to_string()],
+            ignored: vec!["test-flaky".to_string()],
+            ..Default::default()
+        };
+        let checks = vec![
+            check("test-unit", CiStatus::Passed),
+            check("test-flaky", CiStatus::Failed),
+        ];
+        assert_eq!(policy.aggregate(&checks), Some(CiStatus::Passed));
+
+        // A required pattern whose only check is ignored doesn't wait for another one
+        let checks = vec![check("test-flaky", CiStatus::Pending)];
+        assert_eq!(policy.aggregate(&checks), Some(CiStatus::Skipped));
+    }
+
+    #[test]
+    fn test_require_exact_escapes_names() {
+        let mut policy = CiCheckPolicy::default();
+        policy.require_exact(vec!["ci/[build]".to_string(), "ci/[build]".to_string()]);
+        assert_eq!(policy.required.len(), 1);
+
+        let checks = vec![CiCheck {
+            context: "status:ci/[build]".to_string(),
+            ..check("ci/[build]", CiStatus::Passed)
+        }];
+        assert_eq!(policy.aggregate(&checks), Some(CiStatus::Passed));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_patterns() {
+        let policy = CiCheckPolicy {
+            required: vec!["build[".to_string()],
+            ..Default::default()
+        };
+        assert!(policy.validate().is_err());
+    }
+}
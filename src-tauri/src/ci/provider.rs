@@ -1,9 +1,10 @@
+use crate::db::store::{Repository, Store};
 use crate::types::CiStatus;
 use anyhow::Result;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Context passed to CI providers for polling
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize)]
 pub struct CiContext {
     pub commit_sha: String,
     pub branch: String,
@@ -54,32 +55,161 @@ pub trait CiProvider: Send + Sync {
 
     /// Get URL for viewing commit CI status
     fn get_commit_url(&self, ctx: &CiContext) -> Result<String>;
+
+    /// Fetch raw log output for a check
+    /// Returns None when the provider does not expose logs for this kind of check
+    async fn fetch_logs(&self, _ctx: &CiContext, _check: &CiCheck) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Fetch the check names required by branch protection on the given base branch
+    /// Returns None when the provider has no such concept or the branch is unprotected
+    async fn fetch_required_checks(
+        &self,
+        _ctx: &CiContext,
+        _base_branch: &str,
+    ) -> Result<Option<Vec<String>>> {
+        Ok(None)
+    }
+
+    /// Whether the provider can confirm that no CI will ever report for this commit
+    /// Only consulted after polling has found no checks for the grace period; returning
+    /// false keeps the execution pending and the watcher polling
+    async fn confirms_no_ci(&self, _ctx: &CiContext) -> Result<bool> {
+        Ok(false)
+    }
 }
 
 /// Factory function to create a CI provider
-pub async fn create_ci_provider(provider: &str, _provider_id: &str) -> Result<Arc<dyn CiProvider>> {
-    use crate::ci::{GitHubCiProvider, GitLabCiProvider};
-    use crate::commands::tokens::get_token_value;
+/// Git host CI uses the credential profile of the repository's host, falling back to the
+/// provider's tokens
+pub async fn create_ci_provider(provider: &str, provider_id: &str) -> Result<Arc<dyn CiProvider>> {
+    use crate::ci::{
+        BitbucketCiProvider, CommandCiProvider, GitHubCiProvider, GitLabCiProvider,
+        GiteaCiProvider, JenkinsCiProvider,
+    };
+    use crate::commands::tokens::{cached_tokens, get_token_value};
+    use crate::util::git::{provider_api_base_url, resolve_provider_token};
 
+    let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
     match provider {
         "github" => {
-            let token = get_token_value("github_token")
-                .map_err(|e| anyhow::anyhow!("Failed to access GitHub token: {}", e))?
-                .ok_or_else(|| anyhow::anyhow!("GitHub token not configured"))?;
+            let token = resolve_provider_token(&tokens, provider, provider_id).await?;
+            let base_url = provider_api_base_url(&tokens, provider, provider_id)?;
 
-            let provider = GitHubCiProvider::new(token)?;
+            let provider = GitHubCiProvider::new(token, base_url)?;
             Ok(Arc::new(provider))
         }
         "gitlab" => {
-            let token = get_token_value("gitlab_token")
-                .map_err(|e| anyhow::anyhow!("Failed to access GitLab token: {}", e))?
-                .ok_or_else(|| anyhow::anyhow!("GitLab token not configured"))?;
+            let token = resolve_provider_token(&tokens, provider, provider_id).await?;
+            let base_url = provider_api_base_url(&tokens, provider, provider_id)?;
+
+            let provider = GitLabCiProvider::new(token, Some(base_url))?;
+            Ok(Arc::new(provider))
+        }
+        "gitea" => {
+            let token = resolve_provider_token(&tokens, provider, provider_id).await?;
+            let base_url = provider_api_base_url(&tokens, provider, provider_id)?;
+
+            let provider = GiteaCiProvider::new(token, base_url)?;
+            Ok(Arc::new(provider))
+        }
+        "bitbucket" => {
+            let token = resolve_provider_token(&tokens, provider, provider_id).await?;
+            let base_url = provider_api_base_url(&tokens, provider, provider_id)?;
+
+            let provider = BitbucketCiProvider::new(token, base_url)?;
+            Ok(Arc::new(provider))
+        }
+        "jenkins" => {
+            let base_url = get_token_value("jenkins_url")
+                .map_err(|e| anyhow::anyhow!("Failed to access Jenkins URL: {}", e))?
+                .ok_or_else(|| anyhow::anyhow!("Jenkins URL not configured"))?;
 
-            let base_url = get_token_value("gitlab_instance_url").ok().flatten();
+            let username = get_token_value("jenkins_username").ok().flatten();
+            let api_token = get_token_value("jenkins_api_token").ok().flatten();
 
-            let provider = GitLabCiProvider::new(token, base_url).await?;
+            let provider = JenkinsCiProvider::new(base_url, username, api_token)?;
             Ok(Arc::new(provider))
         }
+        "command" => Ok(Arc::new(CommandCiProvider::new())),
         _ => Err(anyhow::anyhow!("Unsupported CI provider: {}", provider)),
     }
 }
+
+/// Git providers with no CI of their own (plain git remotes)
+/// Their repositories report CI as not configured unless a CI override is attached
+pub const PROVIDERS_WITHOUT_CI: &[&str] = &["git"];
+
+/// Whether a repository has CI to poll: its git host's own, or an attached override
+pub fn repository_has_ci(repository: &Repository, has_ci_override: bool) -> bool {
+    has_ci_override || !PROVIDERS_WITHOUT_CI.contains(&repository.provider.as_str())
+}
+
+/// CI providers a repository can be pointed at instead of its git host's CI
+pub const CI_PROVIDER_OVERRIDES: &[&str] = &["jenkins", "command"];
+
+/// Check that a repository CI override names a known provider with a usable config
+pub fn validate_ci_config(ci_provider: &str, config: &serde_json::Value) -> Result<()> {
+    match ci_provider {
+        "jenkins" => {
+            let cfg: crate::ci::JenkinsCiConfig = serde_json::from_value(config.clone())
+                .map_err(|e| anyhow::anyhow!("Invalid Jenkins config: {}", e))?;
+            if cfg.jobs.is_empty() {
+                anyhow::bail!("Jenkins config must list at least one job");
+            }
+            Ok(())
+        }
+        "command" => {
+            let cfg: crate::ci::CommandCiConfig = serde_json::from_value(config.clone())
+                .map_err(|e| anyhow::anyhow!("Invalid command config: {}", e))?;
+            if cfg.command.trim().is_empty() {
+                anyhow::bail!("Command config must name an executable");
+            }
+            if cfg.timeout_secs == 0 {
+                anyhow::bail!("Command timeout must be at least 1 second");
+            }
+            Ok(())
+        }
+        _ => Err(anyhow::anyhow!(
+            "Unsupported CI provider override: {} (expected one of: {})",
+            ci_provider,
+            CI_PROVIDER_OVERRIDES.join(", ")
+        )),
+    }
+}
+
+/// Create the CI provider for a repository along with the provider_cfg for its CiContext
+/// A repository CI override (e.g. Jenkins) takes precedence over the git host's own CI
+pub async fn create_repository_ci_provider(
+    store: &Mutex<Store>,
+    repository: &Repository,
+) -> Result<(Arc<dyn CiProvider>, serde_json::Value)> {
+    let ci_config = store
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock store: {}", e))?
+        .get_repository_ci_config(&repository.id)?;
+
+    match ci_config {
+        Some(ci_config) => {
+            let provider =
+                create_ci_provider(&ci_config.ci_provider, &repository.provider_id).await?;
+            Ok((provider, ci_config.config))
+        }
+        None if !repository_has_ci(repository, false) => Err(anyhow::anyhow!(
+            "Repository {} has no CI; attach a CI provider to track builds",
+            repository.provider_id
+        )),
+        None => {
+            let provider =
+                create_ci_provider(&repository.provider, &repository.provider_id).await?;
+            let tokens = crate::commands::tokens::cached_tokens().map_err(anyhow::Error::msg)?;
+            let provider_cfg = crate::util::git::build_provider_cfg(
+                &tokens,
+                &repository.provider,
+                &repository.provider_id,
+            )?;
+            Ok((provider, provider_cfg))
+        }
+    }
+}
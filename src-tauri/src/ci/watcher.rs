@@ -0,0 +1,320 @@
+use anyhow::Result;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::task::JoinSet;
+use tokio::time::{sleep, Duration};
+
+use crate::ci::{check_ci_once, record_checks, CiContext};
+use crate::commands::executor_events;
+use crate::db::store::{CiWatch, ExecutionUpdates, Store};
+use crate::types::CiStatus;
+use crate::util::rate_limit;
+
+/// Seconds between polls; the last interval repeats until CI finishes
+pub const DEFAULT_POLL_SCHEDULE: &[u64] = &[10, 20, 40, 80, 120, 300];
+
+/// How often the watcher looks for due watches
+const TICK_SECS: u64 = 5;
+
+/// Due watches polled at once, so one slow provider doesn't hold up the others
+const MAX_CONCURRENT_POLLS: usize = 4;
+
+lazy_static::lazy_static! {
+    static ref WATCHER_ACTIVE: Mutex<bool> = Mutex::new(false);
+}
+
+/// Parse a comma-separated list of poll intervals in seconds (e.g. "10,30,60,300")
+pub fn parse_poll_schedule(value: &str) -> Result<Vec<u64>> {
+    let schedule = value
+        .split(',')
+        .map(|part| {
+            let secs = part
+                .trim()
+                .parse::<u64>()
+                .map_err(|e| anyhow::anyhow!("Invalid poll interval '{}': {}", part.trim(), e))?;
+            if secs == 0 {
+                anyhow::bail!("Poll intervals must be at least 1 second");
+            }
+            Ok(secs)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if schedule.is_empty() {
+        anyhow::bail!("Poll schedule must contain at least one interval");
+    }
+    Ok(schedule)
+}
+
+/// Delay after a poll, given how many polls had completed before it
+fn next_poll_delay(schedule: &[u64], attempts: i64) -> u64 {
+    let index = (attempts.max(0) as usize).min(schedule.len().saturating_sub(1));
+    schedule
+        .get(index)
+        .copied()
+        .unwrap_or(DEFAULT_POLL_SCHEDULE[0])
+}
+
+/// Queue an execution's commit for CI polling, due immediately
+pub fn watch_execution(store: &Store, execution_id: &str, commit_sha: &str) -> Result<()> {
+    store.watch_ci(
+        execution_id,
+        commit_sha,
+        chrono::Utc::now().timestamp_millis(),
+    )
+}
+
+/// Queue an execution's commit for CI polling from scratch, including one whose watch expired
+pub fn rewatch_execution(store: &Store, execution_id: &str, commit_sha: &str) -> Result<()> {
+    store.revive_ci_watch(
+        execution_id,
+        commit_sha,
+        chrono::Utc::now().timestamp_millis(),
+    )
+}
+
+/// Start the background CI watcher (no-op if it is already running)
+/// Watches are stored in the database, so anything left pending by a previous run resumes
+pub fn start_ci_watcher(app: AppHandle) {
+    {
+        let mut active = WATCHER_ACTIVE.lock().unwrap();
+        if *active {
+            return;
+        }
+        *active = true;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        log::info!("[ci_watcher] Started");
+        loop {
+            let due = {
+                let store = app.state::<Mutex<Store>>();
+                let store = store.lock().unwrap();
+                store.get_due_ci_watches(chrono::Utc::now().timestamp_millis())
+            };
+
+            match due {
+                Ok(watches) => {
+                    let mut polls = JoinSet::new();
+                    for watch in watches {
+                        if polls.len() >= MAX_CONCURRENT_POLLS {
+                            polls.join_next().await;
+                        }
+                        let app = app.clone();
+                        polls.spawn(async move { process_watch(&app, &watch).await });
+                    }
+                    while polls.join_next().await.is_some() {}
+                }
+                Err(e) => log::error!("[ci_watcher] Failed to load CI watches: {}", e),
+            }
+
+            sleep(Duration::from_secs(TICK_SECS)).await;
+        }
+    });
+}
+
+enum WatchOutcome {
+    /// CI reached a terminal state (or the watch no longer applies)
+    Finished,
+    /// CI is still running or hasn't reported yet
+    Pending,
+}
+
+async fn process_watch(app: &AppHandle, watch: &CiWatch) {
+    log::info!(
+        "[ci_watcher] Polling CI for execution {} (attempt {})",
+        watch.execution_id,
+        watch.attempts + 1
+    );
+
+    // Polls leave the hosts' reserve to the user's own requests
+    let outcome = rate_limit::send_background(poll_watch(app, watch)).await;
+
+    let store = app.state::<Mutex<Store>>();
+    let store = store.lock().unwrap();
+    let result = match outcome {
+        Ok(WatchOutcome::Finished) => store.delete_ci_watch(&watch.execution_id).map(|_| ()),
+        Ok(WatchOutcome::Pending) => reschedule(&store, watch, None),
+        // A spent API budget isn't the watch's fault: wait for it without using up attempts
+        Err(e) => match rate_limit::as_rate_limited(&e) {
+            Some(limited) => {
+                log::info!(
+                    "[ci_watcher] Deferring CI check for execution {}: {}",
+                    watch.execution_id,
+                    limited
+                );
+                store.defer_ci_watch(
+                    &watch.execution_id,
+                    limited.retry_at,
+                    Some(&limited.to_string()),
+                )
+            }
+            None => {
+                log::warn!(
+                    "[ci_watcher] Error checking CI for execution {}: {:#}",
+                    watch.execution_id,
+                    e
+                );
+                reschedule(&store, watch, Some(&format!("{:#}", e)))
+            }
+        },
+    };
+
+    if let Err(e) = result {
+        log::error!(
+            "[ci_watcher] Failed to update CI watch for execution {}: {}",
+            watch.execution_id,
+            e
+        );
+    }
+}
+
+/// Schedule the next poll, or give up once the watch has outlived ci_watch_max_hours
+/// Giving up leaves the execution pending; it is never marked skipped for taking too long.
+/// The watch is kept, expired, until reconcile_stuck_ci revives it
+fn reschedule(store: &Store, watch: &CiWatch, error: Option<&str>) -> Result<()> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let max_hours = store.get_ci_watch_max_hours().unwrap_or(24);
+
+    if now - watch.created_at > max_hours * 60 * 60 * 1000 {
+        log::warn!(
+            "[ci_watcher] Giving up on CI for execution {} after {} hours, leaving it pending",
+            watch.execution_id,
+            max_hours
+        );
+        store.expire_ci_watch(&watch.execution_id)?;
+        return Ok(());
+    }
+
+    let schedule = store.get_ci_poll_schedule().unwrap_or_else(|e| {
+        log::warn!("[ci_watcher] {}, using default schedule", e);
+        DEFAULT_POLL_SCHEDULE.to_vec()
+    });
+    let delay_secs = next_poll_delay(&schedule, watch.attempts);
+
+    store.reschedule_ci_watch(&watch.execution_id, now + (delay_secs as i64) * 1000, error)
+}
+
+async fn poll_watch(app: &AppHandle, watch: &CiWatch) -> Result<WatchOutcome> {
+    let (execution, repository, grace_minutes) = {
+        let store = app.state::<Mutex<Store>>();
+        let store = store.lock().unwrap();
+        let Some(execution) = store.get_execution(&watch.execution_id)? else {
+            return Ok(WatchOutcome::Finished);
+        };
+        let Some(repository) = store.get_repository(&execution.repository_id)? else {
+            return Ok(WatchOutcome::Finished);
+        };
+        let grace_minutes = store.get_ci_stuck_threshold_minutes().unwrap_or(10);
+        (execution, repository, grace_minutes)
+    };
+
+    // A newer commit or a status set elsewhere (webhook, manual refresh) supersedes this watch
+    if execution.commit_sha.as_deref() != Some(watch.commit_sha.as_str())
+        || execution.ci_status != Some(CiStatus::Pending)
+    {
+        return Ok(WatchOutcome::Finished);
+    }
+
+    let (provider, provider_cfg) = crate::ci::provider::create_repository_ci_provider(
+        &app.state::<Mutex<Store>>(),
+        &repository,
+    )
+    .await?;
+
+    let ctx = CiContext {
+        commit_sha: watch.commit_sha.clone(),
+        branch: execution
+            .branch
+            .clone()
+            .unwrap_or_else(|| "main".to_string()),
+        provider_cfg,
+    };
+
+    let policy = crate::commands::ci::resolve_check_policy(
+        &app.state::<Mutex<Store>>(),
+        provider.as_ref(),
+        &ctx,
+        &execution.promptset_id,
+        &repository,
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!(e))?;
+
+    let (status, ci_url, checks) = check_ci_once(provider.clone(), ctx.clone(), &policy).await?;
+    record_checks(app, &watch.execution_id, &watch.commit_sha, &checks);
+
+    let status = match status {
+        Some(status) => status,
+        None => {
+            // Nothing has reported yet. Only skip once the grace period has passed and the
+            // provider confirms no CI will run; slow-to-start pipelines stay pending
+            let waited_minutes =
+                (chrono::Utc::now().timestamp_millis() - watch.created_at) / 60_000;
+            if waited_minutes < grace_minutes || !provider.confirms_no_ci(&ctx).await? {
+                return Ok(WatchOutcome::Pending);
+            }
+            log::info!(
+                "[ci_watcher] Provider confirms no CI for execution {}, marking as skipped",
+                watch.execution_id
+            );
+            CiStatus::Skipped
+        }
+    };
+
+    {
+        let store = app.state::<Mutex<Store>>();
+        let store = store.lock().unwrap();
+        store.update_execution(
+            &watch.execution_id,
+            ExecutionUpdates {
+                ci_status: Some(status),
+                ci_checked_at: Some(chrono::Utc::now().timestamp_millis()),
+                ci_url: ci_url.clone(),
+                ..Default::default()
+            },
+        )?;
+    }
+
+    // Emit event (serialize status properly using serde)
+    let status_str = serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| format!("{:?}", status).to_lowercase());
+
+    executor_events::emit_execution_ci(app, &watch.execution_id, &status_str, ci_url.as_deref());
+
+    if status == CiStatus::Pending {
+        Ok(WatchOutcome::Pending)
+    } else {
+        log::info!(
+            "[ci_watcher] CI reached terminal state {:?} for execution {}",
+            status,
+            watch.execution_id
+        );
+        Ok(WatchOutcome::Finished)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_poll_schedule() {
+        assert_eq!(
+            parse_poll_schedule("10, 30,300").unwrap(),
+            vec![10, 30, 300]
+        );
+        assert!(parse_poll_schedule("").is_err());
+        assert!(parse_poll_schedule("10,0").is_err());
+        assert!(parse_poll_schedule("10,abc").is_err());
+    }
+
+    #[test]
+    fn test_last_interval_repeats() {
+        let schedule = [10, 60, 300];
+        assert_eq!(next_poll_delay(&schedule, 0), 10);
+        assert_eq!(next_poll_delay(&schedule, 2), 300);
+        assert_eq!(next_poll_delay(&schedule, 500), 300);
+    }
+}
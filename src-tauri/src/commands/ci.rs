@@ -1,14 +1,89 @@
 use std::sync::Mutex;
 use tauri::AppHandle;
 
-use crate::ci::{check_ci_once, CiContext};
+use crate::ci::{
+    check_ci_once, fetch_and_cache_logs, record_checks, watch_execution, CiCheckPolicy, CiContext,
+    CiLog, CiProvider,
+};
 use crate::commands::executor_events;
-use crate::db::store::{ExecutionUpdates, Store};
+use crate::db::store::{
+    CiCheckFailureStat, CiCheckRecord, CiCheckTransition, CiWatch, ExecutionUpdates, Repository,
+    RepositoryCiConfig, Store,
+};
 use crate::types::CiStatus;
-use crate::util::paths::admin_repo_path;
+use crate::util::paths::{admin_repo_path, ci_log_path};
 use crate::Paths;
 
-/// Start CI checking for an execution (spawns background polling)
+/// Resolve the CI check policy for an execution's repository within its promptset,
+/// adding branch protection required checks when discovery is enabled
+pub(crate) async fn resolve_check_policy(
+    store: &Mutex<Store>,
+    provider: &dyn CiProvider,
+    ctx: &CiContext,
+    promptset_id: &str,
+    repository: &Repository,
+) -> Result<CiCheckPolicy, String> {
+    let mut policy = {
+        let store = store.lock().map_err(|e| e.to_string())?;
+        store
+            .resolve_ci_check_policy(promptset_id, &repository.id)
+            .map_err(|e| e.to_string())?
+    };
+
+    if policy.discover_required {
+        let base_branch = repository.default_branch.as_deref().unwrap_or("main");
+        match provider.fetch_required_checks(ctx, base_branch).await {
+            Ok(Some(names)) => policy.require_exact(names),
+            Ok(None) => {}
+            Err(e) => log::warn!(
+                "Failed to discover required checks for {} on {}: {}",
+                repository.provider_id,
+                base_branch,
+                e
+            ),
+        }
+    }
+
+    Ok(policy)
+}
+
+/// Whether a repository has CI to track, either from its git host or a CI override
+fn repository_has_ci(store: &Mutex<Store>, repository: &Repository) -> Result<bool, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    let has_ci_override = store
+        .get_repository_ci_config(&repository.id)
+        .map_err(|e| e.to_string())?
+        .is_some();
+    Ok(crate::ci::provider::repository_has_ci(
+        repository,
+        has_ci_override,
+    ))
+}
+
+/// Record that an execution's repository has no CI to track
+pub(crate) fn record_ci_not_configured(app: &AppHandle, execution_id: &str) -> Result<(), String> {
+    use tauri::Manager;
+
+    {
+        let store = app.state::<Mutex<Store>>();
+        let store = store.lock().map_err(|e| e.to_string())?;
+        store
+            .update_execution(
+                execution_id,
+                ExecutionUpdates {
+                    ci_status: Some(CiStatus::NotConfigured),
+                    ci_checked_at: Some(chrono::Utc::now().timestamp_millis()),
+                    ..Default::default()
+                },
+            )
+            .map_err(|e| format!("Failed to update status: {}", e))?;
+    }
+
+    executor_events::emit_execution_ci(app, execution_id, "not_configured", None);
+    Ok(())
+}
+
+/// Start CI checking for an execution (queues it for the background CI watcher)
 #[tauri::command]
 pub async fn start_ci_check(
     execution_id: String,
@@ -43,12 +118,10 @@ pub async fn start_ci_check(
             .ok_or_else(|| format!("Repository {} not found", execution.repository_id))?
     };
 
-    // Parse owner/repo from provider_id
-    let (owner, repo_name) = crate::util::git::parse_provider_id(&repository.provider_id)
-        .map_err(|e| format!("Failed to parse provider ID: {}", e))?;
-
     // Check if commit has been pushed to remote
-    let repo_path = admin_repo_path(&paths, &owner, &repo_name);
+    let tokens = crate::commands::tokens::cached_tokens()?;
+    let repo_path = admin_repo_path(&paths, &tokens, &repository.provider_id)
+        .map_err(|e| format!("Failed to parse provider ID: {}", e))?;
     let is_pushed = crate::git::is_commit_pushed(&repo_path, &commit_sha).unwrap_or(false);
 
     if !is_pushed {
@@ -68,20 +141,20 @@ pub async fn start_ci_check(
         return Ok(());
     }
 
+    if !repository_has_ci(&store, &repository)? {
+        return record_ci_not_configured(&app, &execution_id);
+    }
+
     // Create CI provider using the provider trait
-    let provider =
-        crate::ci::provider::create_ci_provider(&repository.provider, &repository.provider_id)
+    let (provider, provider_cfg) =
+        crate::ci::provider::create_repository_ci_provider(&store, &repository)
             .await
             .map_err(|e| format!("Failed to create CI provider: {}", e))?;
 
     let ctx = CiContext {
         commit_sha: commit_sha.clone(),
         branch: branch.clone(),
-        provider_cfg: crate::util::git::build_provider_cfg(
-            &repository.provider,
-            &repository.provider_id,
-        )
-        .map_err(|e| e.to_string())?,
+        provider_cfg,
     };
 
     // Get initial CI URL from provider
@@ -90,7 +163,7 @@ pub async fn start_ci_check(
     // Emit initial pending status
     executor_events::emit_execution_ci(&app, &execution_id, "pending", Some(&ci_url));
 
-    // Update database with pending status
+    // Update database with pending status and hand the commit to the CI watcher
     {
         let store = store.lock().map_err(|e| e.to_string())?;
         let now = chrono::Utc::now().timestamp_millis();
@@ -105,17 +178,9 @@ pub async fn start_ci_check(
                 },
             )
             .map_err(|e| e.to_string())?;
+        watch_execution(&store, &execution_id, &commit_sha).map_err(|e| e.to_string())?;
     }
 
-    // Spawn background polling task
-    let app_clone = app.clone();
-    let exec_id = execution_id.clone();
-    tokio::spawn(async move {
-        if let Err(e) = crate::ci::poll_ci_until_terminal(provider, ctx, exec_id, app_clone).await {
-            log::warn!("CI polling error: {:?}", e);
-        }
-    });
-
     Ok(())
 }
 
@@ -152,12 +217,10 @@ pub async fn refresh_ci_status(
             .ok_or_else(|| format!("Repository {} not found", execution.repository_id))?
     };
 
-    // Parse owner/repo from provider_id
-    let (owner, repo_name) = crate::util::git::parse_provider_id(&repository.provider_id)
-        .map_err(|e| format!("Failed to parse provider ID: {}", e))?;
-
     // Check if commit has been pushed to remote
-    let repo_path = admin_repo_path(&paths, &owner, &repo_name);
+    let tokens = crate::commands::tokens::cached_tokens()?;
+    let repo_path = admin_repo_path(&paths, &tokens, &repository.provider_id)
+        .map_err(|e| format!("Failed to parse provider ID: {}", e))?;
     let is_pushed = crate::git::is_commit_pushed(&repo_path, &commit_sha).unwrap_or(false);
 
     if !is_pushed {
@@ -177,27 +240,38 @@ pub async fn refresh_ci_status(
         return Ok(());
     }
 
+    if !repository_has_ci(&store, &repository)? {
+        return record_ci_not_configured(&app, &execution_id);
+    }
+
     // Create CI provider using the provider trait
-    let provider =
-        crate::ci::provider::create_ci_provider(&repository.provider, &repository.provider_id)
+    let (provider, provider_cfg) =
+        crate::ci::provider::create_repository_ci_provider(&store, &repository)
             .await
             .map_err(|e| format!("Failed to create CI provider: {}", e))?;
 
     let ctx = CiContext {
         commit_sha: commit_sha.clone(),
         branch,
-        provider_cfg: crate::util::git::build_provider_cfg(
-            &repository.provider,
-            &repository.provider_id,
-        )
-        .map_err(|e| e.to_string())?,
+        provider_cfg,
     };
 
+    let policy = resolve_check_policy(
+        &store,
+        provider.as_ref(),
+        &ctx,
+        &execution.promptset_id,
+        &repository,
+    )
+    .await?;
+
     // Check CI once
-    let (status_opt, ci_url_opt) = check_ci_once(provider, ctx)
+    let (status_opt, ci_url_opt, checks) = check_ci_once(provider, ctx, &policy)
         .await
         .map_err(|e| e.to_string())?;
 
+    record_checks(&app, &execution_id, &commit_sha, &checks);
+
     let status = status_opt.unwrap_or(CiStatus::NotConfigured);
 
     // Update database
@@ -215,6 +289,11 @@ pub async fn refresh_ci_status(
                 },
             )
             .map_err(|e| e.to_string())?;
+
+        // Make sure a still-running pipeline keeps being watched
+        if status == CiStatus::Pending {
+            watch_execution(&store, &execution_id, &commit_sha).map_err(|e| e.to_string())?;
+        }
     }
 
     // Emit event (serialize status properly using serde)
@@ -227,3 +306,198 @@ pub async fn refresh_ci_status(
 
     Ok(())
 }
+
+/// Fetch logs for the failing CI checks of an execution
+/// Logs are trimmed to their tail and cached on disk per commit; pass refresh to refetch
+#[tauri::command]
+pub async fn get_ci_failure_logs(
+    execution_id: String,
+    refresh: Option<bool>,
+    store: tauri::State<'_, Mutex<Store>>,
+    paths: tauri::State<'_, Paths>,
+) -> Result<Vec<CiLog>, String> {
+    // Get execution details
+    let execution = {
+        let store = store.lock().map_err(|e| e.to_string())?;
+        store
+            .get_execution(&execution_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Execution {} not found", execution_id))?
+    };
+
+    let commit_sha = execution
+        .commit_sha
+        .ok_or_else(|| "Execution must be committed before fetching CI logs".to_string())?;
+
+    let branch = execution.branch.unwrap_or_else(|| "main".to_string());
+
+    // Get repository details
+    let repository = {
+        let store = store.lock().map_err(|e| e.to_string())?;
+        store
+            .get_repository(&execution.repository_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Repository {} not found", execution.repository_id))?
+    };
+
+    let (provider, provider_cfg) =
+        crate::ci::provider::create_repository_ci_provider(&store, &repository)
+            .await
+            .map_err(|e| format!("Failed to create CI provider: {}", e))?;
+
+    let ctx = CiContext {
+        commit_sha: commit_sha.clone(),
+        branch,
+        provider_cfg,
+    };
+
+    let checks = provider.poll(&ctx).await.map_err(|e| e.to_string())?;
+    let refresh = refresh.unwrap_or(false);
+
+    let mut logs = Vec::new();
+    for check in checks.iter().filter(|c| c.state == CiStatus::Failed) {
+        let path = ci_log_path(&paths, &execution_id, &commit_sha, &check.context);
+        match fetch_and_cache_logs(provider.as_ref(), &ctx, check, &path, refresh).await {
+            Ok(Some(log)) => logs.push(log),
+            Ok(None) => {}
+            Err(e) => log::warn!(
+                "Failed to fetch CI logs for {} on execution {}: {}",
+                check.context,
+                execution_id,
+                e
+            ),
+        }
+    }
+
+    Ok(logs)
+}
+
+/// Individual CI checks recorded for an execution, with their state transitions
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionCiChecks {
+    pub checks: Vec<CiCheckRecord>,
+    pub transitions: Vec<CiCheckTransition>,
+}
+
+/// Get the individual CI checks recorded for an execution
+#[tauri::command]
+pub fn get_execution_ci_checks(
+    execution_id: String,
+    store: tauri::State<'_, Mutex<Store>>,
+) -> Result<ExecutionCiChecks, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    let checks = store
+        .get_ci_checks(&execution_id)
+        .map_err(|e| e.to_string())?;
+    let transitions = store
+        .get_ci_check_transitions(&execution_id)
+        .map_err(|e| e.to_string())?;
+    Ok(ExecutionCiChecks {
+        checks,
+        transitions,
+    })
+}
+
+/// Get per-check failure counts across all executions of a revision, optionally only
+/// those against repositories matching a tag expression
+#[tauri::command]
+pub fn get_revision_ci_check_stats(
+    revision_id: String,
+    tag_expression: Option<String>,
+    store: tauri::State<'_, Mutex<Store>>,
+) -> Result<Vec<CiCheckFailureStat>, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    let repository_ids =
+        crate::commands::tags::repository_ids_matching(&store, tag_expression.as_deref())?;
+    store
+        .get_ci_check_failure_stats(&revision_id, repository_ids.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Get the CI check policy stored for a promptset, or for a repository override within it
+#[tauri::command]
+pub fn get_ci_check_policy(
+    promptset_id: String,
+    repository_id: Option<String>,
+    store: tauri::State<'_, Mutex<Store>>,
+) -> Result<Option<CiCheckPolicy>, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .get_ci_check_policy(&promptset_id, repository_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Set the CI check policy for a promptset, or a repository override within it
+#[tauri::command]
+pub fn set_ci_check_policy(
+    promptset_id: String,
+    repository_id: Option<String>,
+    policy: CiCheckPolicy,
+    store: tauri::State<'_, Mutex<Store>>,
+) -> Result<(), String> {
+    policy.validate().map_err(|e| e.to_string())?;
+    let mut store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .set_ci_check_policy(&promptset_id, repository_id.as_deref(), &policy)
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a CI check policy (a repository override falls back to the promptset default)
+#[tauri::command]
+pub fn delete_ci_check_policy(
+    promptset_id: String,
+    repository_id: Option<String>,
+    store: tauri::State<'_, Mutex<Store>>,
+) -> Result<bool, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .delete_ci_check_policy(&promptset_id, repository_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// List executions the background CI watcher is still polling
+#[tauri::command]
+pub fn get_ci_watches(store: tauri::State<'_, Mutex<Store>>) -> Result<Vec<CiWatch>, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store.get_ci_watches().map_err(|e| e.to_string())
+}
+
+/// Get the CI system override for a repository (None = the git host's own CI)
+#[tauri::command]
+pub fn get_repository_ci_config(
+    repository_id: String,
+    store: tauri::State<'_, Mutex<Store>>,
+) -> Result<Option<RepositoryCiConfig>, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .get_repository_ci_config(&repository_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Point a repository's CI at another system (e.g. Jenkins) with its provider config
+#[tauri::command]
+pub fn set_repository_ci_config(
+    repository_id: String,
+    ci_provider: String,
+    config: serde_json::Value,
+    store: tauri::State<'_, Mutex<Store>>,
+) -> Result<(), String> {
+    crate::ci::provider::validate_ci_config(&ci_provider, &config).map_err(|e| e.to_string())?;
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .set_repository_ci_config(&repository_id, &ci_provider, &config)
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a repository's CI override, falling back to the git host's own CI
+#[tauri::command]
+pub fn delete_repository_ci_config(
+    repository_id: String,
+    store: tauri::State<'_, Mutex<Store>>,
+) -> Result<bool, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .delete_repository_ci_config(&repository_id)
+        .map_err(|e| e.to_string())
+}
@@ -1,54 +1,220 @@
-use crate::db::store::{Execution, ExecutionUpdates, PromptRevision, PromptSet, Repository, Store};
-use crate::git::GitProviderContext;
+use crate::commands::tags::repository_ids_matching;
+use crate::commands::tokens::AllTokens;
+use crate::db::store::{
+    Execution, ExecutionUpdates, PromptRevision, PromptSet, Repository, RepositoryQuerySnapshot,
+    RepositoryRename, Store,
+};
+use crate::git::applicability::ApplicabilityCheck;
+use crate::git::checkout::{normalize_sparse_patterns, CloneStrategy};
+use crate::git::{GitProviderContext, RepoMetadata, RepositoryFilter};
+use crate::util::git::{parse_provider_id, renamed_provider_id, require_known_host};
+use crate::util::tags::TagExpression;
+use crate::Paths;
+use anyhow::Context;
 use std::sync::Mutex;
 use tauri::State;
 
 type StoreState<'a> = State<'a, Mutex<Store>>;
 
-#[tauri::command]
-pub async fn sync_repository_metadata(store: StoreState<'_>, id: String) -> Result<(), String> {
-    let repository = store
-        .lock()
-        .unwrap()
-        .get_repository(&id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Repository not found".to_string())?;
+/// Follow a rename or transfer on the provider: move the admin clone, then point the repository
+/// at its new provider_id, keeping the old identity in its rename history
+/// A name that mirrored the old path follows it; other custom names are kept
+/// While executions are queued or running the clone stays put and the rename waits for a later
+/// sync; the old path keeps working through the provider's redirect
+fn follow_repository_rename(
+    store: &Mutex<Store>,
+    paths: &Paths,
+    tokens: &AllTokens,
+    repository: &Repository,
+    new_provider_id: &str,
+) -> Result<(), String> {
+    {
+        let store = store.lock().unwrap();
+        let existing = store
+            .find_repository(&repository.provider, new_provider_id)
+            .map_err(|e| e.to_string())?;
+        if existing.is_some() {
+            return Err(format!(
+                "{} was renamed to {}, which is already tracked as another repository",
+                repository.provider_id, new_provider_id
+            ));
+        }
+    }
+
+    // Checked under the clone's locks, so an execution can't start between the check and the move
+    let moved = crate::commands::executor::move_admin_clone(
+        tokens,
+        &paths.admin_repo_dir,
+        &repository.provider_id,
+        new_provider_id,
+        || {
+            Ok(!store
+                .lock()
+                .unwrap()
+                .has_active_executions(&repository.id)?)
+        },
+    )
+    .map_err(|e| {
+        format!(
+            "Failed to move the clone of {}: {}",
+            repository.provider_id, e
+        )
+    })?;
+    if !moved {
+        log::info!(
+            "[sync_repository_metadata] {} was renamed to {}; following it once its executions finish",
+            repository.provider_id,
+            new_provider_id
+        );
+        return Ok(());
+    }
 
+    let (old_owner, old_repo) =
+        parse_provider_id(tokens, &repository.provider_id).map_err(|e| e.to_string())?;
+    let (new_owner, new_repo) =
+        parse_provider_id(tokens, new_provider_id).map_err(|e| e.to_string())?;
+    let new_name = repository.name.as_deref().map(|name| {
+        if name == old_repo {
+            new_repo.clone()
+        } else if name == format!("{}/{}", old_owner, old_repo) {
+            format!("{}/{}", new_owner, new_repo)
+        } else {
+            name.to_string()
+        }
+    });
+
+    // Put the clone back if the rename can't be recorded; should the app stop in between, the
+    // next sync finds the rename again and, with no clone left at the old path, only records it
+    let renamed = store.lock().unwrap().rename_repository(
+        &repository.id,
+        new_provider_id,
+        new_name.as_deref(),
+    );
+    if let Err(e) = renamed {
+        if let Err(undo) = crate::commands::executor::move_admin_clone(
+            tokens,
+            &paths.admin_repo_dir,
+            new_provider_id,
+            &repository.provider_id,
+            || Ok(true),
+        ) {
+            log::error!(
+                "[sync_repository_metadata] Failed to move the clone of {} back: {}",
+                new_provider_id,
+                undo
+            );
+        }
+        return Err(format!(
+            "Failed to rename {} to {}: {}",
+            repository.provider_id, new_provider_id, e
+        ));
+    }
+    log::info!(
+        "[sync_repository_metadata] {} was renamed to {}",
+        repository.provider_id,
+        new_provider_id
+    );
+    Ok(())
+}
+
+/// Fetch a repository's metadata from its provider
+/// Errors keep their cause, so callers can tell a spent rate limit apart
+pub(crate) async fn fetch_repository_metadata(
+    repository: &Repository,
+) -> anyhow::Result<RepoMetadata> {
     // Create the appropriate git provider
     let provider = crate::git::git_provider::create_git_provider(
         &repository.provider,
         &repository.provider_id,
     )
     .await
-    .map_err(|e| format!("Failed to create git provider: {}", e))?;
+    .context("Failed to create git provider")?;
 
     // Build provider configuration
+    let tokens = crate::commands::tokens::cached_tokens().map_err(anyhow::Error::msg)?;
     let ctx = GitProviderContext {
         provider_cfg: crate::util::git::build_provider_cfg(
+            &tokens,
             &repository.provider,
             &repository.provider_id,
         )
-        .map_err(|e| format!("Failed to build provider config: {}", e))?,
+        .context("Failed to build provider config")?,
     };
 
-    // Fetch default branch from provider
-    let default_branch = provider
-        .fetch_default_branch(&ctx)
+    provider
+        .get_repo_metadata(&ctx)
         .await
-        .map_err(|e| format!("Failed to fetch default branch: {}", e))?;
+        .context("Failed to fetch repository metadata")
+}
 
+/// Persist freshly fetched metadata, returning the updated row
+/// Renames and transfers the provider reports are followed first
+pub(crate) fn apply_repository_metadata(
+    store: &Mutex<Store>,
+    paths: &Paths,
+    repository: &Repository,
+    metadata: &RepoMetadata,
+) -> Result<Repository, String> {
+    let tokens = crate::commands::tokens::cached_tokens()?;
+    let new_provider_id = metadata
+        .full_name
+        .as_deref()
+        .and_then(|full_name| renamed_provider_id(&tokens, &repository.provider_id, full_name));
+    if let Some(new_provider_id) = new_provider_id {
+        follow_repository_rename(store, paths, &tokens, repository, &new_provider_id)?;
+    }
+
+    let store = store.lock().unwrap();
+    let branch_changed = store
+        .update_repository_metadata(&repository.id, metadata)
+        .map_err(|e| e.to_string())?;
+    if branch_changed {
+        log::info!(
+            "[sync_repository_metadata] Default branch of {} changed from {} to {}",
+            repository.provider_id,
+            repository.default_branch.as_deref().unwrap_or_default(),
+            metadata.default_branch
+        );
+    }
     store
+        .get_repository(&repository.id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Repository not found".to_string())
+}
+
+/// Fetch a repository's metadata from its provider and persist it, returning the updated row
+pub(crate) async fn refresh_repository_metadata(
+    store: &Mutex<Store>,
+    paths: &Paths,
+    repository: &Repository,
+) -> Result<Repository, String> {
+    let metadata = fetch_repository_metadata(repository)
+        .await
+        .map_err(|e| format!("{:#}", e))?;
+    apply_repository_metadata(store, paths, repository, &metadata)
+}
+
+#[tauri::command]
+pub async fn sync_repository_metadata(
+    store: StoreState<'_>,
+    paths: State<'_, Paths>,
+    id: String,
+) -> Result<(), String> {
+    let repository = store
         .lock()
         .unwrap()
-        .update_repository_default_branch(&id, &default_branch)
-        .map_err(|e| e.to_string())?;
+        .get_repository(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Repository not found".to_string())?;
 
+    refresh_repository_metadata(&store, &paths, &repository).await?;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn create_repository(
     store: StoreState<'_>,
+    paths: State<'_, Paths>,
     provider: String,
     provider_id: String,
 ) -> Result<Repository, String> {
@@ -58,21 +224,9 @@ pub async fn create_repository(
         .create_repository(&provider, &provider_id)
         .map_err(|e| e.to_string())?;
 
-    // Fetch default branch using GitProvider
-    if let Ok(git_provider) =
-        crate::git::git_provider::create_git_provider(&provider, &provider_id).await
-    {
-        if let Ok(provider_cfg) = crate::util::git::build_provider_cfg(&provider, &provider_id) {
-            let ctx = GitProviderContext { provider_cfg };
-            if let Ok(default_branch) = git_provider.fetch_default_branch(&ctx).await {
-                store
-                    .lock()
-                    .unwrap()
-                    .update_repository_default_branch(&repo.id, &default_branch)
-                    .map_err(|e| e.to_string())?;
-                repo.default_branch = Some(default_branch);
-            }
-        }
+    // Fetch the default branch and the rest of the metadata, best effort
+    if let Ok(refreshed) = refresh_repository_metadata(&store, &paths, &repo).await {
+        repo = refreshed;
     }
 
     Ok(repo)
@@ -91,6 +245,40 @@ pub async fn update_repository_name(
         .map_err(|e| e.to_string())
 }
 
+/// Validated clone strategy, in canonical form, and sparse checkout patterns; a blank
+/// strategy clears it
+fn parse_checkout(
+    clone_strategy: Option<String>,
+    sparse_checkout: Vec<String>,
+) -> Result<(Option<String>, Vec<String>), String> {
+    let clone_strategy = clone_strategy
+        .as_deref()
+        .map(str::trim)
+        .filter(|strategy| !strategy.is_empty())
+        .map(|strategy| CloneStrategy::parse(strategy).map(|strategy| strategy.to_string()))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let sparse_checkout = normalize_sparse_patterns(&sparse_checkout).map_err(|e| e.to_string())?;
+    Ok((clone_strategy, sparse_checkout))
+}
+
+/// Set how a repository's admin clone is made and which paths its execution worktrees check out
+/// A new clone strategy takes effect once the clone is recreated, e.g. after eviction
+#[tauri::command]
+pub async fn update_repository_checkout(
+    store: StoreState<'_>,
+    id: String,
+    clone_strategy: Option<String>,
+    sparse_checkout: Vec<String>,
+) -> Result<(), String> {
+    let (clone_strategy, sparse_checkout) = parse_checkout(clone_strategy, sparse_checkout)?;
+    store
+        .lock()
+        .unwrap()
+        .update_repository_checkout(&id, clone_strategy.as_deref(), &sparse_checkout)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_repository(
     store: StoreState<'_>,
@@ -103,6 +291,89 @@ pub async fn get_repository(
         .map_err(|e| e.to_string())
 }
 
+/// Former identities of a repository renamed or transferred on its provider, newest first
+#[tauri::command]
+pub async fn get_repository_renames(
+    store: StoreState<'_>,
+    repository_id: String,
+) -> Result<Vec<RepositoryRename>, String> {
+    store
+        .lock()
+        .unwrap()
+        .get_repository_renames(&repository_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Import the repositories of an organization, user or group matching a filter, optionally
+/// adding them to a promptset; `host` selects a credential profile and prefixes provider_ids
+#[tauri::command]
+pub async fn import_repositories(
+    store: StoreState<'_>,
+    provider: String,
+    host: Option<String>,
+    filter: RepositoryFilter,
+    promptset_id: Option<String>,
+) -> Result<Vec<Repository>, String> {
+    let host = host
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty());
+    if let Some(host) = &host {
+        let tokens = crate::commands::tokens::cached_tokens()?;
+        require_known_host(&tokens, host).map_err(|e| e.to_string())?;
+    }
+
+    let git_provider = crate::git::git_provider::create_namespace_git_provider(
+        &provider,
+        host.as_deref(),
+        &filter.namespace,
+    )
+    .await
+    .map_err(|e| format!("Failed to create git provider: {}", e))?;
+
+    let listings = git_provider
+        .list_repositories(&filter)
+        .await
+        .map_err(|e| format!("Failed to list repositories: {:#}", e))?
+        .ok_or_else(|| {
+            format!(
+                "Listing repositories is not supported for {}",
+                git_provider.display_name()
+            )
+        })?;
+
+    let mut store = store.lock().unwrap();
+    let repositories = listings
+        .iter()
+        .map(|listing| {
+            let provider_id = match &host {
+                Some(host) => format!("{}/{}", host, listing.full_name),
+                None => listing.full_name.clone(),
+            };
+            store.upsert_repository(&provider, &provider_id, listing.default_branch.as_deref())
+        })
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(promptset_id) = promptset_id {
+        let promptset = store
+            .get_promptset(&promptset_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Promptset {} not found", promptset_id))?;
+
+        let mut repository_ids = promptset.repository_ids;
+        for repository in &repositories {
+            if !repository_ids.contains(&repository.id) {
+                repository_ids.push(repository.id.clone());
+            }
+        }
+        store
+            .update_promptset_repositories(&promptset_id, repository_ids)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(repositories)
+}
+
 #[tauri::command]
 pub async fn find_repository(
     store: StoreState<'_>,
@@ -209,6 +480,100 @@ pub async fn update_promptset_repositories(
         .map_err(|e| e.to_string())
 }
 
+/// Save (or clear, with None or a blank query) the Sourcegraph query a promptset's repositories
+/// are re-resolved from on each run
+#[tauri::command]
+pub async fn update_promptset_repository_query(
+    store: StoreState<'_>,
+    id: String,
+    query: Option<String>,
+) -> Result<(), String> {
+    let query = query
+        .as_deref()
+        .map(str::trim)
+        .filter(|query| !query.is_empty());
+    store
+        .lock()
+        .unwrap()
+        .update_promptset_repository_query(&id, query)
+        .map_err(|e| e.to_string())
+}
+
+/// Set the applicability check (content regex and/or path glob) evaluated against each
+/// repository before an execution runs; blank values clear it
+#[tauri::command]
+pub async fn update_promptset_applicability(
+    store: StoreState<'_>,
+    id: String,
+    pattern: Option<String>,
+    path_glob: Option<String>,
+) -> Result<(), String> {
+    let pattern = pattern.as_deref().map(str::trim).filter(|p| !p.is_empty());
+    let path_glob = path_glob
+        .as_deref()
+        .map(str::trim)
+        .filter(|g| !g.is_empty());
+    ApplicabilityCheck::new(pattern, path_glob).map_err(|e| e.to_string())?;
+
+    store
+        .lock()
+        .unwrap()
+        .update_promptset_applicability(&id, pattern, path_glob)
+        .map_err(|e| e.to_string())
+}
+
+/// Set the clone strategy of the promptset's repositories that don't set their own, and the
+/// sparse checkout patterns added to theirs
+#[tauri::command]
+pub async fn update_promptset_checkout(
+    store: StoreState<'_>,
+    id: String,
+    clone_strategy: Option<String>,
+    sparse_checkout: Vec<String>,
+) -> Result<(), String> {
+    let (clone_strategy, sparse_checkout) = parse_checkout(clone_strategy, sparse_checkout)?;
+    store
+        .lock()
+        .unwrap()
+        .update_promptset_checkout(&id, clone_strategy.as_deref(), &sparse_checkout)
+        .map_err(|e| e.to_string())
+}
+
+/// Save (or clear, with None or a blank expression) the tag expression whose matching
+/// repositories a promptset targets alongside its explicit repositories
+#[tauri::command]
+pub async fn update_promptset_repository_tag_expression(
+    store: StoreState<'_>,
+    id: String,
+    expression: Option<String>,
+) -> Result<(), String> {
+    let expression = expression
+        .as_deref()
+        .map(str::trim)
+        .filter(|expression| !expression.is_empty());
+    if let Some(expression) = expression {
+        TagExpression::parse(expression).map_err(|e| format!("Invalid tag expression: {}", e))?;
+    }
+
+    store
+        .lock()
+        .unwrap()
+        .update_promptset_repository_tag_expression(&id, expression)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_repository_query_snapshots(
+    store: StoreState<'_>,
+    revision_id: String,
+) -> Result<Vec<RepositoryQuerySnapshot>, String> {
+    store
+        .lock()
+        .unwrap()
+        .get_repository_query_snapshots(&revision_id)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn create_prompt_revision(
     store: StoreState<'_>,
@@ -307,28 +672,48 @@ pub async fn find_execution_by_prefix(
         .map_err(|e| e.to_string())
 }
 
+/// Optionally limited to repositories matching a tag expression
 #[tauri::command]
 pub async fn get_executions_by_revision(
     store: StoreState<'_>,
     revision_id: String,
+    tag_expression: Option<String>,
 ) -> Result<Vec<Execution>, String> {
-    store
-        .lock()
-        .unwrap()
+    let store = store.lock().unwrap();
+    let executions = store
         .get_executions_by_revision(&revision_id)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    Ok(
+        match repository_ids_matching(&store, tag_expression.as_deref())? {
+            Some(ids) => executions
+                .into_iter()
+                .filter(|execution| ids.contains(&execution.repository_id))
+                .collect(),
+            None => executions,
+        },
+    )
 }
 
+/// Optionally limited to repositories matching a tag expression
 #[tauri::command]
 pub async fn get_executions_by_promptset(
     store: StoreState<'_>,
     promptset_id: String,
+    tag_expression: Option<String>,
 ) -> Result<Vec<Execution>, String> {
-    store
-        .lock()
-        .unwrap()
+    let store = store.lock().unwrap();
+    let executions = store
         .get_executions_by_promptset(&promptset_id)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    Ok(
+        match repository_ids_matching(&store, tag_expression.as_deref())? {
+            Some(ids) => executions
+                .into_iter()
+                .filter(|execution| ids.contains(&execution.repository_id))
+                .collect(),
+            None => executions,
+        },
+    )
 }
 
 #[tauri::command]
@@ -52,14 +52,20 @@ use super::executor_events::{
     emit_execution_commit, emit_execution_progress, emit_execution_session, emit_execution_status,
     emit_execution_validation,
 };
-use crate::db::store::{ExecutionUpdates, Store};
+use crate::commands::tokens::{cached_tokens, AllTokens};
+use crate::db::store::{ExecutionUpdates, Repository, Store};
+use crate::git::applicability::ApplicabilityCheck;
+use crate::git::checkout::{effective_clone_strategy, sparse_checkout_args, CloneStrategy};
+use crate::git::service::{git_command, GitAuth};
 use crate::git::{
     get_committed_diff, get_committed_file_diff, get_worktree_diff, get_worktree_file_diff,
     GitService, ModifiedFilesResponse,
 };
 use crate::types::{CiStatus, CommitStatus, ExecutionStatus, PromptStatus, ValidationStatus};
-use crate::util::git::{maestro_branch_name, parse_provider_id};
-use crate::util::paths::{admin_repo_path, execution_worktree_path, worktree_path};
+use crate::util::git::{
+    clone_owner_repo, maestro_branch_name, parse_provider_id, resolve_provider_token,
+};
+use crate::util::paths::{admin_repo_path, execution_worktree_path, repo_path_in, worktree_path};
 use crate::Paths;
 
 async fn fetch_default_branch(provider: &str, provider_id: &str) -> Result<String> {
@@ -69,12 +75,12 @@ async fn fetch_default_branch(provider: &str, provider_id: &str) -> Result<Strin
 
     let git_provider = create_git_provider(provider, provider_id).await?;
 
+    let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
     let ctx = GitProviderContext {
-        provider_cfg: build_provider_cfg(provider, provider_id)?,
+        provider_cfg: build_provider_cfg(&tokens, provider, provider_id)?,
     };
 
-    let metadata = git_provider.get_repo_metadata(&ctx).await?;
-    Ok(metadata.default_branch)
+    git_provider.fetch_default_branch(&ctx).await
 }
 
 /// Check if SSH agent is available and has keys loaded
@@ -88,26 +94,6 @@ fn ssh_agent_has_keys() -> bool {
         .unwrap_or(false)
 }
 
-/// Resolve provider-specific PAT token from keyring
-fn resolve_provider_token(provider: &str) -> Result<String> {
-    use crate::commands::tokens::get_token_value;
-
-    let token_key = match provider {
-        "github" => "github_token",
-        "gitlab" => "gitlab_token",
-        _ => anyhow::bail!("Unsupported provider: {}", provider),
-    };
-
-    get_token_value(token_key)
-        .map_err(|e| anyhow::anyhow!("Failed to access {} token: {}", provider, e))?
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "{} token not configured. Please set it in Settings.",
-                provider
-            )
-        })
-}
-
 type ChildHandle = std::sync::Arc<Mutex<Option<tokio::process::Child>>>;
 
 lazy_static::lazy_static! {
@@ -117,7 +103,9 @@ lazy_static::lazy_static! {
     static ref REPO_LOCKS: Mutex<HashMap<String, std::sync::Arc<Mutex<()>>>> = Mutex::new(HashMap::new());
 }
 
-fn get_repo_lock(owner: &str, repo: &str) -> std::sync::Arc<Mutex<()>> {
+/// Lock serializing git operations on an admin clone, keyed by its owner and repo as in the
+/// clone's path
+pub(crate) fn get_repo_lock(owner: &str, repo: &str) -> std::sync::Arc<Mutex<()>> {
     let repo_key = format!("{}/{}", owner, repo);
     let mut locks = REPO_LOCKS.lock().unwrap();
     locks
@@ -129,53 +117,62 @@ fn get_repo_lock(owner: &str, repo: &str) -> std::sync::Arc<Mutex<()>> {
 async fn ensure_admin_repo_and_fetch(
     admin_repo_dir: &Path,
     provider: &str,
+    provider_id: &str,
     owner: &str,
     repo: &str,
+    strategy: CloneStrategy,
 ) -> Result<PathBuf> {
-    use crate::commands::tokens::get_token_value;
+    // Resolved before taking the repository lock, which is never held across an await
+    // (minting a GitHub App installation token is async); only HTTPS operations use it
+    let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
+    let https_token = resolve_provider_token(&tokens, provider, provider_id)
+        .await
+        .map_err(|e| e.to_string());
 
-    let repo_lock = get_repo_lock(owner, repo);
+    let (clone_owner, clone_name) = clone_owner_repo(&tokens, provider_id)?;
+    let repo_lock = get_repo_lock(&clone_owner, &clone_name);
     let _lock = repo_lock.lock().unwrap();
 
-    let admin_repo_path = admin_repo_dir.join(owner).join(repo);
+    let admin_repo_path = repo_path_in(admin_repo_dir, &clone_owner, &clone_name);
 
     // Clone if not exists
     if !admin_repo_path.join(".git").exists() {
-        let parent_dir = admin_repo_dir.join(owner);
-        std::fs::create_dir_all(&parent_dir)?;
+        if let Some(parent_dir) = admin_repo_path.parent() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
 
         let ssh_available = ssh_agent_has_keys();
 
-        if ssh_available {
+        if provider == "git" {
+            // Plain git remotes are cloned from the URL as given; ssh-agent keys are offered if asked
+            clone_repo(provider_id, &admin_repo_path, strategy, None)
+                .map_err(|e| anyhow::anyhow!("Clone of {} failed: {}", provider_id, e))?;
+            log::info!(
+                "[ensure_admin_repo_and_fetch] Cloned {} ({} clone)",
+                provider_id,
+                strategy
+            );
+        } else if ssh_available {
             // Try SSH first
-            let ssh_url = match provider {
-                "github" => format!("git@github.com:{}/{}.git", owner, repo),
-                "gitlab" => {
-                    let instance_url = get_token_value("gitlab_instance_url")
-                        .ok()
-                        .flatten()
-                        .or_else(|| get_token_value("gitlab_endpoint").ok().flatten())
-                        .unwrap_or_else(|| "https://gitlab.com".to_string());
-
-                    let host = if let Ok(parsed) = reqwest::Url::parse(&instance_url) {
-                        parsed.host_str().unwrap_or("gitlab.com").to_string()
-                    } else {
-                        instance_url
-                            .trim_start_matches(|c: char| !c.is_alphanumeric() && c != '.')
-                            .trim_end_matches('/')
-                            .to_string()
-                    };
-
-                    format!("git@{}:{}/{}.git", host, owner, repo)
-                }
-                _ => anyhow::bail!("Unsupported provider: {}", provider),
-            };
-
-            match GitService::clone_repo(&ssh_url, &admin_repo_path) {
-                Ok(_) => log::info!("[ensure_admin_repo_and_fetch] Cloned {} via SSH", ssh_url),
+            let ssh_url =
+                crate::util::git::provider_ssh_url(&tokens, provider, provider_id, owner, repo)?;
+
+            match clone_repo(&ssh_url, &admin_repo_path, strategy, None) {
+                Ok(_) => log::info!(
+                    "[ensure_admin_repo_and_fetch] Cloned {} via SSH ({} clone)",
+                    ssh_url,
+                    strategy
+                ),
                 Err(ssh_err) => {
                     log::warn!("[ensure_admin_repo_and_fetch] SSH clone failed ({}), falling back to HTTPS", ssh_err);
-                    clone_with_https(provider, owner, repo, &admin_repo_path)?;
+                    clone_with_https(
+                        &tokens,
+                        provider,
+                        provider_id,
+                        &https_token,
+                        &admin_repo_path,
+                        strategy,
+                    )?;
                 }
             }
         } else {
@@ -183,7 +180,14 @@ async fn ensure_admin_repo_and_fetch(
             log::info!(
                 "[ensure_admin_repo_and_fetch] No SSH keys detected, using HTTPS authentication"
             );
-            clone_with_https(provider, owner, repo, &admin_repo_path)?;
+            clone_with_https(
+                &tokens,
+                provider,
+                provider_id,
+                &https_token,
+                &admin_repo_path,
+                strategy,
+            )?;
         }
     }
 
@@ -191,80 +195,246 @@ async fn ensure_admin_repo_and_fetch(
     let repository = GitService::open(&admin_repo_path)?;
     let ssh_available = ssh_agent_has_keys();
 
-    if ssh_available {
-        match GitService::fetch(
-            &repository,
-            "origin",
-            &["+refs/heads/*:refs/remotes/origin/*"],
-        ) {
+    if provider == "git" {
+        fetch_origin(&repository, strategy, None)?;
+    } else if ssh_available {
+        match fetch_origin(&repository, strategy, None) {
             Ok(_) => {}
             Err(ssh_err) => {
                 log::warn!(
                     "[ensure_admin_repo_and_fetch] SSH fetch failed ({}), falling back to HTTPS",
                     ssh_err
                 );
-                fetch_with_https(provider, &repository)?;
+                fetch_with_https(
+                    &tokens,
+                    provider,
+                    provider_id,
+                    &https_token,
+                    &repository,
+                    strategy,
+                )?;
             }
         }
     } else {
-        fetch_with_https(provider, &repository)?;
+        fetch_with_https(
+            &tokens,
+            provider,
+            provider_id,
+            &https_token,
+            &repository,
+            strategy,
+        )?;
     }
 
     Ok(admin_repo_path)
 }
 
-/// Clone repository using HTTPS with PAT authentication
-fn clone_with_https(provider: &str, owner: &str, repo: &str, path: &Path) -> Result<()> {
-    use crate::git::service::GitAuth;
+/// Clone with libgit2, or with the git CLI for partial and shallow clones
+fn clone_repo(
+    url: &str,
+    path: &Path,
+    strategy: CloneStrategy,
+    auth: Option<GitAuth<'_>>,
+) -> Result<()> {
+    match (strategy, auth) {
+        (CloneStrategy::Full, None) => {
+            GitService::clone_repo(url, path)?;
+        }
+        (CloneStrategy::Full, Some(auth)) => {
+            GitService::clone_repo_with_auth(url, path, auth)?;
+        }
+        (strategy, auth) => GitService::clone_repo_cli(url, path, strategy, auth)?,
+    }
+    Ok(())
+}
+
+/// Fetch every branch of origin; partial and shallow clones are fetched with the git CLI
+fn fetch_origin(
+    repo: &git2::Repository,
+    strategy: CloneStrategy,
+    auth: Option<GitAuth<'_>>,
+) -> Result<()> {
+    const REFSPECS: [&str; 1] = ["+refs/heads/*:refs/remotes/origin/*"];
+
+    if GitService::needs_cli(repo) {
+        let repo_path = repo.workdir().unwrap_or_else(|| repo.path());
+        return GitService::fetch_cli(repo_path, "origin", &REFSPECS, strategy, auth);
+    }
+    match auth {
+        Some(auth) => GitService::fetch_with_auth(repo, "origin", &REFSPECS, auth)?,
+        None => GitService::fetch(repo, "origin", &REFSPECS)?,
+    }
+    Ok(())
+}
+
+/// Record that a repository's admin clone was just fetched, for least-recently-used eviction
+fn touch_admin_clone(app: &tauri::AppHandle, repository_id: &str) {
+    let store = app.state::<Mutex<Store>>();
+    let result = store.lock().unwrap().touch_repository_clone(repository_id);
+    if let Err(e) = result {
+        log::warn!(
+            "[executor] Failed to record clone use of {}: {}",
+            repository_id,
+            e
+        );
+    }
+}
+
+/// Move a renamed or transferred repository's admin clone to the path of its new provider_id,
+/// re-point origin at the new path and repair the links of its execution worktrees
+/// `can_move` is asked while the clone's locks are held, so nothing starts using the clone in
+/// between; when it says no, the clone stays put and false is returned
+/// Nothing to do when the repository was never cloned
+pub(crate) fn move_admin_clone(
+    tokens: &AllTokens,
+    admin_repo_dir: &Path,
+    old_provider_id: &str,
+    new_provider_id: &str,
+    can_move: impl FnOnce() -> Result<bool>,
+) -> Result<bool> {
+    let (old_owner, old_repo) = parse_provider_id(tokens, old_provider_id)?;
+    let (new_owner, new_repo) = parse_provider_id(tokens, new_provider_id)?;
+    let (old_clone_owner, old_clone_repo) = clone_owner_repo(tokens, old_provider_id)?;
+    let (new_clone_owner, new_clone_repo) = clone_owner_repo(tokens, new_provider_id)?;
+
+    let old_path = repo_path_in(admin_repo_dir, &old_clone_owner, &old_clone_repo);
+    let new_path = repo_path_in(admin_repo_dir, &new_clone_owner, &new_clone_repo);
+    if old_path == new_path {
+        return Ok(true);
+    }
+
+    // Locked in a fixed order, so a rename racing one back the other way can't deadlock
+    let mut keys = [
+        (old_clone_owner.as_str(), old_clone_repo.as_str()),
+        (new_clone_owner.as_str(), new_clone_repo.as_str()),
+    ];
+    keys.sort();
+    let first_lock = get_repo_lock(keys[0].0, keys[0].1);
+    let _first_lock = first_lock.lock().unwrap();
+    let second_lock = get_repo_lock(keys[1].0, keys[1].1);
+    let _second_lock = second_lock.lock().unwrap();
+
+    if !can_move()? {
+        return Ok(false);
+    }
+    if !old_path.join(".git").exists() {
+        return Ok(true);
+    }
+    if new_path.exists() {
+        anyhow::bail!(
+            "Cannot move the clone of {} to {}: {} already exists",
+            old_provider_id,
+            new_provider_id,
+            new_path.display()
+        );
+    }
+
+    if let Some(parent_dir) = new_path.parent() {
+        std::fs::create_dir_all(parent_dir)?;
+    }
+    std::fs::rename(&old_path, &new_path)?;
+    log::info!(
+        "[move_admin_clone] Moved {} to {}",
+        old_path.display(),
+        new_path.display()
+    );
+
+    // Drop the old owner's directories once empty
+    crate::maintenance::remove_empty_parents(&old_path, admin_repo_dir);
+
+    // The remote keeps working through the provider's redirect, until the old name is reused
+    let repository = GitService::open(&new_path)?;
+    if let Some(url) = repository.find_remote("origin")?.url().map(str::to_string) {
+        let old_suffix = format!("{}/{}", old_owner, old_repo);
+        if let Some(index) = url.rfind(&old_suffix) {
+            let new_url = format!(
+                "{}{}/{}{}",
+                &url[..index],
+                new_owner,
+                new_repo,
+                &url[index + old_suffix.len()..]
+            );
+            repository.remote_set_url("origin", &new_url)?;
+        }
+    }
+
+    // Worktrees keep their paths, but their .git files still point into the old clone
+    let output = Command::new("git")
+        .args(["worktree", "repair"])
+        .current_dir(&new_path)
+        .output()?;
+    if !output.status.success() {
+        log::warn!(
+            "[move_admin_clone] Failed to repair worktrees of {}: {}",
+            new_provider_id,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(true)
+}
+
+/// Clone repository using HTTPS with token authentication
+fn clone_with_https(
+    tokens: &AllTokens,
+    provider: &str,
+    provider_id: &str,
+    token: &Result<String, String>,
+    path: &Path,
+    strategy: CloneStrategy,
+) -> Result<()> {
     use crate::util::git::build_https_remote;
 
-    let remote_config = build_https_remote(provider, owner, repo)?;
-    let token = resolve_provider_token(provider)?;
+    let remote_config = build_https_remote(tokens, provider, provider_id)?;
+    let token = token.as_deref().map_err(|e| anyhow::anyhow!("{}", e))?;
     let auth = GitAuth {
-        username: remote_config.username,
-        password: &token,
+        username: &remote_config.username,
+        password: token,
     };
 
-    GitService::clone_repo_with_auth(&remote_config.url, path, auth)
+    clone_repo(&remote_config.url, path, strategy, Some(auth))
         .map_err(|e| anyhow::anyhow!("HTTPS clone failed: {}", e))?;
 
     Ok(())
 }
 
-/// Fetch from repository using HTTPS with PAT authentication
-fn fetch_with_https(provider: &str, repo: &git2::Repository) -> Result<()> {
-    use crate::git::service::GitAuth;
-
-    let token = resolve_provider_token(provider)?;
+/// Fetch from repository using HTTPS with token authentication
+fn fetch_with_https(
+    tokens: &AllTokens,
+    provider: &str,
+    provider_id: &str,
+    token: &Result<String, String>,
+    repo: &git2::Repository,
+    strategy: CloneStrategy,
+) -> Result<()> {
+    let token = token.as_deref().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let username = crate::util::git::https_username(tokens, provider, provider_id)?;
     let auth = GitAuth {
-        username: "oauth2",
-        password: &token,
+        username: &username,
+        password: token,
     };
 
-    GitService::fetch_with_auth(
-        repo,
-        "origin",
-        &["+refs/heads/*:refs/remotes/origin/*"],
-        auth,
-    )
-    .map_err(|e| anyhow::anyhow!("HTTPS fetch failed: {}", e))?;
+    fetch_origin(repo, strategy, Some(auth))
+        .map_err(|e| anyhow::anyhow!("HTTPS fetch failed: {}", e))?;
 
     Ok(())
 }
 
-/// Push to repository using HTTPS with PAT authentication
+/// Push to repository using HTTPS with token authentication
 fn push_with_https(
+    tokens: &AllTokens,
     provider: &str,
+    provider_id: &str,
+    token: &Result<String, String>,
     repo: &git2::Repository,
     branch: &str,
     force: bool,
 ) -> Result<()> {
-    use crate::git::service::GitAuth;
-
-    let token = resolve_provider_token(provider)?;
+    let token = token.as_deref().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let username = crate::util::git::https_username(tokens, provider, provider_id)?;
     let auth = GitAuth {
-        username: "oauth2",
-        password: &token,
+        username: &username,
+        password: token,
     };
 
     GitService::push_branch_with_auth(repo, "origin", branch, force, auth)
@@ -273,6 +443,163 @@ fn push_with_https(
     Ok(())
 }
 
+/// How old synced repository metadata may get before an execution refreshes it
+const METADATA_MAX_AGE_MS: i64 = 60 * 60 * 1000;
+
+/// Repository with its provider metadata refreshed when missing or stale
+/// Best effort: on failure the stored metadata is used as is
+async fn fresh_repository(app: &tauri::AppHandle, repository: Repository) -> Repository {
+    let now = chrono::Utc::now().timestamp_millis();
+    if repository
+        .last_synced_at
+        .is_some_and(|synced_at| now - synced_at < METADATA_MAX_AGE_MS)
+    {
+        return repository;
+    }
+
+    let store = app.state::<Mutex<Store>>();
+    let paths = app.state::<Paths>();
+    match super::db::refresh_repository_metadata(&store, &paths, &repository).await {
+        Ok(refreshed) => refreshed,
+        Err(e) => {
+            log::warn!(
+                "[executor] Could not refresh metadata of {}: {}",
+                repository.provider_id,
+                e
+            );
+            repository
+        }
+    }
+}
+
+/// Why a repository can't take a pull request at all, if it can't
+fn repository_skip_reason(repository: &Repository) -> Option<String> {
+    if repository.archived {
+        Some("Repository is archived".to_string())
+    } else if repository.disabled {
+        Some("Repository is disabled".to_string())
+    } else if repository.can_push == Some(false) {
+        Some("No push access to the repository".to_string())
+    } else {
+        None
+    }
+}
+
+/// Why a repository fails its promptset's applicability check at the default branch, if it does
+/// `credentials` let a partial admin clone fetch the files the check searches
+fn applicability_skip_reason(
+    app: &tauri::AppHandle,
+    promptset_id: &str,
+    admin_repo_path: &Path,
+    default_branch: &str,
+    credentials: Option<&(String, String)>,
+) -> Result<Option<String>> {
+    let promptset = {
+        let store_state = app.state::<Mutex<Store>>();
+        let store = store_state.lock().unwrap();
+        store
+            .get_promptset(promptset_id)?
+            .ok_or_else(|| anyhow::anyhow!("PromptSet {} not found", promptset_id))?
+    };
+
+    let Some(check) = ApplicabilityCheck::new(
+        promptset.applicability_pattern.as_deref(),
+        promptset.applicability_path_glob.as_deref(),
+    )?
+    else {
+        return Ok(None);
+    };
+
+    let repo = GitService::open(admin_repo_path)?;
+    let auth = credentials.map(|(username, password)| GitAuth { username, password });
+    check.skip_reason(&repo, &format!("origin/{}", default_branch), auth.as_ref())
+}
+
+/// Finish an execution as skipped, without running it
+fn skip_execution(app: &tauri::AppHandle, execution_id: &str, reason: &str) -> Result<()> {
+    log::info!("[executor] Skipping execution {}: {}", execution_id, reason);
+    {
+        let store_state = app.state::<Mutex<Store>>();
+        let store = store_state.lock().unwrap();
+        store.update_execution(
+            execution_id,
+            ExecutionUpdates {
+                status: Some(ExecutionStatus::Skipped),
+                skip_reason: Some(reason.to_string()),
+                completed_at: Some(chrono::Utc::now().timestamp_millis()),
+                ..Default::default()
+            },
+        )?;
+    }
+    emit_execution_status(app, execution_id, "skipped");
+    Ok(())
+}
+
+/// How a repository is cloned and its execution worktrees checked out, combining the
+/// repository's settings with its promptset's
+struct CheckoutPlan {
+    clone_strategy: CloneStrategy,
+    /// `git sparse-checkout set` arguments; None checks out every file
+    sparse_args: Option<Vec<String>>,
+    /// HTTPS username and token for the blobs a partial clone fetches during checkout
+    credentials: Option<(String, String)>,
+}
+
+fn checkout_plan(
+    app: &tauri::AppHandle,
+    repository: &Repository,
+    promptset_id: &str,
+) -> Result<CheckoutPlan> {
+    let promptset = {
+        let store_state = app.state::<Mutex<Store>>();
+        let store = store_state.lock().unwrap();
+        store
+            .get_promptset(promptset_id)?
+            .ok_or_else(|| anyhow::anyhow!("PromptSet {} not found", promptset_id))?
+    };
+
+    Ok(CheckoutPlan {
+        clone_strategy: effective_clone_strategy(
+            repository.clone_strategy.as_deref(),
+            promptset.clone_strategy.as_deref(),
+        ),
+        sparse_args: sparse_checkout_args(&repository.sparse_checkout, &promptset.sparse_checkout),
+        credentials: None,
+    })
+}
+
+/// Credentials for the objects a partial admin clone with an HTTPS origin fetches on demand,
+/// e.g. during worktree checkout; SSH remotes are authenticated by ssh-agent, and full clones
+/// never fetch on demand
+pub(crate) async fn lazy_fetch_credentials(
+    repository: &Repository,
+    admin_repo_path: &Path,
+) -> Option<(String, String)> {
+    if repository.provider == "git" {
+        return None;
+    }
+    let https_origin = {
+        let repo = GitService::open(admin_repo_path).ok()?;
+        let remote = repo.find_remote("origin").ok()?;
+        GitService::needs_cli(&repo)
+            && remote
+                .url()
+                .is_some_and(|url| url.starts_with("https://") || url.starts_with("http://"))
+    };
+    if !https_origin {
+        return None;
+    }
+
+    let tokens = cached_tokens().ok()?;
+    let token = resolve_provider_token(&tokens, &repository.provider, &repository.provider_id)
+        .await
+        .ok()?;
+    let username =
+        crate::util::git::https_username(&tokens, &repository.provider, &repository.provider_id)
+            .ok()?;
+    Some((username, token))
+}
+
 struct WorktreeInfo {
     worktree_path: PathBuf,
     branch_name: String,
@@ -286,6 +613,7 @@ async fn add_worktree(
     revision_id: &str,
     execution_id: &str,
     default_branch: &str,
+    checkout: &CheckoutPlan,
 ) -> Result<WorktreeInfo> {
     let branch_name = maestro_branch_name(promptset_id, revision_id, execution_id);
     let worktree_path = worktree_path(worktree_dir, promptset_id, execution_id);
@@ -323,10 +651,20 @@ async fn add_worktree(
         let _ = GitService::delete_local_branch(&repo, &branch_name, true);
     }
 
-    let output = Command::new("git")
+    let auth = checkout
+        .credentials
+        .as_ref()
+        .map(|(username, password)| GitAuth { username, password });
+
+    // A sparse worktree is only checked out once its patterns are set, so files outside them
+    // are never written, nor downloaded into a partial clone
+    let mut worktree_add = git_command(auth.as_ref());
+    worktree_add.args(["worktree", "add"]);
+    if checkout.sparse_args.is_some() {
+        worktree_add.arg("--no-checkout");
+    }
+    let output = worktree_add
         .args([
-            "worktree",
-            "add",
             "-b",
             &branch_name,
             worktree_path.to_str().unwrap(),
@@ -342,6 +680,30 @@ async fn add_worktree(
         );
     }
 
+    if let Some(sparse_args) = &checkout.sparse_args {
+        let output = Command::new("git")
+            .args(sparse_args)
+            .current_dir(&worktree_path)
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to set sparse checkout: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let output = git_command(auth.as_ref())
+            .args(["reset", "--quiet", "--hard"])
+            .current_dir(&worktree_path)
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to check out worktree: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+    }
+
     let repo = GitService::open(&worktree_path)?;
     let base_commit = GitService::rev_parse(&repo, "HEAD")?;
 
@@ -645,31 +1007,91 @@ pub(crate) async fn execute_with_amp(
     Ok((session_id, result_message))
 }
 
-#[tauri::command]
-pub async fn execute_promptset(
-    promptset_id: String,
-    revision_id: String,
+/// Repositories a revision run targets: the requested ones (default all) of the promptset
+/// With a saved repository query, the query is re-resolved first: repositories that no longer
+/// match are skipped, and new matches run unless specific repositories were requested
+/// Repositories matching the promptset's tag expression when the run starts are added
+async fn target_repository_ids(
+    app: &tauri::AppHandle,
+    promptset_id: &str,
+    revision_id: &str,
     repository_ids: Option<Vec<String>>,
-    app: tauri::AppHandle,
-    paths: tauri::State<'_, Paths>,
 ) -> Result<Vec<String>, String> {
     let promptset = {
         let store_state = app.state::<Mutex<Store>>();
         let store = store_state.lock().map_err(|e| e.to_string())?;
         store
-            .get_promptset(&promptset_id)
+            .get_promptset(promptset_id)
             .map_err(|e| e.to_string())?
             .ok_or_else(|| format!("PromptSet {} not found", promptset_id))?
     };
 
-    let repo_ids = if let Some(ids) = repository_ids {
-        ids.into_iter()
-            .filter(|id| promptset.repository_ids.contains(id))
-            .collect()
-    } else {
-        promptset.repository_ids.clone()
+    let mut target_ids = match promptset.repository_query.clone() {
+        None => match &repository_ids {
+            Some(ids) => ids
+                .iter()
+                .filter(|id| promptset.repository_ids.contains(id))
+                .cloned()
+                .collect(),
+            None => promptset.repository_ids.clone(),
+        },
+        Some(query) => {
+            let snapshot = crate::commands::sourcegraph::resolve_repository_query(
+                app,
+                &promptset,
+                revision_id,
+                &query,
+            )
+            .await?;
+
+            // The promptset's repositories that weren't skipped, then the new matches
+            let resolved: Vec<String> = promptset
+                .repository_ids
+                .iter()
+                .filter(|id| !snapshot.skipped_repository_ids.contains(id))
+                .chain(&snapshot.added_repository_ids)
+                .cloned()
+                .collect();
+            // New matches join the promptset and the snapshot, but only run when requested
+            match &repository_ids {
+                Some(ids) => resolved.into_iter().filter(|id| ids.contains(id)).collect(),
+                None => resolved,
+            }
+        }
     };
 
+    // Repositories matching the tag expression right now are targeted too
+    let tagged_ids = {
+        let store_state = app.state::<Mutex<Store>>();
+        let store = store_state.lock().map_err(|e| e.to_string())?;
+        crate::commands::tags::repository_ids_matching(
+            &store,
+            promptset.repository_tag_expression.as_deref(),
+        )?
+        .unwrap_or_default()
+    };
+    for id in tagged_ids {
+        let excluded = repository_ids
+            .as_ref()
+            .is_some_and(|ids| !ids.contains(&id));
+        if !excluded && !target_ids.contains(&id) {
+            target_ids.push(id);
+        }
+    }
+
+    Ok(target_ids)
+}
+
+#[tauri::command]
+pub async fn execute_promptset(
+    promptset_id: String,
+    revision_id: String,
+    repository_ids: Option<Vec<String>>,
+    app: tauri::AppHandle,
+    paths: tauri::State<'_, Paths>,
+) -> Result<Vec<String>, String> {
+    let repo_ids = target_repository_ids(&app, &promptset_id, &revision_id, repository_ids).await?;
+
     let mut execution_ids = Vec::new();
 
     for repository_id in repo_ids {
@@ -705,22 +1127,7 @@ pub async fn prepare_executions(
     app: tauri::AppHandle,
     paths: tauri::State<'_, Paths>,
 ) -> Result<Vec<String>, String> {
-    let promptset = {
-        let store_state = app.state::<Mutex<Store>>();
-        let store = store_state.lock().map_err(|e| e.to_string())?;
-        store
-            .get_promptset(&promptset_id)
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| format!("PromptSet {} not found", promptset_id))?
-    };
-
-    let repo_ids = if let Some(ids) = repository_ids {
-        ids.into_iter()
-            .filter(|id| promptset.repository_ids.contains(id))
-            .collect()
-    } else {
-        promptset.repository_ids.clone()
-    };
+    let repo_ids = target_repository_ids(&app, &promptset_id, &revision_id, repository_ids).await?;
 
     let mut execution_ids = Vec::new();
 
@@ -768,11 +1175,27 @@ async fn prepare_execution_impl(
         (execution, repository)
     };
 
-    let (owner, repo) = parse_provider_id(&repository.provider_id)?;
+    // Archived and read-only repositories would only fail at push time
+    let repository = fresh_repository(&app, repository).await;
+    if let Some(reason) = repository_skip_reason(&repository) {
+        return skip_execution(&app, &execution_id, &reason);
+    }
 
-    let admin_repo_path =
-        ensure_admin_repo_and_fetch(&paths.admin_repo_dir, &repository.provider, &owner, &repo)
-            .await?;
+    let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
+    let (owner, repo) = parse_provider_id(&tokens, &repository.provider_id)?;
+    let mut checkout = checkout_plan(&app, &repository, &execution.promptset_id)?;
+
+    let admin_repo_path = ensure_admin_repo_and_fetch(
+        &paths.admin_repo_dir,
+        &repository.provider,
+        &repository.provider_id,
+        &owner,
+        &repo,
+        checkout.clone_strategy,
+    )
+    .await?;
+    touch_admin_clone(&app, &repository.id);
+    checkout.credentials = lazy_fetch_credentials(&repository, &admin_repo_path).await;
 
     // Use cached default branch or fetch from provider if not cached
     let default_branch = if let Some(cached_branch) = &repository.default_branch {
@@ -785,6 +1208,16 @@ async fn prepare_execution_impl(
         branch
     };
 
+    if let Some(reason) = applicability_skip_reason(
+        &app,
+        &execution.promptset_id,
+        &admin_repo_path,
+        &default_branch,
+        checkout.credentials.as_ref(),
+    )? {
+        return skip_execution(&app, &execution_id, &reason);
+    }
+
     let worktree_info = add_worktree(
         &admin_repo_path,
         &paths.worktree_dir,
@@ -792,6 +1225,7 @@ async fn prepare_execution_impl(
         &execution.revision_id,
         &execution_id,
         &default_branch,
+        &checkout,
     )
     .await?;
 
@@ -880,9 +1314,19 @@ pub(crate) async fn execute_prompt_impl(
     }
 
     let result = async {
-    let (owner, repo) = parse_provider_id(&repository.provider_id)?;
+    // Archived and read-only repositories would only fail at push time
+    let repository = fresh_repository(&app, repository).await;
+    if let Some(reason) = repository_skip_reason(&repository) {
+        return skip_execution(&app, &execution_id, &reason);
+    }
+
+    let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
+    let (owner, repo) = parse_provider_id(&tokens, &repository.provider_id)?;
+    let mut checkout = checkout_plan(&app, &repository, &execution.promptset_id)?;
 
-    let admin_repo_path = ensure_admin_repo_and_fetch(&paths.admin_repo_dir, &repository.provider, &owner, &repo).await?;
+    let admin_repo_path = ensure_admin_repo_and_fetch(&paths.admin_repo_dir, &repository.provider, &repository.provider_id, &owner, &repo, checkout.clone_strategy).await?;
+    touch_admin_clone(&app, &repository.id);
+    checkout.credentials = lazy_fetch_credentials(&repository, &admin_repo_path).await;
 
     // Use cached default branch or fetch from provider if not cached
 	let default_branch = if let Some(cached_branch) = &repository.default_branch {
@@ -896,6 +1340,11 @@ pub(crate) async fn execute_prompt_impl(
 			branch
 		};
 
+		// Don't spend agent tokens on repositories the promptset doesn't apply to
+		if let Some(reason) = applicability_skip_reason(&app, &execution.promptset_id, &admin_repo_path, &default_branch, checkout.credentials.as_ref())? {
+			return skip_execution(&app, &execution_id, &reason);
+		}
+
 		let worktree_info = add_worktree(
 			&admin_repo_path,
 			&paths.worktree_dir,
@@ -903,6 +1352,7 @@ pub(crate) async fn execute_prompt_impl(
 			&execution.revision_id,
 			&execution_id,
 			&default_branch,
+			&checkout,
 		)
 		.await?;
 
@@ -1150,9 +1600,9 @@ async fn validate_execution_impl(
     }
 
     let result = async {
-		let (owner, repo) = parse_provider_id(&repository.provider_id)?;
+		let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
 
-		let _admin_repo_path = admin_repo_path(&paths, &owner, &repo);
+		let _admin_repo_path = admin_repo_path(&paths, &tokens, &repository.provider_id)?;
 		let worktree_path = execution_worktree_path(&paths, &execution.promptset_id, &execution_id);
 
 		let branch_name = maestro_branch_name(&execution.promptset_id, &execution.revision_id, &execution_id);
@@ -1388,10 +1838,13 @@ async fn resume_execution_impl(
 
     let result = async {
     log::info!("[resume_execution] Parsing provider ID for {}", execution_id);
-    let (owner, repo) = parse_provider_id(&repository.provider_id)?;
+    let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
+    let (owner, repo) = parse_provider_id(&tokens, &repository.provider_id)?;
+    let mut checkout = checkout_plan(&app, &repository, &execution.promptset_id)?;
 
     log::info!("[resume_execution] Ensuring admin repo and fetching for {}/{}", owner, repo);
-    let admin_repo_path = ensure_admin_repo_and_fetch(&paths.admin_repo_dir, &repository.provider, &owner, &repo).await?;
+    let admin_repo_path = ensure_admin_repo_and_fetch(&paths.admin_repo_dir, &repository.provider, &repository.provider_id, &owner, &repo, checkout.clone_strategy).await?;
+    touch_admin_clone(&app, &repository.id);
     let worktree_path = execution_worktree_path(&paths, &execution.promptset_id, &execution_id);
 
     let branch_name = maestro_branch_name(&execution.promptset_id, &execution.revision_id, &execution_id);
@@ -1409,6 +1862,7 @@ async fn resume_execution_impl(
     };
 
     log::info!("[resume_execution] Creating worktree on branch {} for {}", default_branch, execution_id);
+    checkout.credentials = lazy_fetch_credentials(&repository, &admin_repo_path).await;
     let worktree_info = add_worktree(
     &admin_repo_path,
     &paths.worktree_dir,
@@ -1416,6 +1870,7 @@ async fn resume_execution_impl(
     &execution.revision_id,
     &execution_id,
     &default_branch,
+    &checkout,
     )
     .await?;
 
@@ -1753,23 +2208,34 @@ pub async fn push_commit(
     emit_execution_progress(&app, &execution_id, "Pushing commit to remote...");
 
     // Get provider info for potential HTTPS fallback
-    let provider_name = {
+    let (provider_name, provider_id) = {
         let store_state = app.state::<Mutex<Store>>();
         let store = store_state.lock().unwrap();
         let repository = store
             .get_repository(&repository_id)
             .map_err(|e| e.to_string())?
             .ok_or_else(|| format!("Repository {} not found", repository_id))?;
-        repository.provider.clone()
+        (repository.provider.clone(), repository.provider_id.clone())
     };
 
+    // Credentials for the HTTPS fallback (a GitHub App installation token is minted on demand)
+    let tokens = cached_tokens()?;
+    let https_token = resolve_provider_token(&tokens, &provider_name, &provider_id)
+        .await
+        .map_err(|e| e.to_string());
+
     // Push the branch
     let worktree_path = execution_worktree_path(&paths, &promptset_id, &execution_id);
     let repo = GitService::open(&worktree_path).map_err(|e| e.to_string())?;
 
     let ssh_available = ssh_agent_has_keys();
 
-    if ssh_available {
+    if provider_name == "git" {
+        // Plain git remotes have no token to fall back to
+        GitService::push_branch(&repo, "origin", &branch, force)
+            .map_err(|e| format!("Push failed: {}", e))?;
+        emit_execution_progress(&app, &execution_id, "Push completed successfully");
+    } else if ssh_available {
         match GitService::push_branch(&repo, "origin", &branch, force) {
             Ok(_) => {
                 emit_execution_progress(&app, &execution_id, "Push completed successfully via SSH");
@@ -1784,8 +2250,16 @@ pub async fn push_commit(
                     &execution_id,
                     "SSH push failed, retrying with HTTPS...",
                 );
-                push_with_https(&provider_name, &repo, &branch, force)
-                    .map_err(|e| format!("HTTPS push also failed: {}", e))?;
+                push_with_https(
+                    &tokens,
+                    &provider_name,
+                    &provider_id,
+                    &https_token,
+                    &repo,
+                    &branch,
+                    force,
+                )
+                .map_err(|e| format!("HTTPS push also failed: {}", e))?;
                 emit_execution_progress(
                     &app,
                     &execution_id,
@@ -1799,8 +2273,16 @@ pub async fn push_commit(
             &execution_id,
             "Using HTTPS authentication for push...",
         );
-        push_with_https(&provider_name, &repo, &branch, force)
-            .map_err(|e| format!("HTTPS push failed: {}", e))?;
+        push_with_https(
+            &tokens,
+            &provider_name,
+            &provider_id,
+            &https_token,
+            &repo,
+            &branch,
+            force,
+        )
+        .map_err(|e| format!("HTTPS push failed: {}", e))?;
         emit_execution_progress(&app, &execution_id, "Push completed successfully via HTTPS");
     }
 
@@ -1808,27 +2290,32 @@ pub async fn push_commit(
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
     // Start CI checking automatically after push using CiProvider
-    let (provider_name, provider_id) = {
+    let (repository, has_ci_override) = {
         let store_state = app.state::<Mutex<Store>>();
         let store = store_state.lock().unwrap();
         let repository = store
             .get_repository(&repository_id)
             .map_err(|e| e.to_string())?
             .ok_or_else(|| format!("Repository {} not found", repository_id))?;
+        let has_ci_override = store
+            .get_repository_ci_config(&repository_id)
+            .map_err(|e| e.to_string())?
+            .is_some();
 
-        (repository.provider.clone(), repository.provider_id.clone())
+        (repository, has_ci_override)
     };
 
+    if !crate::ci::provider::repository_has_ci(&repository, has_ci_override) {
+        return crate::commands::ci::record_ci_not_configured(&app, &execution_id);
+    }
+
     // Create CI provider using the provider trait
-    if let Ok(provider) =
-        crate::ci::provider::create_ci_provider(&provider_name, &provider_id).await
+    let store_state = app.state::<Mutex<Store>>();
+    if let Ok((provider, provider_cfg)) =
+        crate::ci::provider::create_repository_ci_provider(&store_state, &repository).await
     {
         use crate::ci::CiContext;
 
-        // Build provider configuration
-        let provider_cfg = crate::util::git::build_provider_cfg(&provider_name, &provider_id)
-            .map_err(|e| e.to_string())?;
-
         let ctx = CiContext {
             commit_sha: commit_sha.clone(),
             branch: branch.clone(),
@@ -1838,13 +2325,25 @@ pub async fn push_commit(
         // Check if CI is configured by polling once
         let ci_url = provider.get_commit_url(&ctx).map_err(|e| e.to_string())?;
         let ci_status = match provider.poll(&ctx).await {
+            Ok(checks) if checks.is_empty() && has_ci_override => {
+                // CI explicitly configured for the repository (e.g. Jenkins), builds may start later
+                use crate::types::CiStatus;
+                CiStatus::Pending
+            }
             Ok(checks) if checks.is_empty() => {
                 // No CI configured
                 use crate::types::CiStatus;
                 CiStatus::NotConfigured
             }
-            Ok(_checks) => {
+            Ok(checks) => {
                 // CI exists, set to pending
+                crate::ci::record_checks(&app, &execution_id, &commit_sha, &checks);
+                use crate::types::CiStatus;
+                CiStatus::Pending
+            }
+            Err(e) if crate::util::rate_limit::as_rate_limited(&e).is_some() => {
+                // Out of API budget - the watcher checks again once it recovers
+                log::info!("[push_commit] Deferring CI check @ {}: {}", commit_sha, e);
                 use crate::types::CiStatus;
                 CiStatus::Pending
             }
@@ -1881,12 +2380,91 @@ pub async fn push_commit(
                     },
                 )
                 .map_err(|e| e.to_string())?;
+
+            // Keep polling in the background until CI finishes
+            if ci_status == crate::types::CiStatus::Pending {
+                crate::ci::watch_execution(&store, &execution_id, &commit_sha)
+                    .map_err(|e| e.to_string())?;
+            }
         }
     }
 
     Ok(())
 }
 
+/// Open a pull request from a pushed execution branch into the repository's default branch
+#[tauri::command]
+pub async fn create_pull_request(
+    execution_id: String,
+    title: String,
+    description: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<crate::git::PullRequest, String> {
+    use crate::git::git_provider::create_git_provider;
+    use crate::git::{GitProviderContext, NewPullRequest};
+
+    let (execution, repository) = {
+        let store_state = app.state::<Mutex<Store>>();
+        let store = store_state.lock().unwrap();
+        let execution = store
+            .get_execution(&execution_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Execution {} not found", execution_id))?;
+        let repository = store
+            .get_repository(&execution.repository_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Repository {} not found", execution.repository_id))?;
+        (execution, repository)
+    };
+
+    if execution.commit_status != CommitStatus::Committed {
+        return Err(
+            "Execution must be committed and pushed before opening a pull request".to_string(),
+        );
+    }
+    let source_branch = execution
+        .branch
+        .ok_or_else(|| "No branch found for execution".to_string())?;
+
+    let target_branch = match repository.default_branch {
+        Some(branch) => branch,
+        None => fetch_default_branch(&repository.provider, &repository.provider_id)
+            .await
+            .map_err(|e| format!("Failed to fetch default branch: {}", e))?,
+    };
+
+    let git_provider = create_git_provider(&repository.provider, &repository.provider_id)
+        .await
+        .map_err(|e| format!("Failed to create git provider: {}", e))?;
+    let ctx = GitProviderContext {
+        provider_cfg: crate::util::git::build_provider_cfg(
+            &cached_tokens()?,
+            &repository.provider,
+            &repository.provider_id,
+        )
+        .map_err(|e| format!("Failed to build provider config: {}", e))?,
+    };
+
+    git_provider
+        .create_pull_request(
+            &ctx,
+            &NewPullRequest {
+                source_branch,
+                target_branch,
+                title,
+                description,
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to create pull request: {}", e))?
+        .ok_or_else(|| {
+            format!(
+                "Creating pull requests is not supported for {} repositories",
+                git_provider.display_name()
+            )
+        })
+}
+
 #[tauri::command]
 pub fn stop_execution(execution_id: String, app: tauri::AppHandle) -> Result<bool, String> {
     let exec_key = format!("exec:{}", execution_id);
@@ -2016,28 +2594,13 @@ pub fn reconcile_on_startup(store: &Store) -> Result<()> {
             )?;
         }
 
-        // Reset stuck pending CI checks (no active tracking, so check age)
+        // Resume CI watching for pending executions; the watcher decides when to give up,
+        // and a watch it gave up on stays expired until reconcile_stuck_ci
         if execution.ci_status == Some(CiStatus::Pending) {
-            if let Some(checked_at) = execution.ci_checked_at {
-                let threshold_minutes = store.get_ci_stuck_threshold_minutes().unwrap_or(10);
-                let now = chrono::Utc::now().timestamp_millis();
-                let age_minutes = (now - checked_at) / 1000 / 60;
-
-                // If stuck for more than threshold, mark as skipped
-                if age_minutes > threshold_minutes {
-                    log::warn!(
-						"Resetting stuck CI check for execution {} (pending for {} minutes, threshold: {})",
-						execution.id,
-						age_minutes,
-						threshold_minutes
-					);
-                    store.update_execution(
-                        &execution.id,
-                        ExecutionUpdates {
-                            ci_status: Some(CiStatus::Skipped),
-                            ..Default::default()
-                        },
-                    )?;
+            if let Some(commit_sha) = execution.commit_sha.as_deref() {
+                if store.get_ci_watch(&execution.id)?.is_none() {
+                    log::info!("Resuming CI watch for execution {}", execution.id);
+                    crate::ci::watch_execution(store, &execution.id, commit_sha)?;
                 }
             }
         }
@@ -2047,45 +2610,27 @@ pub fn reconcile_on_startup(store: &Store) -> Result<()> {
 }
 
 /// Manually reconcile stuck CI checks across all executions
+/// Pending executions are polled right away, re-queuing any whose watch had expired
 #[tauri::command]
 pub fn reconcile_stuck_ci(app: tauri::AppHandle) -> Result<usize, String> {
     let store = app.state::<Mutex<Store>>();
     let store = store.lock().map_err(|e| e.to_string())?;
 
-    let threshold_minutes = store.get_ci_stuck_threshold_minutes().unwrap_or(10);
     let executions = store.get_all_executions().map_err(|e| e.to_string())?;
-    let mut fixed = 0;
+    let mut requeued = 0;
 
     for execution in executions {
-        // Reset stuck pending CI checks
-        if execution.ci_status == Some(CiStatus::Pending) {
-            if let Some(checked_at) = execution.ci_checked_at {
-                let now = chrono::Utc::now().timestamp_millis();
-                let age_minutes = (now - checked_at) / 1000 / 60;
-
-                if age_minutes > threshold_minutes {
-                    log::warn!(
-						"Resetting stuck CI check for execution {} (pending for {} minutes, threshold: {})",
-						execution.id,
-						age_minutes,
-						threshold_minutes
-					);
-                    store
-                        .update_execution(
-                            &execution.id,
-                            ExecutionUpdates {
-                                ci_status: Some(CiStatus::Skipped),
-                                ..Default::default()
-                            },
-                        )
-                        .map_err(|e| e.to_string())?;
-                    fixed += 1;
-                }
-            }
+        if execution.ci_status != Some(CiStatus::Pending) {
+            continue;
+        }
+        if let Some(commit_sha) = execution.commit_sha.as_deref() {
+            crate::ci::rewatch_execution(&store, &execution.id, commit_sha)
+                .map_err(|e| e.to_string())?;
+            requeued += 1;
         }
     }
 
-    Ok(fixed)
+    Ok(requeued)
 }
 
 #[tauri::command]
@@ -2162,9 +2707,9 @@ pub async fn cleanup_execution(
         )
     };
 
-    let (owner, repo) = parse_provider_id(&repository.provider_id).map_err(|e| e.to_string())?;
-
-    let admin_repo_path = admin_repo_path(&paths, &owner, &repo);
+    let tokens = cached_tokens()?;
+    let admin_repo_path =
+        admin_repo_path(&paths, &tokens, &repository.provider_id).map_err(|e| e.to_string())?;
 
     remove_worktree(
         &admin_repo_path,
@@ -2206,9 +2751,9 @@ pub fn get_execution_modified_files(
             .map_err(|e| e.to_string())?
             .ok_or_else(|| "Repository not found".to_string())?;
 
-        let (owner, repo_name) =
-            parse_provider_id(&repository.provider_id).map_err(|e| e.to_string())?;
-        let admin_repo_path = admin_repo_path(&paths, &owner, &repo_name);
+        let tokens = cached_tokens()?;
+        let admin_repo_path =
+            admin_repo_path(&paths, &tokens, &repository.provider_id).map_err(|e| e.to_string())?;
 
         return get_committed_diff(
             &admin_repo_path,
@@ -2253,9 +2798,9 @@ pub fn get_execution_file_diff(
             .map_err(|e| e.to_string())?
             .ok_or_else(|| "Repository not found".to_string())?;
 
-        let (owner, repo_name) =
-            parse_provider_id(&repository.provider_id).map_err(|e| e.to_string())?;
-        let admin_repo_path = admin_repo_path(&paths, &owner, &repo_name);
+        let tokens = cached_tokens()?;
+        let admin_repo_path =
+            admin_repo_path(&paths, &tokens, &repository.provider_id).map_err(|e| e.to_string())?;
 
         return get_committed_file_diff(
             &admin_repo_path,
@@ -2272,3 +2817,92 @@ pub fn get_execution_file_diff(
 
     get_worktree_file_diff(&worktree_path, &file).map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_git::git;
+
+    #[test]
+    fn test_move_admin_clone() {
+        let root = std::env::temp_dir().join(format!("maestro-move-{}", uuid::Uuid::new_v4()));
+        let admin_repo_dir = root.join("repos");
+        let old_path = repo_path_in(&admin_repo_dir, "acme", "gadgets");
+        std::fs::create_dir_all(&old_path).unwrap();
+        git(&old_path, &["init", "-q", "-b", "main"]);
+        std::fs::write(old_path.join("README.md"), "gadgets\n").unwrap();
+        git(&old_path, &["add", "."]);
+        git(&old_path, &["commit", "-q", "-m", "Initial commit"]);
+        git(
+            &old_path,
+            &[
+                "remote",
+                "add",
+                "origin",
+                "https://github.com/acme/gadgets.git",
+            ],
+        );
+        let worktree = root.join("worktrees").join("execution");
+        git(
+            &old_path,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "maestro/abc",
+                worktree.to_str().unwrap(),
+            ],
+        );
+
+        // An execution started before the locks were taken keeps the clone where it is
+        assert!(!move_admin_clone(
+            &AllTokens::default(),
+            &admin_repo_dir,
+            "acme/gadgets",
+            "acme-corp/widgets",
+            || Ok(false),
+        )
+        .unwrap());
+        assert!(old_path.join(".git").exists());
+
+        assert!(move_admin_clone(
+            &AllTokens::default(),
+            &admin_repo_dir,
+            "acme/gadgets",
+            "acme-corp/widgets",
+            || Ok(true),
+        )
+        .unwrap());
+
+        let new_path = repo_path_in(&admin_repo_dir, "acme-corp", "widgets");
+        assert!(new_path.join(".git").exists());
+        // The old owner's directory went with the clone
+        assert!(!admin_repo_dir.join("acme").exists());
+        assert_eq!(
+            git(&new_path, &["remote", "get-url", "origin"]),
+            "https://github.com/acme-corp/widgets.git"
+        );
+
+        // The worktree finds the clone at its new path
+        let git_dir =
+            std::fs::canonicalize(git(&worktree, &["rev-parse", "--git-common-dir"])).unwrap();
+        assert_eq!(
+            git_dir,
+            std::fs::canonicalize(new_path.join(".git")).unwrap()
+        );
+        assert_eq!(git(&worktree, &["status", "--porcelain"]), "");
+
+        // Nothing to move for a repository that was never cloned
+        move_admin_clone(
+            &AllTokens::default(),
+            &admin_repo_dir,
+            "acme/other",
+            "acme-corp/other",
+            || Ok(true),
+        )
+        .unwrap();
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 pub struct ConfigPaths {
     pub admin_repo_dir: String,
     pub worktree_dir: String,
+    pub ci_log_dir: String,
     pub db_path: String,
 }
 
@@ -14,6 +15,7 @@ pub fn get_config_paths(paths: tauri::State<'_, Paths>) -> Result<ConfigPaths, S
     Ok(ConfigPaths {
         admin_repo_dir: paths.admin_repo_dir.to_string_lossy().to_string(),
         worktree_dir: paths.worktree_dir.to_string_lossy().to_string(),
+        ci_log_dir: paths.ci_log_dir.to_string_lossy().to_string(),
         db_path: paths.db_path.to_string_lossy().to_string(),
     })
 }
@@ -1,8 +1,8 @@
-use octocrab::Octocrab;
 use serde::{Deserialize, Serialize};
 use std::process::Command;
 
-use crate::commands::tokens::get_token_value;
+use crate::commands::tokens::{cached_tokens, get_token_value};
+use crate::util::rate_limit;
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -14,28 +14,113 @@ pub struct HealthCheckResult {
 
 #[tauri::command]
 pub async fn health_check_github() -> Result<HealthCheckResult, String> {
-    let token = get_token_value("github_token")
-        .map_err(|e| format!("Failed to access token: {}", e))?
+    use crate::git::github_app::GitHubAppCredentials;
+
+    let tokens = cached_tokens()?;
+    let api_base_url = crate::util::git::github_api_base_url(&tokens);
+
+    // A configured GitHub App takes precedence over the personal access token
+    if let Some(credentials) = GitHubAppCredentials::from_tokens(&tokens) {
+        return Ok(check_github_app(credentials, api_base_url).await);
+    }
+
+    let token = tokens
+        .github_token
         .ok_or_else(|| "GitHub token not configured".to_string())?;
 
-    match Octocrab::builder().personal_token(token).build() {
-        Ok(client) => match client.current().user().await {
-            Ok(user) => Ok(HealthCheckResult {
-                success: true,
-                username: Some(user.login),
-                error: None,
-            }),
-            Err(e) => Ok(HealthCheckResult {
+    Ok(check_github(token, api_base_url).await)
+}
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+async fn check_github(token: String, api_base_url: String) -> HealthCheckResult {
+    let url = format!("{}/user", api_base_url.trim_end_matches('/'));
+
+    let client = match reqwest::Client::builder()
+        .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            return HealthCheckResult {
                 success: false,
                 username: None,
-                error: Some(format!("Failed to get user info: {}", e)),
-            }),
+                error: Some(format!("Failed to create GitHub client: {}", e)),
+            }
+        }
+    };
+
+    let request = client
+        .get(&url)
+        .bearer_auth(token)
+        .header("Accept", "application/vnd.github+json");
+    match rate_limit::send(request).await {
+        Ok(response) => {
+            if response.status().is_success() {
+                match response.json::<GitHubUser>().await {
+                    Ok(user) => HealthCheckResult {
+                        success: true,
+                        username: Some(user.login),
+                        error: None,
+                    },
+                    Err(e) => HealthCheckResult {
+                        success: false,
+                        username: None,
+                        error: Some(format!("Failed to parse user info: {}", e)),
+                    },
+                }
+            } else {
+                HealthCheckResult {
+                    success: false,
+                    username: None,
+                    error: Some(format!(
+                        "Failed to get user info: HTTP {}: {}",
+                        response.status(),
+                        response.text().await.unwrap_or_default()
+                    )),
+                }
+            }
+        }
+        Err(e) => HealthCheckResult {
+            success: false,
+            username: None,
+            error: Some(format!("Failed to get user info: {}", e)),
         },
-        Err(e) => Ok(HealthCheckResult {
+    }
+}
+
+/// Authenticate as the GitHub App itself; installations are looked up per repository owner
+async fn check_github_app(
+    credentials: crate::git::github_app::GitHubAppCredentials,
+    api_base_url: String,
+) -> HealthCheckResult {
+    use crate::git::github_app::GitHubApp;
+
+    let app = match GitHubApp::new(credentials, api_base_url) {
+        Ok(app) => app,
+        Err(e) => {
+            return HealthCheckResult {
+                success: false,
+                username: None,
+                error: Some(format!("Failed to create GitHub client: {}", e)),
+            }
+        }
+    };
+
+    match app.slug().await {
+        Ok(slug) => HealthCheckResult {
+            success: true,
+            username: Some(format!("{}[bot]", slug)),
+            error: None,
+        },
+        Err(e) => HealthCheckResult {
             success: false,
             username: None,
-            error: Some(format!("Failed to create GitHub client: {}", e)),
-        }),
+            error: Some(format!("Failed to authenticate GitHub App: {}", e)),
+        },
     }
 }
 
@@ -148,6 +233,10 @@ pub async fn health_check_gitlab() -> Result<HealthCheckResult, String> {
         .map_err(|e| format!("Failed to access endpoint: {}", e))?
         .unwrap_or_else(|| "https://gitlab.com".to_string());
 
+    Ok(check_gitlab(token, &endpoint).await)
+}
+
+async fn check_gitlab(token: String, endpoint: &str) -> HealthCheckResult {
     let endpoint = endpoint.trim_end_matches('/');
     let url = format!("{}/api/v4/user", endpoint);
 
@@ -156,19 +245,19 @@ pub async fn health_check_gitlab() -> Result<HealthCheckResult, String> {
         Ok(response) => {
             if response.status().is_success() {
                 match response.json::<GitLabUser>().await {
-                    Ok(user) => Ok(HealthCheckResult {
+                    Ok(user) => HealthCheckResult {
                         success: true,
                         username: Some(user.username),
                         error: None,
-                    }),
-                    Err(e) => Ok(HealthCheckResult {
+                    },
+                    Err(e) => HealthCheckResult {
                         success: false,
                         username: None,
                         error: Some(format!("Failed to parse user info: {}", e)),
-                    }),
+                    },
                 }
             } else {
-                Ok(HealthCheckResult {
+                HealthCheckResult {
                     success: false,
                     username: None,
                     error: Some(format!(
@@ -176,17 +265,152 @@ pub async fn health_check_gitlab() -> Result<HealthCheckResult, String> {
                         response.status(),
                         response.text().await.unwrap_or_default()
                     )),
-                })
+                }
             }
         }
-        Err(e) => Ok(HealthCheckResult {
+        Err(e) => HealthCheckResult {
             success: false,
             username: None,
             error: Some(format!("Request failed: {}", e)),
-        }),
+        },
+    }
+}
+
+#[derive(Deserialize)]
+struct GiteaUser {
+    login: String,
+}
+
+#[tauri::command]
+pub async fn health_check_gitea() -> Result<HealthCheckResult, String> {
+    let token = get_token_value("gitea_token")
+        .map_err(|e| format!("Failed to access token: {}", e))?
+        .ok_or_else(|| "Gitea token not configured".to_string())?;
+
+    let endpoint =
+        crate::util::git::gitea_instance_url(&cached_tokens()?).map_err(|e| e.to_string())?;
+
+    Ok(check_gitea(token, &endpoint).await)
+}
+
+async fn check_gitea(token: String, endpoint: &str) -> HealthCheckResult {
+    let url = format!("{}/api/v1/user", endpoint);
+
+    let client = reqwest::Client::new();
+    match client
+        .get(&url)
+        .header("Authorization", format!("token {}", token))
+        .send()
+        .await
+    {
+        Ok(response) => {
+            if response.status().is_success() {
+                match response.json::<GiteaUser>().await {
+                    Ok(user) => HealthCheckResult {
+                        success: true,
+                        username: Some(user.login),
+                        error: None,
+                    },
+                    Err(e) => HealthCheckResult {
+                        success: false,
+                        username: None,
+                        error: Some(format!("Failed to parse user info: {}", e)),
+                    },
+                }
+            } else {
+                HealthCheckResult {
+                    success: false,
+                    username: None,
+                    error: Some(format!(
+                        "HTTP {}: {}",
+                        response.status(),
+                        response.text().await.unwrap_or_default()
+                    )),
+                }
+            }
+        }
+        Err(e) => HealthCheckResult {
+            success: false,
+            username: None,
+            error: Some(format!("Request failed: {}", e)),
+        },
     }
 }
 
+#[tauri::command]
+pub async fn health_check_bitbucket() -> Result<HealthCheckResult, String> {
+    let token = get_token_value("bitbucket_token")
+        .map_err(|e| format!("Failed to access token: {}", e))?
+        .ok_or_else(|| "Bitbucket token not configured".to_string())?;
+
+    let endpoint =
+        crate::util::git::bitbucket_instance_url(&cached_tokens()?).map_err(|e| e.to_string())?;
+
+    Ok(check_bitbucket(token, &endpoint).await)
+}
+
+async fn check_bitbucket(token: String, endpoint: &str) -> HealthCheckResult {
+    // Bitbucket has no "current user" endpoint; authenticated responses name the user in a header
+    let url = format!("{}/rest/api/1.0/projects?limit=1", endpoint);
+
+    let client = reqwest::Client::new();
+    match client.get(&url).bearer_auth(token).send().await {
+        Ok(response) => {
+            let username = response
+                .headers()
+                .get("X-AUSERNAME")
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string());
+
+            if response.status().is_success() && username.is_some() {
+                HealthCheckResult {
+                    success: true,
+                    username,
+                    error: None,
+                }
+            } else if response.status().is_success() {
+                HealthCheckResult {
+                    success: false,
+                    username: None,
+                    error: Some("Token was not accepted (anonymous access)".to_string()),
+                }
+            } else {
+                HealthCheckResult {
+                    success: false,
+                    username: None,
+                    error: Some(format!(
+                        "HTTP {}: {}",
+                        response.status(),
+                        response.text().await.unwrap_or_default()
+                    )),
+                }
+            }
+        }
+        Err(e) => HealthCheckResult {
+            success: false,
+            username: None,
+            error: Some(format!("Request failed: {}", e)),
+        },
+    }
+}
+
+/// Test one credential profile against its own host
+#[tauri::command]
+pub async fn health_check_credential_profile(name: String) -> Result<HealthCheckResult, String> {
+    let profile = crate::commands::tokens::get_credential_profile(&name)?
+        .ok_or_else(|| format!("Credential profile {} not found", name))?;
+    let base_url = crate::util::git::profile_api_base_url(&profile);
+
+    let result = match profile.provider.as_str() {
+        "github" => check_github(profile.token, base_url).await,
+        "gitlab" => check_gitlab(profile.token, &base_url).await,
+        "gitea" => check_gitea(profile.token, &base_url).await,
+        "bitbucket" => check_bitbucket(profile.token, &base_url).await,
+        other => return Err(format!("Unsupported provider: {}", other)),
+    };
+    Ok(result)
+}
+
 #[derive(Deserialize)]
 struct SourcegraphCurrentUserResponse {
     data: SourcegraphCurrentUserData,
@@ -279,3 +503,9 @@ pub async fn health_check_sourcegraph() -> Result<HealthCheckResult, String> {
         }),
     }
 }
+
+/// Last known API rate limit budget of every provider host contacted since startup
+#[tauri::command]
+pub fn get_rate_limits() -> Vec<crate::util::rate_limit::RateLimitBudget> {
+    crate::util::rate_limit::budgets()
+}
@@ -0,0 +1,35 @@
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::commands::tokens::cached_tokens;
+use crate::db::store::Store;
+use crate::maintenance::{DiskUsage, MaintenanceReport};
+use crate::Paths;
+
+/// Disk used by each admin clone and its execution worktrees
+#[tauri::command]
+pub async fn get_disk_usage(app: tauri::AppHandle) -> Result<DiskUsage, String> {
+    let tokens = cached_tokens()?;
+    tokio::task::spawn_blocking(move || {
+        let store = app.state::<Mutex<Store>>();
+        let paths = app.state::<Paths>();
+        crate::maintenance::disk_usage(&store, &paths, tokens)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Run a maintenance pass now, garbage collecting every clone regardless of the gc interval
+#[tauri::command]
+pub async fn run_clone_maintenance(app: tauri::AppHandle) -> Result<MaintenanceReport, String> {
+    let tokens = cached_tokens()?;
+    tokio::task::spawn_blocking(move || {
+        let store = app.state::<Mutex<Store>>();
+        let paths = app.state::<Paths>();
+        crate::maintenance::run_maintenance(&store, &paths, tokens, true)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::time::{sleep, Duration};
+
+use crate::commands::tokens::{cached_tokens, AllTokens};
+use crate::db::store::{DefaultBranchChange, Repository, Store};
+use crate::util::rate_limit;
+use crate::Paths;
+
+/// How often the sync looks for repositories due for a refresh
+const TICK_SECS: u64 = 60;
+
+/// Wait before retrying a repository whose sync failed
+const FAILURE_RETRY_MS: i64 = 60 * 60 * 1000;
+
+lazy_static::lazy_static! {
+    static ref SYNC_ACTIVE: Mutex<bool> = Mutex::new(false);
+}
+
+/// Key of the provider host a repository's API requests go to
+/// Repositories sharing it share a rate limit budget
+fn host_key(tokens: &AllTokens, repository: &Repository) -> String {
+    let base_url = crate::util::git::provider_web_base_url(
+        tokens,
+        &repository.provider,
+        &repository.provider_id,
+    )
+    .unwrap_or_default();
+    format!("{}:{}", repository.provider, base_url)
+}
+
+/// Start the background metadata sync (no-op if it is already running)
+/// Every `metadata_sync_interval_hours`, each repository's default branch and other provider
+/// metadata are refreshed; the setting is read on every tick, so changes apply without a restart
+pub fn start_metadata_sync(app: AppHandle) {
+    {
+        let mut active = SYNC_ACTIVE.lock().unwrap();
+        if *active {
+            return;
+        }
+        *active = true;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        log::info!("[metadata_sync] Started");
+        // Repositories whose last sync failed, and hosts whose rate limit is spent, by when
+        // to try them again (ms since epoch)
+        let mut failed: HashMap<String, i64> = HashMap::new();
+        let mut limited_hosts: HashMap<String, i64> = HashMap::new();
+
+        loop {
+            sync_due_repositories(&app, &mut failed, &mut limited_hosts).await;
+            sleep(Duration::from_secs(TICK_SECS)).await;
+        }
+    });
+}
+
+async fn sync_due_repositories(
+    app: &AppHandle,
+    failed: &mut HashMap<String, i64>,
+    limited_hosts: &mut HashMap<String, i64>,
+) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let due = {
+        let store = app.state::<Mutex<Store>>();
+        let store = store.lock().unwrap();
+        store.get_metadata_sync_interval_hours().and_then(|hours| {
+            if hours <= 0 {
+                return Ok(Vec::new());
+            }
+            store.get_repositories_due_for_sync(now - hours * 60 * 60 * 1000)
+        })
+    };
+    let due = match due {
+        Ok(due) => due,
+        Err(e) => {
+            log::error!("[metadata_sync] Failed to load repositories: {}", e);
+            return;
+        }
+    };
+
+    failed.retain(|_, retry_at| *retry_at > now);
+    limited_hosts.retain(|_, retry_at| *retry_at > now);
+
+    let tokens = match cached_tokens() {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            log::error!("[metadata_sync] Failed to read tokens: {}", e);
+            return;
+        }
+    };
+
+    for repository in due {
+        if failed.contains_key(&repository.id) {
+            continue;
+        }
+        let host = host_key(&tokens, &repository);
+        if limited_hosts.contains_key(&host) {
+            continue;
+        }
+
+        // Syncs leave the host's reserve to the user's own requests
+        let fetch = super::db::fetch_repository_metadata(&repository);
+        let metadata = match rate_limit::send_background(fetch).await {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                if let Some(limited) = rate_limit::as_rate_limited(&e) {
+                    // Leave the rest of the host's repositories for after the reset
+                    log::info!("[metadata_sync] {}; pausing syncs for {}", limited, host);
+                    limited_hosts.insert(host, limited.retry_at);
+                } else {
+                    log::warn!(
+                        "[metadata_sync] Failed to sync {}: {:#}",
+                        repository.provider_id,
+                        e
+                    );
+                    failed.insert(repository.id.clone(), now + FAILURE_RETRY_MS);
+                }
+                continue;
+            }
+        };
+
+        let store = app.state::<Mutex<Store>>();
+        let paths = app.state::<Paths>();
+        if let Err(e) = super::db::apply_repository_metadata(&store, &paths, &repository, &metadata)
+        {
+            log::warn!(
+                "[metadata_sync] Failed to update {}: {}",
+                repository.provider_id,
+                e
+            );
+            failed.insert(repository.id.clone(), now + FAILURE_RETRY_MS);
+        }
+    }
+}
+
+/// Repositories whose default branch changed since a promptset last ran on them, for one
+/// promptset or all of them
+#[tauri::command]
+pub fn get_default_branch_changes(
+    store: tauri::State<Mutex<Store>>,
+    promptset_id: Option<String>,
+) -> Result<Vec<DefaultBranchChange>, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .get_default_branch_changes(promptset_id.as_deref())
+        .map_err(|e| e.to_string())
+}
@@ -9,7 +9,11 @@ pub mod executor;
 pub mod executor_events;
 pub mod github;
 pub mod health_check;
+pub mod maintenance;
+pub mod metadata_sync;
 pub mod settings;
 pub mod sourcegraph;
+pub mod tags;
 pub mod tokens;
+pub mod webhooks;
 pub mod worktree;
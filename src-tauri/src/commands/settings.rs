@@ -28,6 +28,113 @@ pub fn get_ci_stuck_threshold_minutes(store: tauri::State<Mutex<Store>>) -> Resu
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn get_ci_poll_schedule(store: tauri::State<Mutex<Store>>) -> Result<Vec<u64>, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store.get_ci_poll_schedule().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_ci_poll_schedule(
+    schedule: Vec<u64>,
+    store: tauri::State<Mutex<Store>>,
+) -> Result<(), String> {
+    let value = schedule
+        .iter()
+        .map(|secs| secs.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    // Validate before saving so the watcher never reads a broken schedule
+    crate::ci::parse_poll_schedule(&value).map_err(|e| e.to_string())?;
+
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .set_setting("ci_poll_schedule", &value)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_ci_watch_max_hours(store: tauri::State<Mutex<Store>>) -> Result<i64, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store.get_ci_watch_max_hours().map_err(|e| e.to_string())
+}
+
+/// Set how many hours the watcher keeps polling a pending pipeline before giving up on it
+#[tauri::command]
+pub fn set_ci_watch_max_hours(hours: i64, store: tauri::State<Mutex<Store>>) -> Result<(), String> {
+    if hours <= 0 {
+        return Err("CI watch duration must be at least 1 hour".to_string());
+    }
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .set_setting("ci_watch_max_hours", &hours.to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_metadata_sync_interval_hours(store: tauri::State<Mutex<Store>>) -> Result<i64, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .get_metadata_sync_interval_hours()
+        .map_err(|e| e.to_string())
+}
+
+/// Set the hours between background syncs of each repository; 0 turns them off
+#[tauri::command]
+pub fn set_metadata_sync_interval_hours(
+    hours: i64,
+    store: tauri::State<Mutex<Store>>,
+) -> Result<(), String> {
+    if hours < 0 {
+        return Err("Metadata sync interval can't be negative".to_string());
+    }
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .set_setting("metadata_sync_interval_hours", &hours.to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_clone_gc_interval_hours(store: tauri::State<Mutex<Store>>) -> Result<i64, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .get_clone_gc_interval_hours()
+        .map_err(|e| e.to_string())
+}
+
+/// Set the hours between scheduled gc runs of each clone; 0 turns them off
+#[tauri::command]
+pub fn set_clone_gc_interval_hours(
+    hours: i64,
+    store: tauri::State<Mutex<Store>>,
+) -> Result<(), String> {
+    if hours < 0 {
+        return Err("Clone gc interval can't be negative".to_string());
+    }
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .set_setting("clone_gc_interval_hours", &hours.to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_disk_budget_gb(store: tauri::State<Mutex<Store>>) -> Result<i64, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store.get_disk_budget_gb().map_err(|e| e.to_string())
+}
+
+/// Set the gigabytes clones and worktrees may use before clones are evicted; 0 for no budget
+#[tauri::command]
+pub fn set_disk_budget_gb(gb: i64, store: tauri::State<Mutex<Store>>) -> Result<(), String> {
+    if gb < 0 {
+        return Err("Disk budget can't be negative".to_string());
+    }
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .set_setting("disk_budget_gb", &gb.to_string())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_max_concurrent_executions(store: tauri::State<Mutex<Store>>) -> Result<i64, String> {
     let store = store.lock().map_err(|e| e.to_string())?;
@@ -1,11 +1,13 @@
+use serde_json::json;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
 use crate::commands::tokens;
-use crate::sourcegraph::{RepositorySearchResult, SourcegraphClient};
+use crate::db::store::{PromptSet, RepositoryQuerySnapshot, Store};
+use crate::sourcegraph::{RepositorySearchResult, SourcegraphClient, MAX_REPOSITORY_MATCHES};
 
-#[tauri::command]
-pub async fn search_sourcegraph_repositories(
-    query: String,
-    limit: Option<i32>,
-) -> Result<RepositorySearchResult, String> {
+/// Sourcegraph client for the configured instance
+fn sourcegraph_client() -> Result<SourcegraphClient, String> {
     // Get Sourcegraph endpoint from keyring
     let endpoint = tokens::get_token_value("sourcegraph_endpoint")
         .map_err(|e| format!("Failed to access endpoint: {}", e))?
@@ -20,16 +22,129 @@ pub async fn search_sourcegraph_repositories(
             "Sourcegraph access token not configured. Please set it in Settings.".to_string()
         })?;
 
-    // Create client
-    let client = SourcegraphClient::new(endpoint, access_token)
-        .map_err(|e| format!("Failed to create Sourcegraph client: {}", e))?;
+    SourcegraphClient::new(endpoint, access_token)
+        .map_err(|e| format!("Failed to create Sourcegraph client: {}", e))
+}
+
+/// Report how many repositories a running search has found so far
+fn emit_search_progress(app: &tauri::AppHandle, query: &str, repositories_found: usize) {
+    let _ = app.emit(
+        "sourcegraph:search-progress",
+        json!({
+            "query": query,
+            "repositoriesFound": repositories_found
+        }),
+    );
+}
+
+#[tauri::command]
+pub async fn search_sourcegraph_repositories(
+    query: String,
+    limit: Option<i32>,
+    app: tauri::AppHandle,
+) -> Result<RepositorySearchResult, String> {
+    let client = sourcegraph_client()?;
 
     // Search repositories
     let limit = limit.unwrap_or(50);
     let result = client
-        .search_repositories(&query, limit)
+        .search_repositories(&query, limit, |found| {
+            emit_search_progress(&app, &query, found)
+        })
         .await
         .map_err(|e| format!("Failed to search repositories: {}", e))?;
 
     Ok(result)
 }
+
+/// Re-resolve a promptset's saved query for a revision run
+/// New matches are added to the promptset; repositories that no longer match stay in it but
+/// are skipped for the run, unless the search was cut short and can't tell. The outcome is
+/// recorded as a snapshot of the run
+pub(crate) async fn resolve_repository_query(
+    app: &tauri::AppHandle,
+    promptset: &PromptSet,
+    revision_id: &str,
+    query: &str,
+) -> Result<RepositoryQuerySnapshot, String> {
+    let result = sourcegraph_client()?
+        .search_repositories(query, MAX_REPOSITORY_MATCHES as i32, |found| {
+            emit_search_progress(app, query, found)
+        })
+        .await
+        .map_err(|e| format!("Failed to resolve repository query '{}': {}", query, e))?;
+    if result.has_next_page {
+        log::warn!(
+            "[sourcegraph] Repository query '{}' is incomplete: stopped at {} repositories, none are skipped",
+            query,
+            result.repositories.len()
+        );
+    }
+
+    let tokens = crate::commands::tokens::cached_tokens()?;
+    let store_state = app.state::<Mutex<Store>>();
+    let mut store = store_state.lock().map_err(|e| e.to_string())?;
+
+    let mut matched_repository_ids = Vec::new();
+    let mut unsupported_repositories = Vec::new();
+    for repository in &result.repositories {
+        let Some((provider, provider_id)) = repository.provider_repository(&tokens) else {
+            unsupported_repositories.push(repository.name.clone());
+            continue;
+        };
+        let repository = store
+            .upsert_repository(provider, &provider_id, None)
+            .map_err(|e| e.to_string())?;
+        if !matched_repository_ids.contains(&repository.id) {
+            matched_repository_ids.push(repository.id);
+        }
+    }
+
+    let added_repository_ids: Vec<String> = matched_repository_ids
+        .iter()
+        .filter(|id| !promptset.repository_ids.contains(id))
+        .cloned()
+        .collect();
+    // A repository missing from an incomplete result may still match
+    let skipped_repository_ids: Vec<String> = if result.has_next_page {
+        Vec::new()
+    } else {
+        promptset
+            .repository_ids
+            .iter()
+            .filter(|id| !matched_repository_ids.contains(id))
+            .cloned()
+            .collect()
+    };
+
+    if !added_repository_ids.is_empty() {
+        let mut repository_ids = promptset.repository_ids.clone();
+        repository_ids.extend(added_repository_ids.iter().cloned());
+        store
+            .update_promptset_repositories(&promptset.id, repository_ids)
+            .map_err(|e| e.to_string())?;
+    }
+
+    log::info!(
+        "[sourcegraph] Query '{}' matched {} repositories for promptset {} ({} new, {} no longer matching)",
+        query,
+        matched_repository_ids.len(),
+        promptset.id,
+        added_repository_ids.len(),
+        skipped_repository_ids.len()
+    );
+
+    store
+        .record_repository_query_snapshot(RepositoryQuerySnapshot {
+            id: 0,
+            promptset_id: promptset.id.clone(),
+            revision_id: revision_id.to_string(),
+            query: query.to_string(),
+            matched_repository_ids,
+            added_repository_ids,
+            skipped_repository_ids,
+            unsupported_repositories,
+            created_at: 0,
+        })
+        .map_err(|e| e.to_string())
+}
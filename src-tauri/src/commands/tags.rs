@@ -0,0 +1,214 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+use crate::commands::executor::lazy_fetch_credentials;
+use crate::db::store::{Repository, RepositoryTag, Store, TagCount};
+use crate::git::catalog::catalog_tags;
+use crate::git::service::GitAuth;
+use crate::git::GitService;
+use crate::util::paths::admin_repo_path;
+use crate::util::tags::{normalize_tag, TagExpression};
+use crate::Paths;
+
+type StoreState<'a> = tauri::State<'a, Mutex<Store>>;
+
+/// Ids of the repositories matching an optional tag expression filter; None when there is
+/// no filter, so callers can skip filtering entirely
+pub(crate) fn repository_ids_matching(
+    store: &Store,
+    tag_expression: Option<&str>,
+) -> Result<Option<Vec<String>>, String> {
+    let Some(expression) = tag_expression.map(str::trim).filter(|e| !e.is_empty()) else {
+        return Ok(None);
+    };
+    let expression =
+        TagExpression::parse(expression).map_err(|e| format!("Invalid tag expression: {}", e))?;
+    store
+        .find_repository_ids_by_tags(&expression)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// Tags an import source derives for a repository
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagImportResult {
+    pub repository_id: String,
+    pub tags: Vec<String>,
+    /// Why nothing was imported for this repository, if something went wrong
+    pub error: Option<String>,
+}
+
+#[tauri::command]
+pub fn add_repository_tags(
+    store: StoreState<'_>,
+    repository_id: String,
+    tags: Vec<String>,
+) -> Result<Repository, String> {
+    let tags = tags
+        .iter()
+        .map(|tag| normalize_tag(tag))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .add_repository_tags(&repository_id, &tags, "manual")
+        .map_err(|e| e.to_string())?;
+    store
+        .get_repository(&repository_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Repository {} not found", repository_id))
+}
+
+#[tauri::command]
+pub fn remove_repository_tag(
+    store: StoreState<'_>,
+    repository_id: String,
+    tag: String,
+) -> Result<bool, String> {
+    let tag = normalize_tag(&tag).map_err(|e| e.to_string())?;
+    store
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove_repository_tag(&repository_id, &tag)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_repository_tags(
+    store: StoreState<'_>,
+    repository_id: String,
+) -> Result<Vec<RepositoryTag>, String> {
+    store
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get_repository_tags(&repository_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_tag_counts(store: StoreState<'_>) -> Result<Vec<TagCount>, String> {
+    store
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get_tag_counts()
+        .map_err(|e| e.to_string())
+}
+
+/// Preview which repositories a tag expression targets
+#[tauri::command]
+pub fn find_repositories_by_tags(
+    store: StoreState<'_>,
+    expression: String,
+) -> Result<Vec<Repository>, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    let ids = repository_ids_matching(&store, Some(&expression))?.unwrap_or_default();
+    ids.iter()
+        .filter_map(|id| store.get_repository(id).transpose())
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Tags from a repository's provider metadata: its topics as is, plus `lang:{language}`
+fn topic_tags(repository: &Repository) -> Vec<String> {
+    let mut tags: Vec<String> = repository
+        .topics
+        .iter()
+        .filter_map(|topic| normalize_tag(topic).ok())
+        .collect();
+    if let Some(language) = &repository.language {
+        if let Ok(tag) = normalize_tag(&format!("lang:{}", language.replace(' ', "-"))) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Tags from a repository's CODEOWNERS and catalog-info.yaml, read from its admin clone
+/// The files are read as of the clone's last fetch, by the repository's last execution, which
+/// may be behind the provider; only a partial clone fetches, and just the files it is missing
+async fn catalog_import_tags(
+    paths: &Paths,
+    repository: &Repository,
+) -> Result<Vec<String>, String> {
+    let tokens = crate::commands::tokens::cached_tokens()?;
+    let repo_path =
+        admin_repo_path(paths, &tokens, &repository.provider_id).map_err(|e| e.to_string())?;
+    if !repo_path.exists() {
+        return Err("Not cloned yet; catalog tags are read from the admin clone".to_string());
+    }
+    let default_branch = repository
+        .default_branch
+        .as_deref()
+        .ok_or_else(|| "Default branch unknown; sync the repository first".to_string())?;
+
+    let credentials = lazy_fetch_credentials(repository, &repo_path).await;
+    let auth = credentials
+        .as_ref()
+        .map(|(username, password)| GitAuth { username, password });
+    let git_repo = GitService::open(&repo_path).map_err(|e| e.to_string())?;
+    catalog_tags(
+        &git_repo,
+        &format!("origin/{}", default_branch),
+        auth.as_ref(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Import tags from a source ("topic" or "catalog"), replacing what that source imported
+/// before; manual tags are kept. Without repository_ids every repository is imported
+#[tauri::command]
+pub async fn import_repository_tags(
+    store: StoreState<'_>,
+    paths: tauri::State<'_, Paths>,
+    source: String,
+    repository_ids: Option<Vec<String>>,
+) -> Result<Vec<TagImportResult>, String> {
+    if source != "topic" && source != "catalog" {
+        return Err(format!("Unknown tag source '{}'", source));
+    }
+
+    let repositories = {
+        let store = store.lock().map_err(|e| e.to_string())?;
+        match repository_ids {
+            Some(ids) => ids
+                .iter()
+                .filter_map(|id| store.get_repository(id).transpose())
+                .collect::<anyhow::Result<Vec<_>>>(),
+            None => store.get_all_repositories(),
+        }
+        .map_err(|e| e.to_string())?
+    };
+
+    let mut results = Vec::new();
+    for repository in repositories {
+        let tags = if source == "topic" {
+            Ok(topic_tags(&repository))
+        } else {
+            catalog_import_tags(&paths, &repository).await
+        };
+        let result = match tags {
+            Ok(tags) => {
+                store
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .replace_repository_tags(&repository.id, &source, &tags)
+                    .map_err(|e| e.to_string())?;
+                TagImportResult {
+                    repository_id: repository.id,
+                    tags,
+                    error: None,
+                }
+            }
+            Err(error) => TagImportResult {
+                repository_id: repository.id,
+                tags: Vec::new(),
+                error: Some(error),
+            },
+        };
+        results.push(result);
+    }
+
+    Ok(results)
+}
@@ -13,12 +13,55 @@ static CREDENTIAL_CACHE: OnceLock<Arc<RwLock<AllTokens>>> = OnceLock::new();
 pub struct AllTokens {
     pub amp_token: Option<String>,
     pub github_token: Option<String>,
+    pub github_instance_url: Option<String>,
+    pub github_api_url: Option<String>,
+    /// GitHub App used instead of github_token when both the app ID and private key are set
+    pub github_app_id: Option<String>,
+    pub github_app_private_key: Option<String>,
     pub gitlab_token: Option<String>,
     pub gitlab_instance_url: Option<String>,
     pub sourcegraph_endpoint: Option<String>,
     pub sourcegraph_token: Option<String>,
+    pub github_webhook_secret: Option<String>,
+    pub gitlab_webhook_token: Option<String>,
+    pub jenkins_url: Option<String>,
+    pub jenkins_username: Option<String>,
+    pub jenkins_api_token: Option<String>,
+    pub gitea_token: Option<String>,
+    pub gitea_instance_url: Option<String>,
+    pub bitbucket_url: Option<String>,
+    pub bitbucket_username: Option<String>,
+    pub bitbucket_token: Option<String>,
+    /// Per-host credentials for repositories outside the default instance of their provider
+    #[serde(default)]
+    pub credential_profiles: Vec<CredentialProfile>,
 }
 
+/// Named credentials for one provider host (a GitHub Enterprise Server, a second GitLab, ...)
+/// Repositories whose provider_id starts with `host` use these instead of the provider's tokens
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialProfile {
+    pub name: String,
+    /// github | gitlab | gitea | bitbucket
+    pub provider: String,
+    /// Host (with port, if any) as it appears in provider IDs, e.g. "ghe.example.com"
+    pub host: String,
+    /// Web base URL, including any context path
+    pub base_url: String,
+    /// API base URL when it can't be derived from the web base URL
+    pub api_url: Option<String>,
+    /// Username for HTTPS git operations (required for Bitbucket)
+    pub username: Option<String>,
+    /// SSH port for git operations, when the host doesn't serve SSH on its provider's default
+    #[serde(default)]
+    pub ssh_port: Option<u16>,
+    pub token: String,
+}
+
+/// Providers a credential profile can be created for
+const PROFILE_PROVIDERS: &[&str] = &["github", "gitlab", "gitea", "bitbucket"];
+
 fn get_tokens_entry() -> Result<Entry, String> {
     Entry::new(SERVICE_NAME, CREDENTIALS).map_err(|e| format!("Failed to access keyring: {}", e))
 }
@@ -66,6 +109,19 @@ pub fn init_token_cache() -> Result<(), String> {
     Ok(())
 }
 
+/// Snapshot of the token cache, for resolving repository credentials and hosts
+pub(crate) fn cached_tokens() -> Result<AllTokens, String> {
+    let cache = CREDENTIAL_CACHE
+        .get()
+        .ok_or_else(|| "Token cache not initialized".to_string())?;
+
+    let tokens = cache
+        .read()
+        .map_err(|e| format!("Failed to read token cache: {}", e))?;
+
+    Ok(tokens.clone())
+}
+
 /// Internal helper to get token value from cache
 pub(crate) fn get_token_value(key: &str) -> Result<Option<String>, String> {
     let cache = CREDENTIAL_CACHE
@@ -76,17 +132,66 @@ pub(crate) fn get_token_value(key: &str) -> Result<Option<String>, String> {
         .read()
         .map_err(|e| format!("Failed to read token cache: {}", e))?;
 
-    match key {
-        "amp_token" => Ok(tokens.amp_token.clone()),
-        "github_token" => Ok(tokens.github_token.clone()),
-        "gitlab_token" => Ok(tokens.gitlab_token.clone()),
-        "gitlab_instance_url" => Ok(tokens.gitlab_instance_url.clone()),
-        "sourcegraph_endpoint" => Ok(tokens.sourcegraph_endpoint.clone()),
-        "sourcegraph_token" => Ok(tokens.sourcegraph_token.clone()),
-        _ => Err(format!("Unknown token key: {}", key)),
+    tokens.value(key)
+}
+
+impl AllTokens {
+    /// Token value by its settings key
+    pub(crate) fn value(&self, key: &str) -> Result<Option<String>, String> {
+        match key {
+            "amp_token" => Ok(self.amp_token.clone()),
+            "github_token" => Ok(self.github_token.clone()),
+            "github_instance_url" => Ok(self.github_instance_url.clone()),
+            "github_api_url" => Ok(self.github_api_url.clone()),
+            "github_app_id" => Ok(self.github_app_id.clone()),
+            "github_app_private_key" => Ok(self.github_app_private_key.clone()),
+            "gitlab_token" => Ok(self.gitlab_token.clone()),
+            "gitlab_instance_url" => Ok(self.gitlab_instance_url.clone()),
+            "sourcegraph_endpoint" => Ok(self.sourcegraph_endpoint.clone()),
+            "sourcegraph_token" => Ok(self.sourcegraph_token.clone()),
+            "github_webhook_secret" => Ok(self.github_webhook_secret.clone()),
+            "gitlab_webhook_token" => Ok(self.gitlab_webhook_token.clone()),
+            "jenkins_url" => Ok(self.jenkins_url.clone()),
+            "jenkins_username" => Ok(self.jenkins_username.clone()),
+            "jenkins_api_token" => Ok(self.jenkins_api_token.clone()),
+            "gitea_token" => Ok(self.gitea_token.clone()),
+            "gitea_instance_url" => Ok(self.gitea_instance_url.clone()),
+            "bitbucket_url" => Ok(self.bitbucket_url.clone()),
+            "bitbucket_username" => Ok(self.bitbucket_username.clone()),
+            "bitbucket_token" => Ok(self.bitbucket_token.clone()),
+            _ => Err(format!("Unknown token key: {}", key)),
+        }
+    }
+
+    /// Credential profile for a provider host, if one is configured
+    pub(crate) fn credential_profile(
+        &self,
+        provider: &str,
+        host: &str,
+    ) -> Option<&CredentialProfile> {
+        self.credential_profiles
+            .iter()
+            .find(|profile| profile.provider == provider && profile.host.eq_ignore_ascii_case(host))
     }
 }
 
+/// Credential profile by name
+pub(crate) fn get_credential_profile(name: &str) -> Result<Option<CredentialProfile>, String> {
+    let cache = CREDENTIAL_CACHE
+        .get()
+        .ok_or_else(|| "Token cache not initialized".to_string())?;
+
+    let tokens = cache
+        .read()
+        .map_err(|e| format!("Failed to read token cache: {}", e))?;
+
+    Ok(tokens
+        .credential_profiles
+        .iter()
+        .find(|profile| profile.name == name)
+        .cloned())
+}
+
 #[tauri::command]
 pub fn set_token(key: String, value: String) -> Result<(), String> {
     let cache = CREDENTIAL_CACHE
@@ -112,6 +217,20 @@ pub fn set_token(key: String, value: String) -> Result<(), String> {
                 old.zeroize();
             }
         }
+        "github_instance_url" => {
+            let _ = tokens.github_instance_url.replace(value);
+        }
+        "github_api_url" => {
+            let _ = tokens.github_api_url.replace(value);
+        }
+        "github_app_id" => {
+            let _ = tokens.github_app_id.replace(value);
+        }
+        "github_app_private_key" => {
+            if let Some(mut old) = tokens.github_app_private_key.replace(value) {
+                old.zeroize();
+            }
+        }
         "gitlab_token" => {
             if let Some(mut old) = tokens.gitlab_token.replace(value) {
                 old.zeroize();
@@ -128,6 +247,46 @@ pub fn set_token(key: String, value: String) -> Result<(), String> {
                 old.zeroize();
             }
         }
+        "github_webhook_secret" => {
+            if let Some(mut old) = tokens.github_webhook_secret.replace(value) {
+                old.zeroize();
+            }
+        }
+        "gitlab_webhook_token" => {
+            if let Some(mut old) = tokens.gitlab_webhook_token.replace(value) {
+                old.zeroize();
+            }
+        }
+        "jenkins_url" => {
+            let _ = tokens.jenkins_url.replace(value);
+        }
+        "jenkins_username" => {
+            let _ = tokens.jenkins_username.replace(value);
+        }
+        "jenkins_api_token" => {
+            if let Some(mut old) = tokens.jenkins_api_token.replace(value) {
+                old.zeroize();
+            }
+        }
+        "gitea_token" => {
+            if let Some(mut old) = tokens.gitea_token.replace(value) {
+                old.zeroize();
+            }
+        }
+        "gitea_instance_url" => {
+            let _ = tokens.gitea_instance_url.replace(value);
+        }
+        "bitbucket_url" => {
+            let _ = tokens.bitbucket_url.replace(value);
+        }
+        "bitbucket_username" => {
+            let _ = tokens.bitbucket_username.replace(value);
+        }
+        "bitbucket_token" => {
+            if let Some(mut old) = tokens.bitbucket_token.replace(value) {
+                old.zeroize();
+            }
+        }
         _ => return Err(format!("Unknown token key: {}", key)),
     }
 
@@ -165,6 +324,20 @@ pub fn delete_token(key: String) -> Result<(), String> {
                 s.zeroize();
             }
         }
+        "github_instance_url" => {
+            let _ = tokens.github_instance_url.take();
+        }
+        "github_api_url" => {
+            let _ = tokens.github_api_url.take();
+        }
+        "github_app_id" => {
+            let _ = tokens.github_app_id.take();
+        }
+        "github_app_private_key" => {
+            if let Some(mut s) = tokens.github_app_private_key.take() {
+                s.zeroize();
+            }
+        }
         "gitlab_token" => {
             if let Some(mut s) = tokens.gitlab_token.take() {
                 s.zeroize();
@@ -181,6 +354,46 @@ pub fn delete_token(key: String) -> Result<(), String> {
                 s.zeroize();
             }
         }
+        "github_webhook_secret" => {
+            if let Some(mut s) = tokens.github_webhook_secret.take() {
+                s.zeroize();
+            }
+        }
+        "gitlab_webhook_token" => {
+            if let Some(mut s) = tokens.gitlab_webhook_token.take() {
+                s.zeroize();
+            }
+        }
+        "jenkins_url" => {
+            let _ = tokens.jenkins_url.take();
+        }
+        "jenkins_username" => {
+            let _ = tokens.jenkins_username.take();
+        }
+        "jenkins_api_token" => {
+            if let Some(mut s) = tokens.jenkins_api_token.take() {
+                s.zeroize();
+            }
+        }
+        "gitea_token" => {
+            if let Some(mut s) = tokens.gitea_token.take() {
+                s.zeroize();
+            }
+        }
+        "gitea_instance_url" => {
+            let _ = tokens.gitea_instance_url.take();
+        }
+        "bitbucket_url" => {
+            let _ = tokens.bitbucket_url.take();
+        }
+        "bitbucket_username" => {
+            let _ = tokens.bitbucket_username.take();
+        }
+        "bitbucket_token" => {
+            if let Some(mut s) = tokens.bitbucket_token.take() {
+                s.zeroize();
+            }
+        }
         _ => return Err(format!("Unknown token key: {}", key)),
     }
 
@@ -193,6 +406,115 @@ pub fn delete_token(key: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Create a credential profile, or replace the one with the same name
+/// An empty token keeps the token of the profile being replaced
+#[tauri::command]
+pub fn set_credential_profile(mut profile: CredentialProfile) -> Result<(), String> {
+    profile.name = profile.name.trim().to_string();
+    profile.host = crate::util::git::instance_host(profile.host.trim()).to_lowercase();
+    profile.base_url = profile.base_url.trim().trim_end_matches('/').to_string();
+
+    if profile.name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if !PROFILE_PROVIDERS.contains(&profile.provider.as_str()) {
+        return Err(format!(
+            "Unsupported provider for credential profile: {} (expected one of: {})",
+            profile.provider,
+            PROFILE_PROVIDERS.join(", ")
+        ));
+    }
+    if profile.host.is_empty() || profile.base_url.is_empty() {
+        return Err("Profile host and base URL are required".to_string());
+    }
+    if profile.provider == "bitbucket" && profile.username.as_deref().unwrap_or("").is_empty() {
+        return Err("Bitbucket profiles require a username".to_string());
+    }
+
+    let cache = CREDENTIAL_CACHE
+        .get()
+        .ok_or_else(|| "Token cache not initialized".to_string())?;
+
+    let mut tokens = cache
+        .write()
+        .map_err(|e| format!("Failed to lock token cache: {}", e))?;
+
+    if tokens.credential_profiles.iter().any(|existing| {
+        existing.name != profile.name
+            && existing.provider == profile.provider
+            && existing.host == profile.host
+    }) {
+        return Err(format!(
+            "Another {} profile already uses host {}",
+            profile.provider, profile.host
+        ));
+    }
+
+    if profile.token.is_empty() {
+        profile.token = tokens
+            .credential_profiles
+            .iter()
+            .find(|existing| existing.name == profile.name)
+            .map(|existing| existing.token.clone())
+            .ok_or_else(|| "Profile token is required".to_string())?;
+    }
+
+    // Clone original state for rollback on save failure
+    let original = tokens.clone();
+
+    match tokens
+        .credential_profiles
+        .iter_mut()
+        .find(|existing| existing.name == profile.name)
+    {
+        Some(existing) => {
+            let mut old = std::mem::replace(existing, profile);
+            old.token.zeroize();
+        }
+        None => tokens.credential_profiles.push(profile),
+    }
+
+    // Save entire bundle to keyring; revert on failure to keep cache consistent
+    if let Err(e) = save_all_tokens_to_keyring(&tokens) {
+        *tokens = original;
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_credential_profile(name: String) -> Result<(), String> {
+    let cache = CREDENTIAL_CACHE
+        .get()
+        .ok_or_else(|| "Token cache not initialized".to_string())?;
+
+    let mut tokens = cache
+        .write()
+        .map_err(|e| format!("Failed to lock token cache: {}", e))?;
+
+    // Clone original state for rollback on save failure
+    let original = tokens.clone();
+
+    let Some(index) = tokens
+        .credential_profiles
+        .iter()
+        .position(|profile| profile.name == name)
+    else {
+        return Err(format!("Credential profile {} not found", name));
+    };
+    let mut removed = tokens.credential_profiles.remove(index);
+    removed.token.zeroize();
+
+    // Save entire bundle to keyring; revert on failure to keep cache consistent
+    if let Err(e) = save_all_tokens_to_keyring(&tokens) {
+        *tokens = original;
+        return Err(e);
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 pub fn get_all_tokens() -> Result<AllTokens, String> {
     let cache = CREDENTIAL_CACHE
@@ -231,9 +553,31 @@ pub fn get_all_tokens_masked() -> Result<AllTokens, String> {
     Ok(AllTokens {
         amp_token: mask(&tokens.amp_token),
         github_token: mask(&tokens.github_token),
+        github_instance_url: mask(&tokens.github_instance_url),
+        github_api_url: mask(&tokens.github_api_url),
+        github_app_id: mask(&tokens.github_app_id),
+        github_app_private_key: mask(&tokens.github_app_private_key),
         gitlab_token: mask(&tokens.gitlab_token),
         gitlab_instance_url: mask(&tokens.gitlab_instance_url),
         sourcegraph_endpoint: mask(&tokens.sourcegraph_endpoint),
         sourcegraph_token: mask(&tokens.sourcegraph_token),
+        github_webhook_secret: mask(&tokens.github_webhook_secret),
+        gitlab_webhook_token: mask(&tokens.gitlab_webhook_token),
+        jenkins_url: mask(&tokens.jenkins_url),
+        jenkins_username: mask(&tokens.jenkins_username),
+        jenkins_api_token: mask(&tokens.jenkins_api_token),
+        gitea_token: mask(&tokens.gitea_token),
+        gitea_instance_url: mask(&tokens.gitea_instance_url),
+        bitbucket_url: mask(&tokens.bitbucket_url),
+        bitbucket_username: mask(&tokens.bitbucket_username),
+        bitbucket_token: mask(&tokens.bitbucket_token),
+        credential_profiles: tokens
+            .credential_profiles
+            .iter()
+            .map(|profile| CredentialProfile {
+                token: mask(&Some(profile.token.clone())).unwrap_or_default(),
+                ..profile.clone()
+            })
+            .collect(),
     })
 }
@@ -0,0 +1,46 @@
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::db::store::Store;
+use crate::webhooks::WebhookServerStatus;
+
+/// Get whether the local webhook listener is running and where to point webhooks
+#[tauri::command]
+pub fn get_webhook_server_status() -> Result<WebhookServerStatus, String> {
+    Ok(crate::webhooks::webhook_server_status())
+}
+
+/// Start the local webhook listener and keep it enabled across restarts
+#[tauri::command]
+pub fn start_webhook_server(
+    port: Option<u16>,
+    app: AppHandle,
+    store: tauri::State<'_, Mutex<Store>>,
+) -> Result<WebhookServerStatus, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    let port = match port {
+        Some(port) => port,
+        None => store.get_webhook_port().map_err(|e| e.to_string())?,
+    };
+
+    crate::webhooks::start_webhook_server(app, port).map_err(|e| e.to_string())?;
+
+    store
+        .set_setting("webhook_port", &port.to_string())
+        .map_err(|e| e.to_string())?;
+    store
+        .set_setting("webhook_enabled", "true")
+        .map_err(|e| e.to_string())?;
+
+    Ok(crate::webhooks::webhook_server_status())
+}
+
+/// Stop the local webhook listener and disable it on startup
+#[tauri::command]
+pub fn stop_webhook_server(store: tauri::State<'_, Mutex<Store>>) -> Result<bool, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store
+        .set_setting("webhook_enabled", "false")
+        .map_err(|e| e.to_string())?;
+    Ok(crate::webhooks::stop_webhook_server())
+}
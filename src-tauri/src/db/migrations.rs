@@ -63,6 +63,58 @@ pub const MIGRATIONS: &[Migration] = &[
         version: 14,
         up: migration_14,
     },
+    Migration {
+        version: 15,
+        up: migration_15,
+    },
+    Migration {
+        version: 16,
+        up: migration_16,
+    },
+    Migration {
+        version: 17,
+        up: migration_17,
+    },
+    Migration {
+        version: 18,
+        up: migration_18,
+    },
+    Migration {
+        version: 19,
+        up: migration_19,
+    },
+    Migration {
+        version: 20,
+        up: migration_20,
+    },
+    Migration {
+        version: 21,
+        up: migration_21,
+    },
+    Migration {
+        version: 22,
+        up: migration_22,
+    },
+    Migration {
+        version: 23,
+        up: migration_23,
+    },
+    Migration {
+        version: 24,
+        up: migration_24,
+    },
+    Migration {
+        version: 25,
+        up: migration_25,
+    },
+    Migration {
+        version: 26,
+        up: migration_26,
+    },
+    Migration {
+        version: 27,
+        up: migration_27,
+    },
 ];
 
 fn migration_1(conn: &Connection) -> Result<()> {
@@ -281,6 +333,263 @@ fn migration_14(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+fn migration_15(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Individual CI checks per execution and commit
+		CREATE TABLE ci_checks (
+			id TEXT PRIMARY KEY,
+			execution_id TEXT NOT NULL,
+			commit_sha TEXT NOT NULL,
+			provider TEXT NOT NULL,
+			context TEXT NOT NULL,
+			name TEXT NOT NULL,
+			state TEXT NOT NULL CHECK (state IN ('pending', 'passed', 'failed', 'skipped', 'not_configured', 'not_pushed')),
+			target_url TEXT,
+			description TEXT,
+			external_id TEXT,
+			raw_json TEXT,
+			first_seen_at INTEGER NOT NULL,
+			state_changed_at INTEGER NOT NULL,
+			completed_at INTEGER,
+			updated_at INTEGER NOT NULL,
+			UNIQUE (execution_id, commit_sha, context),
+			FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+		);
+
+		-- State transition history for each check
+		CREATE TABLE ci_check_transitions (
+			id INTEGER PRIMARY KEY AUTOINCREMENT,
+			check_id TEXT NOT NULL,
+			from_state TEXT,
+			to_state TEXT NOT NULL,
+			changed_at INTEGER NOT NULL,
+			FOREIGN KEY (check_id) REFERENCES ci_checks(id) ON DELETE CASCADE
+		);
+
+		CREATE INDEX idx_ci_checks_execution ON ci_checks (execution_id, commit_sha);
+		CREATE INDEX idx_ci_check_transitions_check ON ci_check_transitions (check_id, changed_at);
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_16(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Required/ignored CI check patterns per promptset (repository_id NULL)
+		-- with optional per-repository overrides
+		CREATE TABLE ci_check_policies (
+			id INTEGER PRIMARY KEY AUTOINCREMENT,
+			promptset_id TEXT NOT NULL,
+			repository_id TEXT,
+			required_checks TEXT NOT NULL DEFAULT '[]',
+			ignored_checks TEXT NOT NULL DEFAULT '[]',
+			discover_required INTEGER NOT NULL DEFAULT 0,
+			updated_at INTEGER NOT NULL,
+			FOREIGN KEY (promptset_id) REFERENCES promptsets(id) ON DELETE CASCADE,
+			FOREIGN KEY (repository_id) REFERENCES repositories(id) ON DELETE CASCADE
+		);
+
+		CREATE UNIQUE INDEX idx_ci_check_policies_scope ON ci_check_policies (promptset_id, COALESCE(repository_id, ''));
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_17(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Executions whose CI is still being watched, so polling survives restarts
+		CREATE TABLE ci_watches (
+			execution_id TEXT PRIMARY KEY,
+			commit_sha TEXT NOT NULL,
+			attempts INTEGER NOT NULL DEFAULT 0,
+			next_poll_at INTEGER NOT NULL,
+			last_polled_at INTEGER,
+			last_error TEXT,
+			created_at INTEGER NOT NULL,
+			FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+		);
+
+		CREATE INDEX idx_ci_watches_next_poll ON ci_watches (next_poll_at);
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_18(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- CI system used for a repository when it isn't the git host's own (e.g. Jenkins)
+		CREATE TABLE repository_ci_configs (
+			repository_id TEXT PRIMARY KEY,
+			ci_provider TEXT NOT NULL,
+			config TEXT NOT NULL DEFAULT '{}',
+			updated_at INTEGER NOT NULL,
+			FOREIGN KEY (repository_id) REFERENCES repositories(id) ON DELETE CASCADE
+		);
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_19(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Saved Sourcegraph query re-resolved into the promptset's repositories on every run
+		ALTER TABLE promptsets ADD COLUMN repository_query TEXT;
+
+		-- What the saved query resolved to for a revision run
+		CREATE TABLE repository_query_snapshots (
+			id INTEGER PRIMARY KEY AUTOINCREMENT,
+			promptset_id TEXT NOT NULL,
+			revision_id TEXT NOT NULL,
+			query TEXT NOT NULL,
+			matched_repository_ids TEXT NOT NULL DEFAULT '[]',
+			added_repository_ids TEXT NOT NULL DEFAULT '[]',
+			skipped_repository_ids TEXT NOT NULL DEFAULT '[]',
+			unsupported_repositories TEXT NOT NULL DEFAULT '[]',
+			created_at INTEGER NOT NULL,
+			FOREIGN KEY (promptset_id) REFERENCES promptsets(id) ON DELETE CASCADE,
+			FOREIGN KEY (revision_id) REFERENCES prompt_revisions(id) ON DELETE CASCADE
+		);
+
+		CREATE INDEX idx_repository_query_snapshots_revision ON repository_query_snapshots (revision_id, created_at);
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_20(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Applicability check evaluated against the admin clone before an execution runs
+		ALTER TABLE promptsets ADD COLUMN applicability_pattern TEXT;
+		ALTER TABLE promptsets ADD COLUMN applicability_path_glob TEXT;
+
+		-- Why an execution was skipped instead of run
+		ALTER TABLE executions ADD COLUMN skip_reason TEXT;
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_21(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Provider metadata refreshed alongside the default branch
+		ALTER TABLE repositories ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
+		ALTER TABLE repositories ADD COLUMN disabled INTEGER NOT NULL DEFAULT 0;
+		ALTER TABLE repositories ADD COLUMN visibility TEXT;
+		ALTER TABLE repositories ADD COLUMN language TEXT;
+		ALTER TABLE repositories ADD COLUMN topics TEXT NOT NULL DEFAULT '[]';
+		ALTER TABLE repositories ADD COLUMN size_kb INTEGER;
+		ALTER TABLE repositories ADD COLUMN pushed_at INTEGER;
+		-- NULL when the provider doesn't report push rights
+		ALTER TABLE repositories ADD COLUMN can_push INTEGER;
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_22(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Free-form repository labels such as team:payments; source is manual, topic or catalog
+		CREATE TABLE repository_tags (
+			repository_id TEXT NOT NULL,
+			tag TEXT NOT NULL,
+			source TEXT NOT NULL DEFAULT 'manual',
+			created_at INTEGER NOT NULL,
+			PRIMARY KEY (repository_id, tag),
+			FOREIGN KEY (repository_id) REFERENCES repositories(id) ON DELETE CASCADE
+		);
+
+		CREATE INDEX idx_repository_tags_tag ON repository_tags (tag);
+
+		-- Tag expression whose matching repositories a promptset targets on every run
+		ALTER TABLE promptsets ADD COLUMN repository_tag_expression TEXT;
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_23(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Former identities of repositories renamed or transferred on their provider
+		CREATE TABLE repository_renames (
+			id INTEGER PRIMARY KEY AUTOINCREMENT,
+			repository_id TEXT NOT NULL,
+			old_provider_id TEXT NOT NULL,
+			new_provider_id TEXT NOT NULL,
+			old_name TEXT,
+			renamed_at INTEGER NOT NULL,
+			FOREIGN KEY (repository_id) REFERENCES repositories(id) ON DELETE CASCADE
+		);
+
+		CREATE INDEX idx_repository_renames_repository ON repository_renames (repository_id, renamed_at);
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_24(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Default branch switches seen by metadata syncs, e.g. master -> main
+		CREATE TABLE default_branch_changes (
+			id INTEGER PRIMARY KEY AUTOINCREMENT,
+			repository_id TEXT NOT NULL,
+			old_branch TEXT NOT NULL,
+			new_branch TEXT NOT NULL,
+			changed_at INTEGER NOT NULL,
+			FOREIGN KEY (repository_id) REFERENCES repositories(id) ON DELETE CASCADE
+		);
+
+		CREATE INDEX idx_default_branch_changes_repository ON default_branch_changes (repository_id, changed_at);
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_25(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Admin clone activity, for scheduled gc and least-recently-used eviction
+		ALTER TABLE repositories ADD COLUMN clone_used_at INTEGER;
+		ALTER TABLE repositories ADD COLUMN clone_gc_at INTEGER;
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_26(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Clone strategy (e.g. blobless, shallow:50) and sparse checkout patterns (JSON array),
+		-- set per repository and as a default for a promptset's repositories
+		ALTER TABLE repositories ADD COLUMN clone_strategy TEXT;
+		ALTER TABLE repositories ADD COLUMN sparse_checkout TEXT NOT NULL DEFAULT '[]';
+		ALTER TABLE promptsets ADD COLUMN clone_strategy TEXT;
+		ALTER TABLE promptsets ADD COLUMN sparse_checkout TEXT NOT NULL DEFAULT '[]';
+		",
+    )?;
+    Ok(())
+}
+
+fn migration_27(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+		-- Watches that outlived ci_watch_max_hours are kept, marked expired, so a restart
+		-- doesn't queue them again
+		ALTER TABLE ci_watches ADD COLUMN expired_at INTEGER;
+		",
+    )?;
+    Ok(())
+}
+
 pub fn run_migrations(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS schema_migrations (
@@ -2,18 +2,48 @@ use anyhow::Result;
 use rusqlite::{params, Connection, OptionalExtension, Row};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 use super::migrations::run_migrations;
+use crate::ci::{CiCheck, CiCheckPolicy};
+use crate::git::RepoMetadata;
 use crate::types::{
     Analysis, AnalysisStatus, AnalysisType, CiStatus, CommitStatus, ExecutionStatus, PromptStatus,
     ValidationStatus,
 };
+use crate::util::tags::TagExpression;
 
 fn now_ms() -> i64 {
     chrono::Utc::now().timestamp_millis()
 }
 
+/// How much a check's state weighs when one context is reported more than once
+fn check_severity(state: CiStatus) -> u8 {
+    match state {
+        CiStatus::Failed => 3,
+        CiStatus::Pending => 2,
+        CiStatus::Passed => 1,
+        CiStatus::Skipped | CiStatus::NotConfigured | CiStatus::NotPushed => 0,
+    }
+}
+
+/// One check per context, in first-reported order; the worst state wins, then the latest report
+fn dedupe_checks(checks: &[CiCheck]) -> Vec<&CiCheck> {
+    let mut deduped: Vec<&CiCheck> = Vec::new();
+    for check in checks {
+        match deduped.iter_mut().find(|c| c.context == check.context) {
+            Some(kept) => {
+                if check_severity(check.state) >= check_severity(kept.state) {
+                    *kept = check;
+                }
+            }
+            None => deduped.push(check),
+        }
+    }
+    deduped
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Repository {
@@ -22,10 +52,80 @@ pub struct Repository {
     pub provider_id: String,
     pub name: Option<String>,
     pub default_branch: Option<String>,
+    pub archived: bool,
+    pub disabled: bool,
+    /// "public", "internal" or "private"
+    pub visibility: Option<String>,
+    pub language: Option<String>,
+    pub topics: Vec<String>,
+    pub size_kb: Option<i64>,
+    /// Last push to any branch (ms since epoch)
+    pub pushed_at: Option<i64>,
+    /// Whether the configured token may push; None when the provider doesn't say
+    pub can_push: Option<bool>,
+    /// Normalized tags from every source, sorted
+    pub tags: Vec<String>,
+    /// How the admin clone is made, e.g. "blobless" or "shallow:50"; None for the promptset's
+    pub clone_strategy: Option<String>,
+    /// Sparse checkout patterns of execution worktrees; empty checks out everything
+    pub sparse_checkout: Vec<String>,
     pub last_synced_at: Option<i64>,
     pub created_at: i64,
 }
 
+/// A tag on a repository and where it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryTag {
+    pub repository_id: String,
+    pub tag: String,
+    /// "manual", "topic" or "catalog"; imports only replace tags of their own source
+    pub source: String,
+    pub created_at: i64,
+}
+
+/// A former identity of a repository renamed or transferred on its provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryRename {
+    pub id: i64,
+    pub repository_id: String,
+    pub old_provider_id: String,
+    pub new_provider_id: String,
+    pub old_name: Option<String>,
+    pub renamed_at: i64,
+}
+
+/// A repository whose default branch changed after a promptset last ran on it, so that run
+/// was based on a branch the repository no longer builds from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultBranchChange {
+    pub promptset_id: String,
+    pub repository_id: String,
+    /// Default branch when the promptset last ran
+    pub old_branch: String,
+    pub new_branch: String,
+    /// Latest change since that run
+    pub changed_at: i64,
+    pub last_run_at: i64,
+}
+
+/// When a repository's admin clone was last fetched and last garbage collected
+#[derive(Debug, Clone, Default)]
+pub struct CloneActivity {
+    pub used_at: Option<i64>,
+    pub gc_at: Option<i64>,
+}
+
+/// A tag and how many repositories carry it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagCount {
+    pub tag: String,
+    pub repository_count: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PromptSetStats {
@@ -43,6 +143,18 @@ pub struct PromptSet {
     pub repository_ids: Vec<String>,
     pub validation_prompt: Option<String>,
     pub auto_validate: bool,
+    /// Saved Sourcegraph query re-resolved into `repository_ids` on every run
+    pub repository_query: Option<String>,
+    /// Regex some file must contain at the default branch for the promptset to run
+    pub applicability_pattern: Option<String>,
+    /// Glob the files checked against `applicability_pattern` must match
+    pub applicability_path_glob: Option<String>,
+    /// Tag expression whose matching repositories are targeted alongside `repository_ids`
+    pub repository_tag_expression: Option<String>,
+    /// Clone strategy of repositories that don't set their own
+    pub clone_strategy: Option<String>,
+    /// Sparse checkout patterns added to each repository's own
+    pub sparse_checkout: Vec<String>,
     pub created_at: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stats: Option<PromptSetStats>,
@@ -97,11 +209,102 @@ pub struct Execution {
     pub ci_checked_at: Option<i64>,
     pub ci_url: Option<String>,
     pub error_message: Option<String>,
+    /// Why a skipped execution didn't run
+    pub skip_reason: Option<String>,
     pub created_at: i64,
     pub completed_at: Option<i64>,
 }
 
-const SELECT_REPOSITORY: &str = "SELECT id, provider, provider_id, name, default_branch, last_synced_at, created_at FROM repositories";
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiCheckRecord {
+    pub id: String,
+    pub execution_id: String,
+    pub commit_sha: String,
+    pub provider: String,
+    pub context: String,
+    pub name: String,
+    pub state: CiStatus,
+    pub target_url: Option<String>,
+    pub description: Option<String>,
+    pub external_id: Option<String>,
+    pub raw_json: Option<String>,
+    pub first_seen_at: i64,
+    pub state_changed_at: i64,
+    pub completed_at: Option<i64>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiCheckTransition {
+    pub check_id: String,
+    pub from_state: Option<CiStatus>,
+    pub to_state: CiStatus,
+    pub changed_at: i64,
+}
+
+/// Per-check failure counts across the executions of a revision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiCheckFailureStat {
+    pub context: String,
+    pub name: String,
+    pub total: i64,
+    pub failed: i64,
+    pub passed: i64,
+}
+
+/// An execution whose CI status is still being polled by the background watcher
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiWatch {
+    pub execution_id: String,
+    pub commit_sha: String,
+    pub attempts: i64,
+    pub next_poll_at: i64,
+    pub last_polled_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    /// Set once the watch outlived ci_watch_max_hours; it's no longer polled
+    pub expired_at: Option<i64>,
+}
+
+/// Repositories a promptset's saved query resolved to for a revision run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryQuerySnapshot {
+    pub id: i64,
+    pub promptset_id: String,
+    pub revision_id: String,
+    pub query: String,
+    pub matched_repository_ids: Vec<String>,
+    /// Matches that weren't among the promptset's repositories yet
+    pub added_repository_ids: Vec<String>,
+    /// Promptset repositories that no longer match; they're skipped for the run
+    pub skipped_repository_ids: Vec<String>,
+    /// Matches on code hosts Maestro has no provider for (Sourcegraph repository names)
+    pub unsupported_repositories: Vec<String>,
+    pub created_at: i64,
+}
+
+/// CI system override for a repository; provider_cfg for its CiContext is `config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryCiConfig {
+    pub repository_id: String,
+    pub ci_provider: String,
+    pub config: serde_json::Value,
+    pub updated_at: i64,
+}
+
+const SELECT_CI_WATCH: &str = "SELECT execution_id, commit_sha, attempts, next_poll_at, last_polled_at, last_error, created_at, expired_at FROM ci_watches";
+
+const SELECT_REPOSITORY: &str = "SELECT id, provider, provider_id, name, default_branch, archived, disabled,
+	visibility, language, topics, size_kb, pushed_at, can_push, clone_strategy, sparse_checkout,
+	last_synced_at, created_at,
+	(SELECT json_group_array(tag) FROM (SELECT tag FROM repository_tags t WHERE t.repository_id = repositories.id ORDER BY tag)) AS tags
+FROM repositories";
 
 const SELECT_EXECUTION: &str = "
 SELECT 
@@ -114,7 +317,7 @@ SELECT
 	COALESCE(lines_removed, 0) AS lines_removed,
 	COALESCE(commit_status, 'none') AS commit_status,
 	commit_sha, committed_at, parent_sha, branch,
-	ci_status, ci_checked_at, ci_url, error_message,
+	ci_status, ci_checked_at, ci_url, error_message, skip_reason,
 	created_at, completed_at
 FROM executions";
 
@@ -134,6 +337,29 @@ fn map_repository(row: &Row) -> rusqlite::Result<Repository> {
         provider_id: row.get("provider_id")?,
         name: row.get("name")?,
         default_branch: row.get("default_branch")?,
+        archived: row.get("archived")?,
+        disabled: row.get("disabled")?,
+        visibility: row.get("visibility")?,
+        language: row.get("language")?,
+        topics: serde_json::from_str(&row.get::<_, String>("topics")?).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        size_kb: row.get("size_kb")?,
+        pushed_at: row.get("pushed_at")?,
+        can_push: row.get("can_push")?,
+        tags: serde_json::from_str(&row.get::<_, String>("tags")?).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        clone_strategy: row.get("clone_strategy")?,
+        sparse_checkout: serde_json::from_str(&row.get::<_, String>("sparse_checkout")?).map_err(
+            |e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    0,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            },
+        )?,
         last_synced_at: row.get("last_synced_at")?,
         created_at: row.get("created_at")?,
     })
@@ -167,11 +393,51 @@ fn map_execution(row: &Row) -> rusqlite::Result<Execution> {
         ci_checked_at: row.get("ci_checked_at")?,
         ci_url: row.get("ci_url")?,
         error_message: row.get("error_message")?,
+        skip_reason: row.get("skip_reason")?,
         created_at: row.get("created_at")?,
         completed_at: row.get("completed_at")?,
     })
 }
 
+const SELECT_CI_CHECK: &str = "
+SELECT
+	id, execution_id, commit_sha, provider, context, name, state, target_url,
+	description, external_id, raw_json, first_seen_at, state_changed_at, completed_at, updated_at
+FROM ci_checks";
+
+fn map_ci_check(row: &Row) -> rusqlite::Result<CiCheckRecord> {
+    Ok(CiCheckRecord {
+        id: row.get("id")?,
+        execution_id: row.get("execution_id")?,
+        commit_sha: row.get("commit_sha")?,
+        provider: row.get("provider")?,
+        context: row.get("context")?,
+        name: row.get("name")?,
+        state: row.get("state")?,
+        target_url: row.get("target_url")?,
+        description: row.get("description")?,
+        external_id: row.get("external_id")?,
+        raw_json: row.get("raw_json")?,
+        first_seen_at: row.get("first_seen_at")?,
+        state_changed_at: row.get("state_changed_at")?,
+        completed_at: row.get("completed_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn map_ci_watch(row: &Row) -> rusqlite::Result<CiWatch> {
+    Ok(CiWatch {
+        execution_id: row.get("execution_id")?,
+        commit_sha: row.get("commit_sha")?,
+        attempts: row.get("attempts")?,
+        next_poll_at: row.get("next_poll_at")?,
+        last_polled_at: row.get("last_polled_at")?,
+        last_error: row.get("last_error")?,
+        created_at: row.get("created_at")?,
+        expired_at: row.get("expired_at")?,
+    })
+}
+
 fn map_analysis(row: &Row) -> rusqlite::Result<Analysis> {
     Ok(Analysis {
         id: row.get("id")?,
@@ -224,11 +490,41 @@ impl Store {
             provider_id: provider_id.to_string(),
             name: None,
             default_branch: None,
+            archived: false,
+            disabled: false,
+            visibility: None,
+            language: None,
+            topics: Vec::new(),
+            size_kb: None,
+            pushed_at: None,
+            can_push: None,
+            tags: Vec::new(),
+            clone_strategy: None,
+            sparse_checkout: Vec::new(),
             last_synced_at: None,
             created_at: now,
         })
     }
 
+    /// Create a repository unless it already exists, filling in its default branch when known
+    /// and not yet recorded; a changed branch is left to metadata sync, which records the change
+    pub fn upsert_repository(
+        &self,
+        provider: &str,
+        provider_id: &str,
+        default_branch: Option<&str>,
+    ) -> Result<Repository> {
+        let mut repo = match self.find_repository(provider, provider_id)? {
+            Some(repo) => repo,
+            None => self.create_repository(provider, provider_id)?,
+        };
+        if let (Some(default_branch), None) = (default_branch, &repo.default_branch) {
+            self.update_repository_default_branch(&repo.id, default_branch)?;
+            repo.default_branch = Some(default_branch.to_string());
+        }
+        Ok(repo)
+    }
+
     pub fn update_repository_name(&self, id: &str, name: &str) -> Result<()> {
         self.conn.execute(
             "UPDATE repositories SET name = ?1, last_synced_at = ?2 WHERE id = ?3",
@@ -245,6 +541,200 @@ impl Store {
         Ok(())
     }
 
+    pub fn update_repository_checkout(
+        &self,
+        id: &str,
+        clone_strategy: Option<&str>,
+        sparse_checkout: &[String],
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE repositories SET clone_strategy = ?1, sparse_checkout = ?2 WHERE id = ?3",
+            params![clone_strategy, serde_json::to_string(sparse_checkout)?, id],
+        )?;
+        Ok(())
+    }
+
+    /// Point a repository at its new provider identity, recording the old one
+    pub fn rename_repository(
+        &mut self,
+        id: &str,
+        new_provider_id: &str,
+        new_name: Option<&str>,
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        let (old_provider_id, old_name): (String, Option<String>) = tx.query_row(
+            "SELECT provider_id, name FROM repositories WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        tx.execute(
+            "INSERT INTO repository_renames (repository_id, old_provider_id, new_provider_id, old_name, renamed_at)
+			 VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id, old_provider_id, new_provider_id, old_name, now_ms()],
+        )?;
+        tx.execute(
+            "UPDATE repositories SET provider_id = ?1, name = ?2 WHERE id = ?3",
+            params![new_provider_id, new_name, id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_repository_renames(&self, repository_id: &str) -> Result<Vec<RepositoryRename>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, repository_id, old_provider_id, new_provider_id, old_name, renamed_at
+			 FROM repository_renames WHERE repository_id = ?1 ORDER BY renamed_at DESC, id DESC",
+        )?;
+        let renames = stmt
+            .query_map([repository_id], |row| {
+                Ok(RepositoryRename {
+                    id: row.get("id")?,
+                    repository_id: row.get("repository_id")?,
+                    old_provider_id: row.get("old_provider_id")?,
+                    new_provider_id: row.get("new_provider_id")?,
+                    old_name: row.get("old_name")?,
+                    renamed_at: row.get("renamed_at")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(renames)
+    }
+
+    /// Record everything the provider reported about a repository, marking it as synced
+    /// Returns whether its default branch changed, which is kept in `default_branch_changes`
+    pub fn update_repository_metadata(&self, id: &str, metadata: &RepoMetadata) -> Result<bool> {
+        let now = now_ms();
+        let changed = self.conn.execute(
+            "INSERT INTO default_branch_changes (repository_id, old_branch, new_branch, changed_at)
+			 SELECT id, default_branch, ?2, ?3 FROM repositories
+			 WHERE id = ?1 AND default_branch IS NOT NULL AND default_branch != ?2",
+            params![id, metadata.default_branch, now],
+        )? > 0;
+        self.conn.execute(
+            "UPDATE repositories SET default_branch = ?1, archived = ?2, disabled = ?3,
+				visibility = ?4, language = ?5, topics = ?6, size_kb = ?7, pushed_at = ?8,
+				can_push = ?9, last_synced_at = ?10
+			WHERE id = ?11",
+            params![
+                metadata.default_branch,
+                metadata.archived,
+                metadata.disabled,
+                metadata.visibility,
+                metadata.language,
+                serde_json::to_string(&metadata.topics)?,
+                metadata.size_kb,
+                metadata.pushed_at,
+                metadata.can_push,
+                now,
+                id
+            ],
+        )?;
+        Ok(changed)
+    }
+
+    /// Repositories whose metadata was never synced or last synced before `synced_before`,
+    /// least recently synced first
+    pub fn get_repositories_due_for_sync(&self, synced_before: i64) -> Result<Vec<Repository>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "{SELECT_REPOSITORY} WHERE last_synced_at IS NULL OR last_synced_at < ?1
+			 ORDER BY last_synced_at IS NOT NULL, last_synced_at"
+        ))?;
+        let repositories = stmt
+            .query_map([synced_before], map_repository)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(repositories)
+    }
+
+    /// Repositories whose default branch changed after a promptset last ran on them, for one
+    /// promptset or all of them. A branch that switched back to the one of the last run isn't
+    /// reported
+    pub fn get_default_branch_changes(
+        &self,
+        promptset_id: Option<&str>,
+    ) -> Result<Vec<DefaultBranchChange>> {
+        let mut stmt = self.conn.prepare_cached(
+            "WITH last_runs AS (
+				SELECT promptset_id, repository_id, MAX(created_at) AS last_run_at
+				FROM executions
+				WHERE ?1 IS NULL OR promptset_id = ?1
+				GROUP BY promptset_id, repository_id
+			),
+			changes AS (
+				SELECT lr.promptset_id, lr.repository_id, lr.last_run_at,
+					r.default_branch AS new_branch,
+					(SELECT c.old_branch FROM default_branch_changes c
+					 WHERE c.repository_id = lr.repository_id AND c.changed_at > lr.last_run_at
+					 ORDER BY c.changed_at, c.id LIMIT 1) AS old_branch,
+					(SELECT MAX(c.changed_at) FROM default_branch_changes c
+					 WHERE c.repository_id = lr.repository_id AND c.changed_at > lr.last_run_at) AS changed_at
+				FROM last_runs lr
+				JOIN repositories r ON r.id = lr.repository_id
+			)
+			SELECT promptset_id, repository_id, old_branch, new_branch, changed_at, last_run_at
+			FROM changes
+			WHERE old_branch IS NOT NULL AND old_branch != new_branch
+			ORDER BY changed_at DESC",
+        )?;
+        let changes = stmt
+            .query_map([promptset_id], |row| {
+                Ok(DefaultBranchChange {
+                    promptset_id: row.get("promptset_id")?,
+                    repository_id: row.get("repository_id")?,
+                    old_branch: row.get("old_branch")?,
+                    new_branch: row.get("new_branch")?,
+                    changed_at: row.get("changed_at")?,
+                    last_run_at: row.get("last_run_at")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(changes)
+    }
+
+    /// Record that a repository's admin clone was just fetched
+    pub fn touch_repository_clone(&self, id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE repositories SET clone_used_at = ?1 WHERE id = ?2",
+            params![now_ms(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Record that a repository's admin clone was just garbage collected
+    pub fn mark_repository_clone_gc(&self, id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE repositories SET clone_gc_at = ?1 WHERE id = ?2",
+            params![now_ms(), id],
+        )?;
+        Ok(())
+    }
+
+    /// When a repository's admin clone was last fetched
+    pub fn get_clone_used_at(&self, id: &str) -> Result<Option<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT clone_used_at FROM repositories WHERE id = ?1")?;
+        Ok(stmt.query_row([id], |row| row.get(0)).optional()?.flatten())
+    }
+
+    /// Admin clone activity of every repository, by repository id
+    pub fn get_clone_activity(&self) -> Result<HashMap<String, CloneActivity>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, clone_used_at, clone_gc_at FROM repositories")?;
+        let activity = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>("id")?,
+                    CloneActivity {
+                        used_at: row.get("clone_used_at")?,
+                        gc_at: row.get("clone_gc_at")?,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+        Ok(activity)
+    }
+
     pub fn get_repository(&self, id: &str) -> Result<Option<Repository>> {
         let mut stmt = self
             .conn
@@ -273,6 +763,122 @@ impl Store {
         Ok(repos)
     }
 
+    // Repository tag operations
+    /// Add normalized tags to a repository; tags it already has take the new source, so adding
+    /// an imported tag by hand keeps it through the next import
+    pub fn add_repository_tags(
+        &self,
+        repository_id: &str,
+        tags: &[String],
+        source: &str,
+    ) -> Result<()> {
+        let now = now_ms();
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO repository_tags (repository_id, tag, source, created_at) VALUES (?1, ?2, ?3, ?4)
+			 ON CONFLICT(repository_id, tag) DO UPDATE SET source = excluded.source",
+        )?;
+        for tag in tags {
+            stmt.execute(params![repository_id, tag, source, now])?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_repository_tag(&self, repository_id: &str, tag: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "DELETE FROM repository_tags WHERE repository_id = ?1 AND tag = ?2",
+            params![repository_id, tag],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Replace the tags an import source gave a repository, leaving tags from other sources
+    pub fn replace_repository_tags(
+        &mut self,
+        repository_id: &str,
+        source: &str,
+        tags: &[String],
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM repository_tags WHERE repository_id = ?1 AND source = ?2",
+            params![repository_id, source],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO repository_tags (repository_id, tag, source, created_at) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            let now = now_ms();
+            for tag in tags {
+                stmt.execute(params![repository_id, tag, source, now])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_repository_tags(&self, repository_id: &str) -> Result<Vec<RepositoryTag>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT repository_id, tag, source, created_at FROM repository_tags WHERE repository_id = ?1 ORDER BY tag",
+        )?;
+        let tags = stmt
+            .query_map([repository_id], |row| {
+                Ok(RepositoryTag {
+                    repository_id: row.get("repository_id")?,
+                    tag: row.get("tag")?,
+                    source: row.get("source")?,
+                    created_at: row.get("created_at")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tags)
+    }
+
+    /// Every tag in use, with how many repositories carry it
+    pub fn get_tag_counts(&self) -> Result<Vec<TagCount>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tag, COUNT(*) AS repository_count FROM repository_tags GROUP BY tag ORDER BY tag",
+        )?;
+        let counts = stmt
+            .query_map([], |row| {
+                Ok(TagCount {
+                    tag: row.get("tag")?,
+                    repository_count: row.get("repository_count")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(counts)
+    }
+
+    /// Ids of the repositories whose tags match an expression, oldest first
+    pub fn find_repository_ids_by_tags(&self, expression: &TagExpression) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT r.id, t.tag FROM repositories r
+			LEFT JOIN repository_tags t ON t.repository_id = r.id
+			ORDER BY r.created_at ASC, r.id",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut repositories: Vec<(String, HashSet<String>)> = Vec::new();
+        for (id, tag) in rows {
+            if repositories.last().map(|(last, _)| last) != Some(&id) {
+                repositories.push((id, HashSet::new()));
+            }
+            if let (Some(tag), Some((_, tags))) = (tag, repositories.last_mut()) {
+                tags.insert(tag);
+            }
+        }
+
+        Ok(repositories
+            .into_iter()
+            .filter(|(_, tags)| expression.matches(tags))
+            .map(|(id, _)| id)
+            .collect())
+    }
+
     pub fn create_promptset(
         &mut self,
         name: &str,
@@ -307,13 +913,19 @@ impl Store {
             repository_ids,
             validation_prompt,
             auto_validate,
+            repository_query: None,
+            applicability_pattern: None,
+            applicability_path_glob: None,
+            repository_tag_expression: None,
+            clone_strategy: None,
+            sparse_checkout: Vec::new(),
             created_at: now,
             stats: None,
         })
     }
 
     pub fn get_all_promptsets(&self) -> Result<Vec<PromptSet>> {
-        let mut stmt = self.conn.prepare("SELECT id, name, created_at, validation_prompt, auto_validate FROM promptsets ORDER BY created_at DESC")?;
+        let mut stmt = self.conn.prepare("SELECT id, name, created_at, validation_prompt, auto_validate, repository_query, applicability_pattern, applicability_path_glob, repository_tag_expression, clone_strategy, sparse_checkout FROM promptsets ORDER BY created_at DESC")?;
         let mut rows = stmt.query([])?;
 
         let mut promptsets = Vec::new();
@@ -323,6 +935,12 @@ impl Store {
             let created_at: i64 = row.get(2)?;
             let validation_prompt: Option<String> = row.get(3)?;
             let auto_validate: bool = row.get::<_, i32>(4)? != 0;
+            let repository_query: Option<String> = row.get(5)?;
+            let applicability_pattern: Option<String> = row.get(6)?;
+            let applicability_path_glob: Option<String> = row.get(7)?;
+            let repository_tag_expression: Option<String> = row.get(8)?;
+            let clone_strategy: Option<String> = row.get(9)?;
+            let sparse_checkout: Vec<String> = serde_json::from_str(&row.get::<_, String>(10)?)?;
 
             let mut repo_stmt = self.conn.prepare_cached(
                 "SELECT repository_id FROM promptset_repositories WHERE promptset_id = ?1",
@@ -356,6 +974,12 @@ impl Store {
                 repository_ids: repo_ids,
                 validation_prompt,
                 auto_validate,
+                repository_query,
+                applicability_pattern,
+                applicability_path_glob,
+                repository_tag_expression,
+                clone_strategy,
+                sparse_checkout,
                 created_at,
                 stats,
             });
@@ -365,7 +989,7 @@ impl Store {
     }
 
     pub fn get_promptset(&self, id: &str) -> Result<Option<PromptSet>> {
-        let mut stmt = self.conn.prepare_cached("SELECT id, name, created_at, validation_prompt, auto_validate FROM promptsets WHERE id = ?1")?;
+        let mut stmt = self.conn.prepare_cached("SELECT id, name, created_at, validation_prompt, auto_validate, repository_query, applicability_pattern, applicability_path_glob, repository_tag_expression, clone_strategy, sparse_checkout FROM promptsets WHERE id = ?1")?;
         let result = stmt
             .query_row([id], |row| {
                 Ok((
@@ -374,11 +998,30 @@ impl Store {
                     row.get::<_, i64>(2)?,
                     row.get::<_, Option<String>>(3)?,
                     row.get::<_, i32>(4)? != 0,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
+                    row.get::<_, Option<String>>(8)?,
+                    row.get::<_, Option<String>>(9)?,
+                    row.get::<_, String>(10)?,
                 ))
             })
             .optional()?;
 
-        if let Some((id, name, created_at, validation_prompt, auto_validate)) = result {
+        if let Some((
+            id,
+            name,
+            created_at,
+            validation_prompt,
+            auto_validate,
+            repository_query,
+            applicability_pattern,
+            applicability_path_glob,
+            repository_tag_expression,
+            clone_strategy,
+            sparse_checkout,
+        )) = result
+        {
             let mut repo_stmt = self.conn.prepare_cached(
                 "SELECT repository_id FROM promptset_repositories WHERE promptset_id = ?1",
             )?;
@@ -392,6 +1035,12 @@ impl Store {
                 repository_ids: repo_ids,
                 validation_prompt,
                 auto_validate,
+                repository_query,
+                applicability_pattern,
+                applicability_path_glob,
+                repository_tag_expression,
+                clone_strategy,
+                sparse_checkout: serde_json::from_str(&sparse_checkout)?,
                 created_at,
                 stats: None,
             }))
@@ -402,7 +1051,7 @@ impl Store {
 
     pub fn find_promptset_by_prefix(&self, id_prefix: &str) -> Result<Option<PromptSet>> {
         let pattern = format!("{}%", id_prefix);
-        let mut stmt = self.conn.prepare_cached("SELECT id, name, created_at, validation_prompt, auto_validate FROM promptsets WHERE id LIKE ?1")?;
+        let mut stmt = self.conn.prepare_cached("SELECT id, name, created_at, validation_prompt, auto_validate, repository_query, applicability_pattern, applicability_path_glob, repository_tag_expression, clone_strategy, sparse_checkout FROM promptsets WHERE id LIKE ?1")?;
         let result = stmt
             .query_row([pattern], |row| {
                 Ok((
@@ -411,11 +1060,30 @@ impl Store {
                     row.get::<_, i64>(2)?,
                     row.get::<_, Option<String>>(3)?,
                     row.get::<_, i32>(4)? != 0,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
+                    row.get::<_, Option<String>>(8)?,
+                    row.get::<_, Option<String>>(9)?,
+                    row.get::<_, String>(10)?,
                 ))
             })
             .optional()?;
 
-        if let Some((id, name, created_at, validation_prompt, auto_validate)) = result {
+        if let Some((
+            id,
+            name,
+            created_at,
+            validation_prompt,
+            auto_validate,
+            repository_query,
+            applicability_pattern,
+            applicability_path_glob,
+            repository_tag_expression,
+            clone_strategy,
+            sparse_checkout,
+        )) = result
+        {
             let mut repo_stmt = self.conn.prepare_cached(
                 "SELECT repository_id FROM promptset_repositories WHERE promptset_id = ?1",
             )?;
@@ -429,6 +1097,12 @@ impl Store {
                 repository_ids: repo_ids,
                 validation_prompt,
                 auto_validate,
+                repository_query,
+                applicability_pattern,
+                applicability_path_glob,
+                repository_tag_expression,
+                clone_strategy,
+                sparse_checkout: serde_json::from_str(&sparse_checkout)?,
                 created_at,
                 stats: None,
             }))
@@ -482,6 +1156,129 @@ impl Store {
         Ok(())
     }
 
+    pub fn update_promptset_repository_query(&self, id: &str, query: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE promptsets SET repository_query = ?1 WHERE id = ?2",
+            params![query, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_promptset_applicability(
+        &self,
+        id: &str,
+        pattern: Option<&str>,
+        path_glob: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE promptsets SET applicability_pattern = ?1, applicability_path_glob = ?2 WHERE id = ?3",
+            params![pattern, path_glob, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_promptset_checkout(
+        &self,
+        id: &str,
+        clone_strategy: Option<&str>,
+        sparse_checkout: &[String],
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE promptsets SET clone_strategy = ?1, sparse_checkout = ?2 WHERE id = ?3",
+            params![clone_strategy, serde_json::to_string(sparse_checkout)?, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_promptset_repository_tag_expression(
+        &self,
+        id: &str,
+        expression: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE promptsets SET repository_tag_expression = ?1 WHERE id = ?2",
+            params![expression, id],
+        )?;
+        Ok(())
+    }
+
+    /// Store a snapshot, filling in its id and creation time
+    pub fn record_repository_query_snapshot(
+        &self,
+        mut snapshot: RepositoryQuerySnapshot,
+    ) -> Result<RepositoryQuerySnapshot> {
+        snapshot.created_at = now_ms();
+        self.conn.execute(
+            "INSERT INTO repository_query_snapshots (promptset_id, revision_id, query, matched_repository_ids, added_repository_ids, skipped_repository_ids, unsupported_repositories, created_at)
+			 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                snapshot.promptset_id,
+                snapshot.revision_id,
+                snapshot.query,
+                serde_json::to_string(&snapshot.matched_repository_ids)?,
+                serde_json::to_string(&snapshot.added_repository_ids)?,
+                serde_json::to_string(&snapshot.skipped_repository_ids)?,
+                serde_json::to_string(&snapshot.unsupported_repositories)?,
+                snapshot.created_at
+            ],
+        )?;
+        snapshot.id = self.conn.last_insert_rowid();
+        Ok(snapshot)
+    }
+
+    /// Snapshots recorded for a revision's runs, newest first
+    pub fn get_repository_query_snapshots(
+        &self,
+        revision_id: &str,
+    ) -> Result<Vec<RepositoryQuerySnapshot>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, promptset_id, revision_id, query, matched_repository_ids, added_repository_ids, skipped_repository_ids, unsupported_repositories, created_at
+			 FROM repository_query_snapshots WHERE revision_id = ?1 ORDER BY created_at DESC, id DESC",
+        )?;
+        let rows = stmt
+            .query_map([revision_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    [
+                        row.get::<_, String>(4)?,
+                        row.get::<_, String>(5)?,
+                        row.get::<_, String>(6)?,
+                        row.get::<_, String>(7)?,
+                    ],
+                    row.get::<_, i64>(8)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(
+                |(
+                    id,
+                    promptset_id,
+                    revision_id,
+                    query,
+                    [matched, added, skipped, unsupported],
+                    created_at,
+                )| {
+                    Ok(RepositoryQuerySnapshot {
+                        id,
+                        promptset_id,
+                        revision_id,
+                        query,
+                        matched_repository_ids: serde_json::from_str(&matched)?,
+                        added_repository_ids: serde_json::from_str(&added)?,
+                        skipped_repository_ids: serde_json::from_str(&skipped)?,
+                        unsupported_repositories: serde_json::from_str(&unsupported)?,
+                        created_at,
+                    })
+                },
+            )
+            .collect()
+    }
+
     pub fn create_prompt_revision(
         &self,
         promptset_id: &str,
@@ -640,6 +1437,7 @@ impl Store {
             ci_checked_at: None,
             ci_url: None,
             error_message: None,
+            skip_reason: None,
             created_at: now,
             completed_at: None,
         })
@@ -670,8 +1468,9 @@ impl Store {
 				ci_checked_at = COALESCE(?20, ci_checked_at),
 				ci_url = COALESCE(?21, ci_url),
 				error_message = COALESCE(?22, error_message),
-				completed_at = COALESCE(?23, completed_at)
-			WHERE id = ?24",
+				skip_reason = COALESCE(?23, skip_reason),
+				completed_at = COALESCE(?24, completed_at)
+			WHERE id = ?25",
             params![
                 u.status,
                 u.session_id,
@@ -695,6 +1494,7 @@ impl Store {
                 u.ci_checked_at,
                 u.ci_url,
                 u.error_message,
+                u.skip_reason,
                 u.completed_at,
                 id,
             ],
@@ -741,6 +1541,26 @@ impl Store {
         Ok(executions)
     }
 
+    pub fn find_executions_by_commit_sha(&self, commit_sha: &str) -> Result<Vec<Execution>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{SELECT_EXECUTION} WHERE commit_sha = ?1 ORDER BY created_at DESC"
+        ))?;
+        let executions = stmt
+            .query_map([commit_sha], map_execution)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(executions)
+    }
+
+    pub fn find_executions_by_branch(&self, branch: &str) -> Result<Vec<Execution>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{SELECT_EXECUTION} WHERE branch = ?1 ORDER BY created_at DESC"
+        ))?;
+        let executions = stmt
+            .query_map([branch], map_execution)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(executions)
+    }
+
     pub fn get_all_executions(&self) -> Result<Vec<Execution>> {
         let mut stmt = self
             .conn
@@ -751,6 +1571,25 @@ impl Store {
         Ok(executions)
     }
 
+    /// Repository id of every execution, by execution id
+    pub fn get_execution_repository_ids(&self) -> Result<HashMap<String, String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, repository_id FROM executions")?;
+        let ids = stmt
+            .query_map([], |row| Ok((row.get("id")?, row.get("repository_id")?)))?
+            .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+        Ok(ids)
+    }
+
+    /// Whether any execution of a repository is queued or running
+    pub fn has_active_executions(&self, repository_id: &str) -> Result<bool> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT EXISTS(SELECT 1 FROM executions WHERE repository_id = ?1 AND status IN ('pending', 'running'))",
+        )?;
+        Ok(stmt.query_row([repository_id], |row| row.get(0))?)
+    }
+
     pub fn delete_execution(&self, id: &str) -> Result<bool> {
         let result = self
             .conn
@@ -828,6 +1667,65 @@ impl Store {
             .map_err(|e| anyhow::anyhow!("Invalid max concurrent executions: {}", e))
     }
 
+    pub fn get_webhook_port(&self) -> Result<u16> {
+        match self.get_setting("webhook_port")? {
+            Some(value) => value
+                .parse::<u16>()
+                .map_err(|e| anyhow::anyhow!("Invalid webhook port: {}", e)),
+            None => Ok(crate::webhooks::DEFAULT_WEBHOOK_PORT),
+        }
+    }
+
+    pub fn get_webhook_enabled(&self) -> Result<bool> {
+        Ok(self.get_setting("webhook_enabled")?.as_deref() == Some("true"))
+    }
+
+    pub fn get_ci_poll_schedule(&self) -> Result<Vec<u64>> {
+        match self.get_setting("ci_poll_schedule")? {
+            Some(value) => crate::ci::parse_poll_schedule(&value),
+            None => Ok(crate::ci::DEFAULT_POLL_SCHEDULE.to_vec()),
+        }
+    }
+
+    /// Hours between background metadata syncs of each repository; 0 turns them off
+    pub fn get_metadata_sync_interval_hours(&self) -> Result<i64> {
+        let value = self
+            .get_setting("metadata_sync_interval_hours")?
+            .unwrap_or_else(|| "24".to_string());
+        value
+            .parse::<i64>()
+            .map_err(|e| anyhow::anyhow!("Invalid metadata sync interval: {}", e))
+    }
+
+    /// Hours between scheduled `git gc` runs of each admin clone; 0 turns them off
+    pub fn get_clone_gc_interval_hours(&self) -> Result<i64> {
+        match self.get_setting("clone_gc_interval_hours")? {
+            Some(value) => value
+                .parse::<i64>()
+                .map_err(|e| anyhow::anyhow!("Invalid clone gc interval: {}", e)),
+            None => Ok(crate::maintenance::DEFAULT_GC_INTERVAL_HOURS),
+        }
+    }
+
+    /// Gigabytes admin clones and worktrees may use before clones are evicted; 0 for no budget
+    pub fn get_disk_budget_gb(&self) -> Result<i64> {
+        let value = self
+            .get_setting("disk_budget_gb")?
+            .unwrap_or_else(|| "0".to_string());
+        value
+            .parse::<i64>()
+            .map_err(|e| anyhow::anyhow!("Invalid disk budget: {}", e))
+    }
+
+    pub fn get_ci_watch_max_hours(&self) -> Result<i64> {
+        let value = self
+            .get_setting("ci_watch_max_hours")?
+            .unwrap_or_else(|| "24".to_string());
+        value
+            .parse::<i64>()
+            .map_err(|e| anyhow::anyhow!("Invalid CI watch duration: {}", e))
+    }
+
     // Analysis operations
     pub fn create_analysis(
         &self,
@@ -984,6 +1882,449 @@ impl Store {
             .execute("DELETE FROM analyses WHERE id = ?1", params![id])?;
         Ok(rows_affected > 0)
     }
+
+    // CI check operations
+    /// Upsert the checks reported for an execution's commit, recording state transitions
+    /// A context reported twice in one batch (e.g. re-run jobs) is recorded once
+    pub fn record_ci_checks(
+        &mut self,
+        execution_id: &str,
+        commit_sha: &str,
+        checks: &[CiCheck],
+    ) -> Result<()> {
+        let now = now_ms();
+        let tx = self.conn.transaction()?;
+
+        for check in dedupe_checks(checks) {
+            let terminal = matches!(
+                check.state,
+                CiStatus::Passed | CiStatus::Failed | CiStatus::Skipped
+            );
+            let completed_at = if terminal { Some(now) } else { None };
+            let raw_json = check.raw_json.as_ref().map(|v| v.to_string());
+
+            let existing: Option<(String, CiStatus)> = tx
+                .query_row(
+                    "SELECT id, state FROM ci_checks WHERE execution_id = ?1 AND commit_sha = ?2 AND context = ?3",
+                    params![execution_id, commit_sha, check.context],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+
+            match existing {
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    tx.execute(
+                        "INSERT INTO ci_checks (id, execution_id, commit_sha, provider, context, name, state, target_url, description, external_id, raw_json, first_seen_at, state_changed_at, completed_at, updated_at)
+						 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?12, ?13, ?12)",
+                        params![
+                            id,
+                            execution_id,
+                            commit_sha,
+                            check.provider,
+                            check.context,
+                            check.name,
+                            check.state,
+                            check.target_url,
+                            check.description,
+                            check.external_id,
+                            raw_json,
+                            now,
+                            completed_at,
+                        ],
+                    )?;
+                    tx.execute(
+                        "INSERT INTO ci_check_transitions (check_id, from_state, to_state, changed_at) VALUES (?1, NULL, ?2, ?3)",
+                        params![id, check.state, now],
+                    )?;
+                }
+                Some((id, previous_state)) => {
+                    let changed = previous_state != check.state;
+                    tx.execute(
+                        "UPDATE ci_checks SET
+							name = ?1,
+							state = ?2,
+							target_url = ?3,
+							description = ?4,
+							external_id = ?5,
+							raw_json = ?6,
+							state_changed_at = CASE WHEN ?7 THEN ?8 ELSE state_changed_at END,
+							completed_at = CASE WHEN ?7 THEN ?9 ELSE completed_at END,
+							updated_at = ?8
+						WHERE id = ?10",
+                        params![
+                            check.name,
+                            check.state,
+                            check.target_url,
+                            check.description,
+                            check.external_id,
+                            raw_json,
+                            changed,
+                            now,
+                            completed_at,
+                            id,
+                        ],
+                    )?;
+                    if changed {
+                        tx.execute(
+                            "INSERT INTO ci_check_transitions (check_id, from_state, to_state, changed_at) VALUES (?1, ?2, ?3, ?4)",
+                            params![id, previous_state, check.state, now],
+                        )?;
+                    }
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_ci_checks(&self, execution_id: &str) -> Result<Vec<CiCheckRecord>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{SELECT_CI_CHECK} WHERE execution_id = ?1 ORDER BY first_seen_at DESC, context ASC"
+        ))?;
+        let checks = stmt
+            .query_map([execution_id], map_ci_check)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(checks)
+    }
+
+    pub fn get_ci_check_transitions(&self, execution_id: &str) -> Result<Vec<CiCheckTransition>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.check_id, t.from_state, t.to_state, t.changed_at
+			FROM ci_check_transitions t
+			JOIN ci_checks c ON c.id = t.check_id
+			WHERE c.execution_id = ?1
+			ORDER BY t.changed_at ASC, t.id ASC",
+        )?;
+        let transitions = stmt
+            .query_map([execution_id], |row| {
+                Ok(CiCheckTransition {
+                    check_id: row.get(0)?,
+                    from_state: row.get(1)?,
+                    to_state: row.get(2)?,
+                    changed_at: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(transitions)
+    }
+
+    /// Aggregate check outcomes for the current commit of every execution in a revision,
+    /// most frequently failing checks first
+    /// Optionally limited to executions against the given repositories
+    pub fn get_ci_check_failure_stats(
+        &self,
+        revision_id: &str,
+        repository_ids: Option<&[String]>,
+    ) -> Result<Vec<CiCheckFailureStat>> {
+        let repository_filter = match repository_ids {
+            Some(ids) => format!(
+                "AND e.repository_id IN ({})",
+                (0..ids.len())
+                    .map(|i| format!("?{}", i + 2))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => String::new(),
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT
+				c.context,
+				MAX(c.name) AS name,
+				COUNT(*) AS total,
+				SUM(CASE WHEN c.state = 'failed' THEN 1 ELSE 0 END) AS failed,
+				SUM(CASE WHEN c.state = 'passed' THEN 1 ELSE 0 END) AS passed
+			FROM ci_checks c
+			JOIN executions e ON e.id = c.execution_id AND e.commit_sha = c.commit_sha
+			WHERE e.revision_id = ?1 {repository_filter}
+			GROUP BY c.context
+			ORDER BY failed DESC, total DESC, c.context ASC"
+        ))?;
+        let params = std::iter::once(revision_id).chain(
+            repository_ids
+                .unwrap_or_default()
+                .iter()
+                .map(String::as_str),
+        );
+        let stats = stmt
+            .query_map(rusqlite::params_from_iter(params), |row| {
+                Ok(CiCheckFailureStat {
+                    context: row.get("context")?,
+                    name: row.get("name")?,
+                    total: row.get("total")?,
+                    failed: row.get("failed")?,
+                    passed: row.get("passed")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(stats)
+    }
+
+    // CI check policy operations
+    /// Get the policy stored for exactly this scope (repository_id None = promptset default)
+    pub fn get_ci_check_policy(
+        &self,
+        promptset_id: &str,
+        repository_id: Option<&str>,
+    ) -> Result<Option<CiCheckPolicy>> {
+        let row: Option<(String, String, bool)> = self
+            .conn
+            .query_row(
+                "SELECT required_checks, ignored_checks, discover_required FROM ci_check_policies
+				WHERE promptset_id = ?1 AND repository_id IS ?2",
+                params![promptset_id, repository_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get::<_, i32>(2)? != 0)),
+            )
+            .optional()?;
+
+        row.map(|(required, ignored, discover_required)| {
+            Ok(CiCheckPolicy {
+                required: serde_json::from_str(&required)?,
+                ignored: serde_json::from_str(&ignored)?,
+                discover_required,
+            })
+        })
+        .transpose()
+    }
+
+    /// Resolve the effective policy for a repository within a promptset:
+    /// the repository override if present, otherwise the promptset default
+    pub fn resolve_ci_check_policy(
+        &self,
+        promptset_id: &str,
+        repository_id: &str,
+    ) -> Result<CiCheckPolicy> {
+        if let Some(policy) = self.get_ci_check_policy(promptset_id, Some(repository_id))? {
+            return Ok(policy);
+        }
+        Ok(self
+            .get_ci_check_policy(promptset_id, None)?
+            .unwrap_or_default())
+    }
+
+    pub fn set_ci_check_policy(
+        &mut self,
+        promptset_id: &str,
+        repository_id: Option<&str>,
+        policy: &CiCheckPolicy,
+    ) -> Result<()> {
+        let required = serde_json::to_string(&policy.required)?;
+        let ignored = serde_json::to_string(&policy.ignored)?;
+        let tx = self.conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM ci_check_policies WHERE promptset_id = ?1 AND repository_id IS ?2",
+            params![promptset_id, repository_id],
+        )?;
+        tx.execute(
+            "INSERT INTO ci_check_policies (promptset_id, repository_id, required_checks, ignored_checks, discover_required, updated_at)
+			 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                promptset_id,
+                repository_id,
+                required,
+                ignored,
+                policy.discover_required as i32,
+                now_ms()
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn delete_ci_check_policy(
+        &self,
+        promptset_id: &str,
+        repository_id: Option<&str>,
+    ) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "DELETE FROM ci_check_policies WHERE promptset_id = ?1 AND repository_id IS ?2",
+            params![promptset_id, repository_id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    // Repository CI config operations
+    pub fn get_repository_ci_config(
+        &self,
+        repository_id: &str,
+    ) -> Result<Option<RepositoryCiConfig>> {
+        let row: Option<(String, String, i64)> = self
+            .conn
+            .query_row(
+                "SELECT ci_provider, config, updated_at FROM repository_ci_configs WHERE repository_id = ?1",
+                [repository_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+
+        row.map(|(ci_provider, config, updated_at)| {
+            Ok(RepositoryCiConfig {
+                repository_id: repository_id.to_string(),
+                ci_provider,
+                config: serde_json::from_str(&config)?,
+                updated_at,
+            })
+        })
+        .transpose()
+    }
+
+    pub fn set_repository_ci_config(
+        &self,
+        repository_id: &str,
+        ci_provider: &str,
+        config: &serde_json::Value,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO repository_ci_configs (repository_id, ci_provider, config, updated_at)
+			 VALUES (?1, ?2, ?3, ?4)
+			 ON CONFLICT(repository_id) DO UPDATE SET
+				ci_provider = excluded.ci_provider,
+				config = excluded.config,
+				updated_at = excluded.updated_at",
+            params![
+                repository_id,
+                ci_provider,
+                serde_json::to_string(config)?,
+                now_ms()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_repository_ci_config(&self, repository_id: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "DELETE FROM repository_ci_configs WHERE repository_id = ?1",
+            [repository_id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    // CI watch operations
+    /// Start watching an execution's CI, or pull an existing watch forward to next_poll_at
+    /// A watch for a different commit is replaced and its attempts reset; an expired watch
+    /// for the same commit stays expired (see revive_ci_watch)
+    pub fn watch_ci(&self, execution_id: &str, commit_sha: &str, next_poll_at: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO ci_watches (execution_id, commit_sha, attempts, next_poll_at, created_at)
+			 VALUES (?1, ?2, 0, ?3, ?4)
+			 ON CONFLICT(execution_id) DO UPDATE SET
+				attempts = CASE WHEN commit_sha = excluded.commit_sha THEN attempts ELSE 0 END,
+				next_poll_at = CASE WHEN commit_sha = excluded.commit_sha
+					THEN MIN(next_poll_at, excluded.next_poll_at) ELSE excluded.next_poll_at END,
+				last_polled_at = CASE WHEN commit_sha = excluded.commit_sha THEN last_polled_at ELSE NULL END,
+				last_error = CASE WHEN commit_sha = excluded.commit_sha THEN last_error ELSE NULL END,
+				created_at = CASE WHEN commit_sha = excluded.commit_sha THEN created_at ELSE excluded.created_at END,
+				expired_at = CASE WHEN commit_sha = excluded.commit_sha THEN expired_at ELSE NULL END,
+				commit_sha = excluded.commit_sha",
+            params![execution_id, commit_sha, next_poll_at, now_ms()],
+        )?;
+        Ok(())
+    }
+
+    /// Start a fresh watch for an execution's CI, even if its previous watch expired
+    pub fn revive_ci_watch(
+        &self,
+        execution_id: &str,
+        commit_sha: &str,
+        next_poll_at: i64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO ci_watches (execution_id, commit_sha, attempts, next_poll_at, created_at)
+			 VALUES (?1, ?2, 0, ?3, ?4)
+			 ON CONFLICT(execution_id) DO UPDATE SET
+				commit_sha = excluded.commit_sha,
+				attempts = 0,
+				next_poll_at = excluded.next_poll_at,
+				last_polled_at = NULL,
+				last_error = NULL,
+				created_at = excluded.created_at,
+				expired_at = NULL",
+            params![execution_id, commit_sha, next_poll_at, now_ms()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_ci_watch(&self, execution_id: &str) -> Result<Option<CiWatch>> {
+        self.conn
+            .query_row(
+                &format!("{SELECT_CI_WATCH} WHERE execution_id = ?1"),
+                [execution_id],
+                map_ci_watch,
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    pub fn get_ci_watches(&self) -> Result<Vec<CiWatch>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("{SELECT_CI_WATCH} ORDER BY next_poll_at ASC"))?;
+        let watches = stmt
+            .query_map([], map_ci_watch)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(watches)
+    }
+
+    /// Unexpired watches whose next poll is due at or before `now`
+    pub fn get_due_ci_watches(&self, now: i64) -> Result<Vec<CiWatch>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{SELECT_CI_WATCH} WHERE expired_at IS NULL AND next_poll_at <= ?1 ORDER BY next_poll_at ASC"
+        ))?;
+        let watches = stmt
+            .query_map([now], map_ci_watch)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(watches)
+    }
+
+    /// Record a poll attempt and schedule the next one
+    pub fn reschedule_ci_watch(
+        &self,
+        execution_id: &str,
+        next_poll_at: i64,
+        last_error: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE ci_watches SET attempts = attempts + 1, next_poll_at = ?2, last_polled_at = ?3, last_error = ?4
+			 WHERE execution_id = ?1",
+            params![execution_id, next_poll_at, now_ms(), last_error],
+        )?;
+        Ok(())
+    }
+
+    /// Push a watch's next poll back without counting it as an attempt (e.g. rate limited)
+    pub fn defer_ci_watch(
+        &self,
+        execution_id: &str,
+        next_poll_at: i64,
+        last_error: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE ci_watches SET next_poll_at = ?2, last_polled_at = ?3, last_error = ?4
+			 WHERE execution_id = ?1",
+            params![execution_id, next_poll_at, now_ms(), last_error],
+        )?;
+        Ok(())
+    }
+
+    /// Stop polling a watch but keep its row, so it isn't queued again on startup
+    pub fn expire_ci_watch(&self, execution_id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE ci_watches SET expired_at = ?2 WHERE execution_id = ?1",
+            params![execution_id, now_ms()],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_ci_watch(&self, execution_id: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "DELETE FROM ci_watches WHERE execution_id = ?1",
+            [execution_id],
+        )?;
+        Ok(rows_affected > 0)
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -1034,5 +2375,328 @@ pub struct ExecutionUpdates {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub completed_at: Option<i64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_manual_tags_survive_imports() {
+        let mut store = Store::new(":memory:").unwrap();
+        let repository = store.create_repository("github", "acme/api").unwrap();
+
+        store
+            .replace_repository_tags(&repository.id, "topic", &strings(&["lang:rust", "infra"]))
+            .unwrap();
+        store
+            .add_repository_tags(&repository.id, &strings(&["infra", "tier:1"]), "manual")
+            .unwrap();
+        store
+            .replace_repository_tags(&repository.id, "topic", &strings(&["lang:go"]))
+            .unwrap();
+
+        let tags: Vec<(String, String)> = store
+            .get_repository_tags(&repository.id)
+            .unwrap()
+            .into_iter()
+            .map(|tag| (tag.tag, tag.source))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                ("infra".to_string(), "manual".to_string()),
+                ("lang:go".to_string(), "topic".to_string()),
+                ("tier:1".to_string(), "manual".to_string()),
+            ]
+        );
+    }
+
+    /// Sync a repository's default branch as of `at`
+    fn sync_branch(store: &Store, repository_id: &str, branch: &str, at: i64) -> bool {
+        let metadata = RepoMetadata {
+            default_branch: branch.to_string(),
+            ..Default::default()
+        };
+        let changed = store
+            .update_repository_metadata(repository_id, &metadata)
+            .unwrap();
+        if changed {
+            store
+                .conn
+                .execute(
+                    "UPDATE default_branch_changes SET changed_at = ?1 WHERE id = (SELECT MAX(id) FROM default_branch_changes)",
+                    [at],
+                )
+                .unwrap();
+        }
+        store
+            .conn
+            .execute(
+                "UPDATE repositories SET last_synced_at = ?1 WHERE id = ?2",
+                params![at, repository_id],
+            )
+            .unwrap();
+        changed
+    }
+
+    /// Run a promptset on a repository at `at`
+    fn run(store: &Store, promptset_id: &str, revision_id: &str, repository_id: &str, at: i64) {
+        let execution = store
+            .create_execution(promptset_id, revision_id, repository_id)
+            .unwrap();
+        store
+            .conn
+            .execute(
+                "UPDATE executions SET created_at = ?1 WHERE id = ?2",
+                params![at, execution.id],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_default_branch_changes() {
+        let mut store = Store::new(":memory:").unwrap();
+        let api = store.create_repository("github", "acme/api").unwrap();
+        let web = store.create_repository("github", "acme/web").unwrap();
+        let promptset = store
+            .create_promptset("Upgrade", vec![api.id.clone(), web.id.clone()], None, false)
+            .unwrap();
+        let revision = store
+            .create_prompt_revision(&promptset.id, "Upgrade the build", None)
+            .unwrap();
+
+        // The first sync only records the branch
+        assert!(!sync_branch(&store, &api.id, "master", 100));
+        assert!(!sync_branch(&store, &web.id, "master", 100));
+        assert!(!sync_branch(&store, &api.id, "master", 110));
+
+        // web switched before the last run, api after it
+        assert!(sync_branch(&store, &web.id, "main", 150));
+        run(&store, &promptset.id, &revision.id, &api.id, 200);
+        run(&store, &promptset.id, &revision.id, &web.id, 200);
+        assert!(sync_branch(&store, &api.id, "main", 300));
+
+        let changes = store.get_default_branch_changes(None).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].repository_id, api.id);
+        assert_eq!(changes[0].old_branch, "master");
+        assert_eq!(changes[0].new_branch, "main");
+        assert_eq!(changes[0].changed_at, 300);
+        assert_eq!(changes[0].last_run_at, 200);
+        assert_eq!(
+            store
+                .get_default_branch_changes(Some(&promptset.id))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(store
+            .get_default_branch_changes(Some("other"))
+            .unwrap()
+            .is_empty());
+
+        // Switching back to the branch of the last run is no change
+        assert!(sync_branch(&store, &api.id, "master", 400));
+        assert!(store.get_default_branch_changes(None).unwrap().is_empty());
+
+        // Across several switches, the branch of the last run is reported as the old one
+        assert!(sync_branch(&store, &api.id, "develop", 500));
+        assert!(sync_branch(&store, &api.id, "trunk", 600));
+        let changes = store.get_default_branch_changes(None).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].old_branch, "master");
+        assert_eq!(changes[0].new_branch, "trunk");
+        assert_eq!(changes[0].changed_at, 600);
+    }
+
+    #[test]
+    fn test_upsert_keeps_recorded_default_branch() {
+        let store = Store::new(":memory:").unwrap();
+        let repository = store
+            .upsert_repository("github", "acme/api", Some("master"))
+            .unwrap();
+        assert_eq!(repository.default_branch.as_deref(), Some("master"));
+
+        // A re-import doesn't switch the branch behind metadata sync's back
+        let repository = store
+            .upsert_repository("github", "acme/api", Some("main"))
+            .unwrap();
+        assert_eq!(repository.default_branch.as_deref(), Some("master"));
+        assert!(sync_branch(&store, &repository.id, "main", 100));
+    }
+
+    #[test]
+    fn test_repositories_due_for_sync() {
+        let store = Store::new(":memory:").unwrap();
+        let stale = store.create_repository("github", "acme/stale").unwrap();
+        let fresh = store.create_repository("github", "acme/fresh").unwrap();
+        let older = store.create_repository("github", "acme/older").unwrap();
+        let never = store.create_repository("github", "acme/never").unwrap();
+        sync_branch(&store, &stale.id, "main", 200);
+        sync_branch(&store, &fresh.id, "main", 1_000);
+        sync_branch(&store, &older.id, "main", 100);
+
+        // Never synced first, then the longest ago
+        let due: Vec<String> = store
+            .get_repositories_due_for_sync(500)
+            .unwrap()
+            .into_iter()
+            .map(|repository| repository.id)
+            .collect();
+        assert_eq!(due, vec![never.id.clone(), older.id, stale.id]);
+
+        let metadata = RepoMetadata {
+            default_branch: "main".to_string(),
+            archived: true,
+            topics: vec!["infra".to_string()],
+            can_push: Some(false),
+            ..Default::default()
+        };
+        store
+            .update_repository_metadata(&never.id, &metadata)
+            .unwrap();
+        let never = store.get_repository(&never.id).unwrap().unwrap();
+        assert!(never.archived);
+        assert_eq!(never.default_branch.as_deref(), Some("main"));
+        assert_eq!(never.topics, vec!["infra".to_string()]);
+        assert_eq!(never.can_push, Some(false));
+        assert!(never.last_synced_at.is_some());
+    }
+
+    #[test]
+    fn test_expired_ci_watch_stays_expired() {
+        let mut store = Store::new(":memory:").unwrap();
+        let repository = store.create_repository("github", "acme/api").unwrap();
+        let promptset = store
+            .create_promptset("Upgrade", vec![repository.id.clone()], None, false)
+            .unwrap();
+        let revision = store
+            .create_prompt_revision(&promptset.id, "Upgrade the build", None)
+            .unwrap();
+        let execution = store
+            .create_execution(&promptset.id, &revision.id, &repository.id)
+            .unwrap();
+
+        store.watch_ci(&execution.id, "abc123", 100).unwrap();
+        store.expire_ci_watch(&execution.id).unwrap();
+        assert!(store.get_due_ci_watches(i64::MAX).unwrap().is_empty());
+
+        // Watching the same commit again (e.g. on startup) doesn't revive it
+        store.watch_ci(&execution.id, "abc123", 100).unwrap();
+        assert!(store.get_due_ci_watches(i64::MAX).unwrap().is_empty());
+        assert!(store
+            .get_ci_watch(&execution.id)
+            .unwrap()
+            .unwrap()
+            .expired_at
+            .is_some());
+
+        // A new commit or an explicit revive starts over
+        store.revive_ci_watch(&execution.id, "abc123", 200).unwrap();
+        let due = store.get_due_ci_watches(i64::MAX).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].attempts, 0);
+        assert_eq!(due[0].next_poll_at, 200);
+
+        store.expire_ci_watch(&execution.id).unwrap();
+        store.watch_ci(&execution.id, "def456", 300).unwrap();
+        assert_eq!(store.get_due_ci_watches(i64::MAX).unwrap().len(), 1);
+    }
+
+    fn check(context: &str, state: CiStatus) -> CiCheck {
+        CiCheck {
+            provider: "github".to_string(),
+            context: context.to_string(),
+            name: context.to_string(),
+            state,
+            target_url: None,
+            description: None,
+            external_id: None,
+            raw_json: None,
+        }
+    }
+
+    #[test]
+    fn test_record_ci_checks() {
+        let mut store = Store::new(":memory:").unwrap();
+        let repository = store.create_repository("github", "acme/api").unwrap();
+        let promptset = store
+            .create_promptset("Upgrade", vec![repository.id.clone()], None, false)
+            .unwrap();
+        let revision = store
+            .create_prompt_revision(&promptset.id, "Upgrade the build", None)
+            .unwrap();
+        let execution = store
+            .create_execution(&promptset.id, &revision.id, &repository.id)
+            .unwrap();
+
+        let transitions = |store: &Store, context: &str| -> Vec<(Option<CiStatus>, CiStatus)> {
+            let check_id = store
+                .get_ci_checks(&execution.id)
+                .unwrap()
+                .into_iter()
+                .find(|c| c.context == context)
+                .unwrap()
+                .id;
+            store
+                .get_ci_check_transitions(&execution.id)
+                .unwrap()
+                .into_iter()
+                .filter(|t| t.check_id == check_id)
+                .map(|t| (t.from_state, t.to_state))
+                .collect()
+        };
+
+        // A duplicate context is recorded once, with the worst state
+        store
+            .record_ci_checks(
+                &execution.id,
+                "abc123",
+                &[
+                    check("check:lint", CiStatus::Pending),
+                    check("check:build", CiStatus::Failed),
+                    check("check:build", CiStatus::Passed),
+                ],
+            )
+            .unwrap();
+        let checks = store.get_ci_checks(&execution.id).unwrap();
+        assert_eq!(checks.len(), 2);
+        let build = checks.iter().find(|c| c.context == "check:build").unwrap();
+        assert_eq!(build.state, CiStatus::Failed);
+        assert_eq!(
+            transitions(&store, "check:build"),
+            vec![(None, CiStatus::Failed)]
+        );
+
+        // An unchanged state adds no transition, a changed one does
+        store
+            .record_ci_checks(
+                &execution.id,
+                "abc123",
+                &[
+                    check("check:lint", CiStatus::Passed),
+                    check("check:build", CiStatus::Failed),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            transitions(&store, "check:build"),
+            vec![(None, CiStatus::Failed)]
+        );
+        assert_eq!(
+            transitions(&store, "check:lint"),
+            vec![
+                (None, CiStatus::Pending),
+                (Some(CiStatus::Pending), CiStatus::Passed)
+            ]
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use anyhow::Result;
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+
+use super::service::{GitAuth, GitService};
+
+/// Files larger than this are never searched for the content pattern
+const MAX_SEARCHED_FILE_SIZE: usize = 5 * 1024 * 1024;
+
+/// A promptset's applicability check: some file at the default branch must match the path
+/// glob and contain the pattern for the promptset to run against a repository
+/// Either half may be left out; evaluated against the admin clone, so it works offline unless a
+/// partial clone is missing the files to search
+pub struct ApplicabilityCheck {
+    pattern: Option<Regex>,
+    path_glob: Option<Pattern>,
+    /// Globs without a '/' match file names at any depth, like .gitignore entries
+    match_file_name: bool,
+}
+
+impl ApplicabilityCheck {
+    /// Compile a check; None when neither a pattern nor a path glob is set
+    pub fn new(pattern: Option<&str>, path_glob: Option<&str>) -> Result<Option<Self>> {
+        let pattern = pattern.map(str::trim).filter(|p| !p.is_empty());
+        let path_glob = path_glob.map(str::trim).filter(|g| !g.is_empty());
+        if pattern.is_none() && path_glob.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            pattern: pattern
+                .map(Regex::new)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid applicability pattern: {}", e))?,
+            match_file_name: path_glob.is_some_and(|glob| !glob.contains('/')),
+            path_glob: path_glob
+                .map(|glob| Pattern::new(glob.trim_start_matches('/')))
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid applicability path glob: {}", e))?,
+        }))
+    }
+
+    fn matches_path(&self, path: &str, file_name: &str) -> bool {
+        let Some(glob) = &self.path_glob else {
+            return true;
+        };
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        if self.match_file_name {
+            glob.matches_with(file_name, options)
+        } else {
+            glob.matches_with(path, options)
+        }
+    }
+
+    /// Why the promptset doesn't apply at `rev` (e.g. "origin/main"), or None if a file matches
+    /// Partial clones are listed with the git CLI and the blobs to search fetched first, using
+    /// `auth` for HTTPS remotes
+    pub fn skip_reason(
+        &self,
+        repo: &Repository,
+        rev: &str,
+        auth: Option<&GitAuth<'_>>,
+    ) -> Result<Option<String>> {
+        let partial = GitService::needs_cli(repo);
+        let candidates: Vec<Oid> = if partial {
+            GitService::tree_blobs_cli(repo, rev, &[], auth)?
+                .into_iter()
+                .filter(|(path, _)| {
+                    let file_name = path.rsplit('/').next().unwrap_or(path);
+                    self.matches_path(path, file_name)
+                })
+                .map(|(_, id)| id)
+                .collect()
+        } else {
+            let tree = repo.revparse_single(rev)?.peel_to_tree()?;
+            let mut candidates = Vec::new();
+            tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+                if entry.kind() != Some(ObjectType::Blob) {
+                    return TreeWalkResult::Ok;
+                }
+                let Some(file_name) = entry.name() else {
+                    return TreeWalkResult::Ok;
+                };
+                if self.matches_path(&format!("{}{}", dir, file_name), file_name) {
+                    candidates.push(entry.id());
+                    // Without a pattern the first matching path settles it
+                    if self.pattern.is_none() {
+                        return TreeWalkResult::Abort;
+                    }
+                }
+                TreeWalkResult::Ok
+            })?;
+            candidates
+        };
+
+        let matched = match &self.pattern {
+            None => !candidates.is_empty(),
+            Some(pattern) => {
+                if partial {
+                    GitService::fetch_missing_objects_cli(repo, &candidates, auth)?;
+                }
+                let mut matched = false;
+                for id in candidates {
+                    let blob = repo.find_blob(id)?;
+                    if blob.is_binary() || blob.size() > MAX_SEARCHED_FILE_SIZE {
+                        continue;
+                    }
+                    if pattern.is_match(&String::from_utf8_lossy(blob.content())) {
+                        matched = true;
+                        break;
+                    }
+                }
+                matched
+            }
+        };
+        if matched {
+            return Ok(None);
+        }
+
+        Ok(Some(match (&self.pattern, &self.path_glob) {
+            (Some(pattern), Some(glob)) => format!(
+                "No file matching '{}' contains '{}' at {}",
+                glob.as_str(),
+                pattern.as_str(),
+                rev
+            ),
+            (Some(pattern), None) => format!("No file contains '{}' at {}", pattern.as_str(), rev),
+            (None, Some(glob)) => format!("No file matches '{}' at {}", glob.as_str(), rev),
+            (None, None) => unreachable!("checks without a pattern or glob are never built"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_git::git;
+    use std::path::Path;
+
+    fn commit_files(repo: &Repository, files: &[(&str, &str)]) {
+        let mut index = repo.index().unwrap();
+        for (path, content) in files {
+            let blob = repo.blob(content.as_bytes()).unwrap();
+            index
+                .add(&git2::IndexEntry {
+                    ctime: git2::IndexTime::new(0, 0),
+                    mtime: git2::IndexTime::new(0, 0),
+                    dev: 0,
+                    ino: 0,
+                    mode: 0o100644,
+                    uid: 0,
+                    gid: 0,
+                    file_size: content.len() as u32,
+                    id: blob,
+                    flags: 0,
+                    flags_extended: 0,
+                    path: path.as_bytes().to_vec(),
+                })
+                .unwrap();
+        }
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Maestro", "maestro@example.com").unwrap();
+        repo.commit(
+            Some("refs/remotes/origin/main"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_skip_reason() {
+        let dir =
+            std::env::temp_dir().join(format!("maestro-applicability-{}", uuid::Uuid::new_v4()));
+        let repo = Repository::init_bare(&dir).unwrap();
+        commit_files(
+            &repo,
+            &[
+                ("pom.xml", "<artifactId>app</artifactId>"),
+                ("lib/pom.xml", "<artifactId>log4j-core</artifactId>"),
+                ("README.md", "Uses log4j"),
+            ],
+        );
+
+        let check = |pattern: Option<&str>, glob: Option<&str>| {
+            ApplicabilityCheck::new(pattern, glob)
+                .unwrap()
+                .unwrap()
+                .skip_reason(&repo, "origin/main", None)
+                .unwrap()
+        };
+
+        // File-name globs match at any depth
+        assert_eq!(check(Some("log4j"), Some("pom.xml")), None);
+        assert_eq!(check(None, Some("lib/*.xml")), None);
+        assert_eq!(check(Some(r"log4j-\w+"), None), None);
+        assert_eq!(
+            check(Some("log4j"), Some("/pom.xml")),
+            Some("No file matching 'pom.xml' contains 'log4j' at origin/main".to_string())
+        );
+        assert_eq!(
+            check(None, Some("build.gradle*")),
+            Some("No file matches 'build.gradle*' at origin/main".to_string())
+        );
+        assert!(ApplicabilityCheck::new(Some(" "), None).unwrap().is_none());
+        assert!(ApplicabilityCheck::new(Some("log4j("), None).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_skip_reason_blobless_clone() {
+        let root =
+            std::env::temp_dir().join(format!("maestro-applicability-{}", uuid::Uuid::new_v4()));
+        let source = root.join("source");
+        std::fs::create_dir_all(source.join("lib")).unwrap();
+        git(&source, &["init", "-q", "-b", "main"]);
+        git(&source, &["config", "uploadpack.allowFilter", "true"]);
+        std::fs::write(source.join("README.md"), "Uses log4j\n").unwrap();
+        std::fs::write(
+            source.join("lib/pom.xml"),
+            "<artifactId>log4j-core</artifactId>\n",
+        )
+        .unwrap();
+        git(&source, &["add", "."]);
+        git(&source, &["commit", "-q", "-m", "Initial commit"]);
+
+        let url = format!("file://{}", source.display());
+        let clone = root.join("clone");
+        git(
+            &root,
+            &[
+                "clone",
+                "-q",
+                "--filter=blob:none",
+                "--no-checkout",
+                &url,
+                clone.to_str().unwrap(),
+            ],
+        );
+        let repo = Repository::open(&clone).unwrap();
+        assert!(GitService::needs_cli(&repo));
+
+        let check = |pattern: Option<&str>, glob: Option<&str>| {
+            ApplicabilityCheck::new(pattern, glob)
+                .unwrap()
+                .unwrap()
+                .skip_reason(&repo, "origin/main", None)
+                .unwrap()
+        };
+
+        // Paths alone need no blobs
+        assert_eq!(check(None, Some("pom.xml")), None);
+        assert_eq!(
+            check(None, Some("build.gradle")),
+            Some("No file matches 'build.gradle' at origin/main".to_string())
+        );
+        let pom = repo
+            .revparse_single("origin/main")
+            .and_then(|commit| commit.peel_to_tree())
+            .and_then(|tree| tree.get_path(Path::new("lib/pom.xml")))
+            .unwrap()
+            .id();
+        assert!(!repo.odb().unwrap().exists(pom));
+
+        // Searched blobs are fetched from the promisor remote
+        assert_eq!(check(Some(r"log4j-\w+"), Some("pom.xml")), None);
+        assert!(repo.odb().unwrap().exists(pom));
+        assert_eq!(
+            check(Some("log4j-api"), None),
+            Some("No file contains 'log4j-api' at origin/main".to_string())
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,259 @@
+use super::git_provider::{
+    GitProvider, GitProviderContext, NewPullRequest, PullRequest, RepoMetadata,
+};
+use crate::util::rate_limit;
+use anyhow::Result;
+use serde::Deserialize;
+
+/// Bitbucket Server/Data Center-specific configuration
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BitbucketGitConfig {
+    pub project_key: String,
+    pub repo_slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitbucketBranch {
+    display_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketRepository {
+    /// Only reported by Bitbucket 8.0 and later
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    public: bool,
+    /// "AVAILABLE" once the repository is ready to use
+    state: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketPullRequest {
+    id: u64,
+    links: BitbucketLinks,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketLinks {
+    #[serde(rename = "self", default)]
+    self_links: Vec<BitbucketLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketLink {
+    href: String,
+}
+
+/// Bitbucket Server/Data Center REST API (`/rest/api/1.0`), authenticated with an HTTP access token
+#[derive(Clone)]
+pub struct BitbucketGitProvider {
+    http_client: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl BitbucketGitProvider {
+    pub fn new(token: String, base_url: String) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http_client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    fn repo_url(&self, cfg: &BitbucketGitConfig, path: &str) -> String {
+        format!(
+            "{}/rest/api/1.0/projects/{}/repos/{}/{}",
+            self.base_url, cfg.project_key, cfg.repo_slug, path
+        )
+    }
+
+    /// Branch reference in a pull request body
+    fn pull_request_ref(cfg: &BitbucketGitConfig, branch: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": format!("refs/heads/{}", branch),
+            "repository": {
+                "slug": cfg.repo_slug,
+                "project": { "key": cfg.project_key },
+            },
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl GitProvider for BitbucketGitProvider {
+    fn id(&self) -> &'static str {
+        "bitbucket"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Bitbucket"
+    }
+
+    async fn get_repo_metadata(&self, ctx: &GitProviderContext) -> Result<RepoMetadata> {
+        let cfg: BitbucketGitConfig = ctx.cfg()?;
+
+        let url = self.repo_url(&cfg, "");
+        let url = url.trim_end_matches('/');
+        let response = rate_limit::send(self.http_client.get(url).bearer_auth(&self.token)).await?;
+        if !response.status().is_success() {
+            anyhow::bail!("Bitbucket returned {} for {}", response.status(), url);
+        }
+        let repository: BitbucketRepository = response.json().await?;
+
+        // Bitbucket reports neither languages, topics, sizes nor push times, and push rights
+        // would take a permission search per repository
+        let mut metadata = RepoMetadata {
+            default_branch: "main".to_string(),
+            archived: repository.archived,
+            disabled: repository.state.is_some_and(|state| state != "AVAILABLE"),
+            visibility: Some(
+                if repository.public {
+                    "public"
+                } else {
+                    "private"
+                }
+                .to_string(),
+            ),
+            ..Default::default()
+        };
+
+        let url = self.repo_url(&cfg, "default-branch");
+        let response =
+            rate_limit::send(self.http_client.get(&url).bearer_auth(&self.token)).await?;
+
+        // Empty repositories have no default branch yet
+        if response.status() == reqwest::StatusCode::NO_CONTENT {
+            return Ok(metadata);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("Bitbucket returned {} for {}", response.status(), url);
+        }
+        let branch: BitbucketBranch = response.json().await?;
+        metadata.default_branch = branch.display_id;
+
+        Ok(metadata)
+    }
+
+    async fn create_pull_request(
+        &self,
+        ctx: &GitProviderContext,
+        request: &NewPullRequest,
+    ) -> Result<Option<PullRequest>> {
+        let cfg: BitbucketGitConfig = ctx.cfg()?;
+        let url = self.repo_url(&cfg, "pull-requests");
+
+        let body = serde_json::json!({
+            "title": request.title,
+            "description": request.description,
+            "fromRef": Self::pull_request_ref(&cfg, &request.source_branch),
+            "toRef": Self::pull_request_ref(&cfg, &request.target_branch),
+        });
+
+        let response = rate_limit::send(
+            self.http_client
+                .post(&url)
+                .bearer_auth(&self.token)
+                .json(&body),
+        )
+        .await?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Bitbucket returned {} creating pull request: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            );
+        }
+        let pull_request: BitbucketPullRequest = response.json().await?;
+
+        let url = pull_request
+            .links
+            .self_links
+            .into_iter()
+            .next()
+            .map(|link| link.href)
+            .unwrap_or_else(|| {
+                format!(
+                    "{}/projects/{}/repos/{}/pull-requests/{}",
+                    self.base_url, cfg.project_key, cfg.repo_slug, pull_request.id
+                )
+            });
+
+        Ok(Some(PullRequest {
+            id: pull_request.id.to_string(),
+            url,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{serve, StubResponse};
+
+    #[tokio::test]
+    async fn test_default_branch_and_pull_request() {
+        let (base_url, requests) = serve(|request| {
+            let base = "/bitbucket/rest/api/1.0/projects/WID/repos/widgets";
+            match (request.method.as_str(), request.url.as_str()) {
+                _ if request.header("Authorization") != Some("Bearer bb-token") => {
+                    StubResponse::status(401, "unauthorized")
+                }
+                ("GET", url) if url == base => StubResponse::ok(
+                    r#"{"slug": "widgets", "archived": true, "public": false, "state": "AVAILABLE"}"#,
+                ),
+                ("GET", url) if url == format!("{}/default-branch", base) => {
+                    StubResponse::ok(r#"{"id": "refs/heads/develop", "displayId": "develop"}"#)
+                }
+                ("POST", url) if url == format!("{}/pull-requests", base) => StubResponse::status(
+                    201,
+                    r#"{"id": 42, "links": {"self": [{"href": "https://bb/projects/WID/repos/widgets/pull-requests/42"}]}}"#,
+                ),
+                _ => StubResponse::not_found(),
+            }
+        });
+
+        let provider =
+            BitbucketGitProvider::new("bb-token".to_string(), format!("{}/bitbucket/", base_url))
+                .unwrap();
+        let ctx = GitProviderContext {
+            provider_cfg: serde_json::json!({ "project_key": "WID", "repo_slug": "widgets" }),
+        };
+
+        let metadata = provider.get_repo_metadata(&ctx).await.unwrap();
+        assert_eq!(metadata.default_branch, "develop");
+        assert!(metadata.archived);
+        assert!(!metadata.disabled);
+        assert_eq!(metadata.visibility.as_deref(), Some("private"));
+        assert_eq!(metadata.can_push, None);
+
+        let pull_request = provider
+            .create_pull_request(
+                &ctx,
+                &NewPullRequest {
+                    source_branch: "maestro/abc".to_string(),
+                    target_branch: "develop".to_string(),
+                    title: "Update widgets".to_string(),
+                    description: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(pull_request.id, "42");
+        assert!(pull_request.url.ends_with("/pull-requests/42"));
+
+        let pull_request = requests
+            .try_iter()
+            .find(|request| request.method == "POST")
+            .unwrap();
+        assert!(pull_request
+            .body
+            .contains(r#""id":"refs/heads/maestro/abc""#));
+    }
+}
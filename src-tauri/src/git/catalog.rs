@@ -0,0 +1,291 @@
+use anyhow::Result;
+use git2::{ObjectType, Oid, Repository};
+
+use super::service::{GitAuth, GitService};
+use crate::util::tags::normalize_tag;
+
+/// Where CODEOWNERS files live, in the order GitHub and GitLab look for them
+const CODEOWNERS_PATHS: &[&str] = &[
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+/// Backstage catalog descriptors at the repository root
+const CATALOG_PATHS: &[&str] = &["catalog-info.yaml", "catalog-info.yml"];
+
+/// Tags derived from a repository's CODEOWNERS and Backstage catalog files at `rev`
+/// (e.g. "origin/main"), read from the admin clone; a partial clone fetches the files first,
+/// using `auth` for HTTPS remotes:
+/// - owners of CODEOWNERS' catch-all rule: `@org/payments` -> `team:payments`, `@alice` -> `owner:alice`
+/// - catalog-info.yaml: `spec.owner` -> `team:`/`owner:`, `spec.lifecycle` -> `lifecycle:`,
+///   `spec.system` -> `system:` and `metadata.tags` as is
+pub fn catalog_tags(
+    repo: &Repository,
+    rev: &str,
+    auth: Option<&GitAuth<'_>>,
+) -> Result<Vec<String>> {
+    let blobs: Vec<(String, Oid)> = if GitService::needs_cli(repo) {
+        let paths: Vec<&str> = CODEOWNERS_PATHS
+            .iter()
+            .chain(CATALOG_PATHS)
+            .copied()
+            .collect();
+        let blobs = GitService::tree_blobs_cli(repo, rev, &paths, auth)?;
+        let ids: Vec<Oid> = blobs.iter().map(|(_, id)| *id).collect();
+        GitService::fetch_missing_objects_cli(repo, &ids, auth)?;
+        blobs
+    } else {
+        let tree = repo.revparse_single(rev)?.peel_to_tree()?;
+        CODEOWNERS_PATHS
+            .iter()
+            .chain(CATALOG_PATHS)
+            .filter_map(|path| {
+                let entry = tree.get_path(std::path::Path::new(path)).ok()?;
+                (entry.kind() == Some(ObjectType::Blob)).then(|| (path.to_string(), entry.id()))
+            })
+            .collect()
+    };
+    let read = |path: &str| -> Result<Option<String>> {
+        let Some((_, id)) = blobs.iter().find(|(blob_path, _)| blob_path == path) else {
+            return Ok(None);
+        };
+        let blob = repo.find_blob(*id)?;
+        Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+    };
+
+    let mut tags = Vec::new();
+    for path in CODEOWNERS_PATHS {
+        if let Some(content) = read(path)? {
+            tags.extend(codeowners_tags(&content));
+            break;
+        }
+    }
+    for path in CATALOG_PATHS {
+        if let Some(content) = read(path)? {
+            tags.extend(catalog_info_tags(&content));
+            break;
+        }
+    }
+
+    let mut tags: Vec<String> = tags
+        .iter()
+        .filter_map(|tag| normalize_tag(tag).ok())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    Ok(tags)
+}
+
+fn owner_tag(owner: &str) -> String {
+    let owner = owner.trim_start_matches('@');
+    match owner.split_once('/') {
+        Some((_, team)) => format!("team:{}", team),
+        None => format!("owner:{}", owner),
+    }
+}
+
+/// Owners of the last catch-all rule (`*`, `/` or `/**`), which own the repository as a whole
+fn codeowners_tags(content: &str) -> Vec<String> {
+    let mut owners: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        // GitLab section headers
+        if line.starts_with('[') || line.starts_with("^[") {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        if matches!(fields.next(), Some("*" | "/" | "/*" | "/**" | "**")) {
+            owners = fields.collect();
+        }
+    }
+    owners.into_iter().map(owner_tag).collect()
+}
+
+fn yaml_scalar(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+/// Reads the few fields it needs from the block-style YAML Backstage descriptors use,
+/// stopping after the first document that yields any
+fn catalog_info_tags(content: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut section = "";
+    let mut field_indent = None;
+    let mut in_tags_list = false;
+
+    for line in content.lines() {
+        let line = match line.find(" #") {
+            Some(i) => &line[..i],
+            None => line,
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed == "---" {
+            if !tags.is_empty() {
+                break;
+            }
+            section = "";
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+
+        if in_tags_list {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                if indent >= field_indent.unwrap_or_default() {
+                    tags.push(yaml_scalar(item).to_string());
+                    continue;
+                }
+            }
+            in_tags_list = false;
+        }
+
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        if indent == 0 {
+            section = key.trim();
+            field_indent = None;
+            continue;
+        }
+        // Only direct children of metadata/spec
+        if *field_indent.get_or_insert(indent) != indent {
+            continue;
+        }
+
+        let value = yaml_scalar(value);
+        match (section, key.trim()) {
+            ("metadata", "tags") if value.starts_with('[') => tags.extend(
+                value
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .split(',')
+                    .map(|tag| yaml_scalar(tag).to_string()),
+            ),
+            ("metadata", "tags") => in_tags_list = value.is_empty(),
+            ("spec", "owner") if !value.is_empty() => {
+                // Entity references: [kind:][namespace/]name
+                let (kind, name) = value.split_once(':').unwrap_or(("group", value));
+                let name = name.rsplit('/').next().unwrap_or(name);
+                tags.push(if kind.eq_ignore_ascii_case("user") {
+                    format!("owner:{}", name)
+                } else {
+                    format!("team:{}", name)
+                });
+            }
+            ("spec", "lifecycle") if !value.is_empty() => tags.push(format!("lifecycle:{}", value)),
+            ("spec", "system") if !value.is_empty() => tags.push(format!("system:{}", value)),
+            _ => {}
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_git::git;
+
+    #[test]
+    fn test_codeowners_tags() {
+        let content = "\
+# Default owners
+*       @acme/platform
+*       @acme/payments @alice # overrides the rule above
+/docs/  @acme/docs
+
+[Frontend]
+/web/ @acme/web
+";
+        assert_eq!(
+            codeowners_tags(content),
+            vec!["team:payments".to_string(), "owner:alice".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_catalog_info_tags() {
+        let content = "\
+apiVersion: backstage.io/v1alpha1
+kind: Component
+metadata:
+  name: payments-api
+  tags:
+    - java
+    - 'pci'
+  annotations:
+    github.com/project-slug: acme/payments-api
+spec:
+  type: service
+  owner: group:default/payments # the owning team
+  lifecycle: production
+  system: checkout
+---
+kind: API
+metadata:
+  tags: [ignored]
+";
+        assert_eq!(
+            catalog_info_tags(content),
+            vec![
+                "java",
+                "pci",
+                "team:payments",
+                "lifecycle:production",
+                "system:checkout"
+            ]
+        );
+        assert_eq!(
+            catalog_info_tags("metadata:\n  tags: [go, \"grpc\"]\nspec:\n  owner: user:bob\n"),
+            vec!["go", "grpc", "owner:bob"]
+        );
+    }
+
+    #[test]
+    fn test_catalog_tags_treeless_clone() {
+        let root = std::env::temp_dir().join(format!("maestro-catalog-{}", uuid::Uuid::new_v4()));
+        let source = root.join("source");
+        std::fs::create_dir_all(source.join(".github")).unwrap();
+        git(&source, &["init", "-q", "-b", "main"]);
+        git(&source, &["config", "uploadpack.allowFilter", "true"]);
+        std::fs::write(source.join(".github/CODEOWNERS"), "* @acme/payments\n").unwrap();
+        std::fs::write(
+            source.join("catalog-info.yaml"),
+            "spec:\n  lifecycle: production\n",
+        )
+        .unwrap();
+        git(&source, &["add", "."]);
+        git(&source, &["commit", "-q", "-m", "Initial commit"]);
+
+        let url = format!("file://{}", source.display());
+        let clone = root.join("clone");
+        git(
+            &root,
+            &[
+                "clone",
+                "-q",
+                "--filter=tree:0",
+                "--no-checkout",
+                &url,
+                clone.to_str().unwrap(),
+            ],
+        );
+        let repo = Repository::open(&clone).unwrap();
+        assert!(repo
+            .revparse_single("origin/main")
+            .and_then(|commit| commit.peel_to_tree())
+            .is_err());
+
+        assert_eq!(
+            catalog_tags(&repo, "origin/main", None).unwrap(),
+            vec![
+                "lifecycle:production".to_string(),
+                "team:payments".to_string()
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::Result;
+use git2::{IndexEntryExtendedFlag, Repository};
+use std::collections::HashSet;
+use std::fmt;
+
+/// How much of a repository an admin clone downloads
+/// Anything but a full clone is made with the git CLI, as libgit2 can't filter or deepen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CloneStrategy {
+    /// Every commit, tree and blob
+    #[default]
+    Full,
+    /// Every commit and tree; blobs are fetched when a worktree checks them out
+    Blobless,
+    /// Every commit; trees and blobs are fetched when a worktree checks them out
+    Treeless,
+    /// Only the last `depth` commits of each branch
+    Shallow { depth: u32 },
+}
+
+impl CloneStrategy {
+    /// Parse "full", "blobless", "treeless", "shallow" (depth 1) or "shallow:<depth>"
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim() {
+            "full" => Ok(Self::Full),
+            "blobless" => Ok(Self::Blobless),
+            "treeless" => Ok(Self::Treeless),
+            "shallow" => Ok(Self::Shallow { depth: 1 }),
+            other => {
+                let depth = other
+                    .strip_prefix("shallow:")
+                    .ok_or_else(|| anyhow::anyhow!("Unknown clone strategy: {}", other))?;
+                match depth.trim().parse::<u32>() {
+                    Ok(depth) if depth > 0 => Ok(Self::Shallow { depth }),
+                    _ => anyhow::bail!("Invalid shallow clone depth: {}", depth),
+                }
+            }
+        }
+    }
+
+    /// Arguments to `git clone`; worktrees are checked out from the clone, never the clone itself
+    pub fn clone_args(self) -> Vec<String> {
+        let mut args = match self {
+            Self::Full => Vec::new(),
+            Self::Blobless => vec!["--filter=blob:none".to_string()],
+            Self::Treeless => vec!["--filter=tree:0".to_string()],
+            // Every branch, as pushed execution branches are looked up among origin's
+            Self::Shallow { depth } => vec![
+                format!("--depth={}", depth),
+                "--no-single-branch".to_string(),
+            ],
+        };
+        args.push("--no-checkout".to_string());
+        args
+    }
+
+    /// Arguments to `git fetch`; partial clones keep their filter from the clone's config
+    pub fn fetch_args(self) -> Vec<String> {
+        match self {
+            Self::Shallow { depth } => vec![format!("--depth={}", depth)],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for CloneStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => write!(f, "full"),
+            Self::Blobless => write!(f, "blobless"),
+            Self::Treeless => write!(f, "treeless"),
+            Self::Shallow { depth } => write!(f, "shallow:{}", depth),
+        }
+    }
+}
+
+/// Clone strategy of a repository: its own if set, else its promptset's, else a full clone
+/// Only applies when the admin clone is created; an existing clone keeps how it was made
+pub fn effective_clone_strategy(
+    repository: Option<&str>,
+    promptset: Option<&str>,
+) -> CloneStrategy {
+    let Some(value) = repository.or(promptset) else {
+        return CloneStrategy::Full;
+    };
+    CloneStrategy::parse(value).unwrap_or_else(|e| {
+        log::warn!("[checkout] {}, using a full clone", e);
+        CloneStrategy::Full
+    })
+}
+
+/// Trimmed, deduplicated sparse checkout patterns, rejecting ones git would take for options
+/// or that would split into several patterns
+pub fn normalize_sparse_patterns(patterns: &[String]) -> Result<Vec<String>> {
+    let mut seen = HashSet::new();
+    let mut normalized = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            continue;
+        }
+        if pattern.starts_with('-') || pattern.contains(['\n', '\r']) {
+            anyhow::bail!("Invalid sparse checkout pattern: {}", pattern);
+        }
+        if seen.insert(pattern) {
+            normalized.push(pattern.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+/// Arguments to `git sparse-checkout set` for a worktree: every pattern of the repository and
+/// of the promptset, so each gets the files it asks for; None to check out everything
+/// Plain directories use cone mode, which git matches fastest; any glob or negation switches
+/// all patterns to .gitignore-style matching
+pub fn sparse_checkout_args(repository: &[String], promptset: &[String]) -> Option<Vec<String>> {
+    let patterns: Vec<&String> = repository.iter().chain(promptset).collect();
+    if patterns.is_empty() {
+        return None;
+    }
+
+    let cone = patterns
+        .iter()
+        .all(|pattern| !pattern.contains(['*', '?', '[', '!']));
+    let mut args = vec![
+        "sparse-checkout".to_string(),
+        "set".to_string(),
+        if cone { "--cone" } else { "--no-cone" }.to_string(),
+        "--".to_string(),
+    ];
+    let mut seen = HashSet::new();
+    for pattern in patterns {
+        let pattern = if cone {
+            pattern.trim_matches('/')
+        } else {
+            pattern.as_str()
+        };
+        if !pattern.is_empty() && seen.insert(pattern) {
+            args.push(pattern.to_string());
+        }
+    }
+    Some(args)
+}
+
+/// Index paths a sparse checkout left out of a worktree
+/// libgit2 doesn't know about sparse checkouts and reports these files as deleted
+pub fn skip_worktree_paths(repo: &Repository) -> Result<HashSet<String>, git2::Error> {
+    let index = repo.index()?;
+    Ok(index
+        .iter()
+        .filter(|entry| {
+            IndexEntryExtendedFlag::from_bits_truncate(entry.flags_extended)
+                .contains(IndexEntryExtendedFlag::SKIP_WORKTREE)
+        })
+        .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_clone_strategy() {
+        assert_eq!(CloneStrategy::parse("full").unwrap(), CloneStrategy::Full);
+        assert_eq!(
+            CloneStrategy::parse(" blobless ").unwrap(),
+            CloneStrategy::Blobless
+        );
+        assert_eq!(
+            CloneStrategy::parse("treeless").unwrap(),
+            CloneStrategy::Treeless
+        );
+        assert_eq!(
+            CloneStrategy::parse("shallow").unwrap(),
+            CloneStrategy::Shallow { depth: 1 }
+        );
+        assert_eq!(
+            CloneStrategy::parse("shallow:50").unwrap(),
+            CloneStrategy::Shallow { depth: 50 }
+        );
+        assert!(CloneStrategy::parse("shallow:0").is_err());
+        assert!(CloneStrategy::parse("shallow:deep").is_err());
+        assert!(CloneStrategy::parse("sparse").is_err());
+
+        for strategy in ["full", "blobless", "treeless", "shallow:50"] {
+            assert_eq!(
+                CloneStrategy::parse(strategy).unwrap().to_string(),
+                strategy
+            );
+        }
+    }
+
+    #[test]
+    fn test_clone_and_fetch_args() {
+        assert_eq!(
+            CloneStrategy::Full.clone_args(),
+            strings(&["--no-checkout"])
+        );
+        assert_eq!(
+            CloneStrategy::Blobless.clone_args(),
+            strings(&["--filter=blob:none", "--no-checkout"])
+        );
+        assert_eq!(
+            CloneStrategy::Shallow { depth: 10 }.clone_args(),
+            strings(&["--depth=10", "--no-single-branch", "--no-checkout"])
+        );
+        assert!(CloneStrategy::Treeless.fetch_args().is_empty());
+        assert_eq!(
+            CloneStrategy::Shallow { depth: 10 }.fetch_args(),
+            strings(&["--depth=10"])
+        );
+    }
+
+    #[test]
+    fn test_effective_clone_strategy() {
+        assert_eq!(effective_clone_strategy(None, None), CloneStrategy::Full);
+        assert_eq!(
+            effective_clone_strategy(None, Some("blobless")),
+            CloneStrategy::Blobless
+        );
+        assert_eq!(
+            effective_clone_strategy(Some("treeless"), Some("blobless")),
+            CloneStrategy::Treeless
+        );
+        assert_eq!(
+            effective_clone_strategy(Some("bogus"), Some("blobless")),
+            CloneStrategy::Full
+        );
+    }
+
+    #[test]
+    fn test_normalize_sparse_patterns() {
+        assert_eq!(
+            normalize_sparse_patterns(&strings(&[" services/api ", "", "docs", "docs"])).unwrap(),
+            strings(&["services/api", "docs"])
+        );
+        assert!(normalize_sparse_patterns(&strings(&["--stdin"])).is_err());
+        assert!(normalize_sparse_patterns(&strings(&["a\nb"])).is_err());
+    }
+
+    #[test]
+    fn test_sparse_checkout_args() {
+        assert_eq!(sparse_checkout_args(&[], &[]), None);
+        assert_eq!(
+            sparse_checkout_args(&strings(&["/services/api/", "docs"]), &strings(&["docs"])),
+            Some(strings(&[
+                "sparse-checkout",
+                "set",
+                "--cone",
+                "--",
+                "services/api",
+                "docs"
+            ]))
+        );
+        assert_eq!(
+            sparse_checkout_args(&strings(&["services/api"]), &strings(&["*.md"])),
+            Some(strings(&[
+                "sparse-checkout",
+                "set",
+                "--no-cone",
+                "--",
+                "services/api",
+                "*.md"
+            ]))
+        );
+    }
+}
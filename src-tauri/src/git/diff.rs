@@ -150,13 +150,15 @@ pub fn get_worktree_diff(worktree_path: &Path) -> Result<ModifiedFilesResponse>
         .diff_index_to_workdir(None, None)
         .context("Failed to create diff")?;
     let diff_stats = diff.stats().context("Failed to get diff stats")?;
+    let skipped = super::checkout::skip_worktree_paths(&repo).context("Failed to read index")?;
 
     let mut files = Vec::new();
 
     for entry in statuses.iter() {
         let path = entry.path().unwrap_or("").to_string();
 
-        if path.ends_with('/') {
+        // Directories, and files left out by a sparse checkout
+        if path.ends_with('/') || skipped.contains(&path) {
             continue;
         }
 
@@ -1,9 +1,104 @@
 use anyhow::Result;
 
 /// Repository metadata fetched from git hosting provider
-#[derive(Debug, Clone)]
+/// Fields a provider doesn't report are left at their defaults
+#[derive(Debug, Clone, Default)]
 pub struct RepoMetadata {
     pub default_branch: String,
+    /// Canonical "owner/repo" path as the provider reports it, which differs from the path we
+    /// asked for once the repository was renamed or transferred (the API follows the redirect)
+    pub full_name: Option<String>,
+    /// Archived (read-only) on the provider
+    pub archived: bool,
+    /// Disabled or otherwise unavailable on the provider
+    pub disabled: bool,
+    /// "public", "private" or "internal"
+    pub visibility: Option<String>,
+    pub language: Option<String>,
+    pub topics: Vec<String>,
+    /// Repository size in kilobytes
+    pub size_kb: Option<i64>,
+    /// Last push (ms since epoch), or last activity where the provider doesn't track pushes
+    pub pushed_at: Option<i64>,
+    /// Whether our token may push to the repository, if the provider says
+    pub can_push: Option<bool>,
+}
+
+/// Milliseconds since epoch of an RFC 3339 timestamp from a provider API
+pub(crate) fn parse_timestamp(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.timestamp_millis())
+}
+
+/// Pull request to open from a pushed branch
+#[derive(Debug, Clone)]
+pub struct NewPullRequest {
+    pub source_branch: String,
+    pub target_branch: String,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+/// Pull request opened on the git hosting provider
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequest {
+    pub id: String,
+    pub url: String,
+}
+
+/// Filters for listing the repositories of an organization, user or group
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryFilter {
+    /// Organization or user (GitHub), or group path such as "group/subgroup" (GitLab)
+    pub namespace: String,
+    pub topic: Option<String>,
+    /// Primary language, compared case-insensitively
+    pub language: Option<String>,
+    /// "public", "private" or "internal"
+    pub visibility: Option<String>,
+    /// Only archived (true) or only active (false) repositories; both when unset
+    pub archived: Option<bool>,
+    /// Only forks (true) or only non-forks (false); both when unset
+    pub fork: Option<bool>,
+}
+
+impl RepositoryFilter {
+    /// Whether a listed repository passes the filter
+    /// Providers apply what their API supports server-side; this covers the rest
+    pub fn matches(&self, repository: &RepositoryListing) -> bool {
+        let eq = |expected: &Option<String>, actual: Option<&str>| match expected {
+            Some(expected) => actual.is_some_and(|actual| actual.eq_ignore_ascii_case(expected)),
+            None => true,
+        };
+
+        eq(&self.language, repository.language.as_deref())
+            && eq(&self.visibility, Some(repository.visibility.as_str()))
+            && self.topic.iter().all(|topic| {
+                repository
+                    .topics
+                    .iter()
+                    .any(|t| t.eq_ignore_ascii_case(topic))
+            })
+            && !matches!(self.archived, Some(archived) if archived != repository.archived)
+            && !matches!(self.fork, Some(fork) if fork != repository.fork)
+    }
+}
+
+/// Repository listed by a git hosting provider
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryListing {
+    /// Full path on the host ("owner/repo", "group/subgroup/project")
+    pub full_name: String,
+    pub default_branch: Option<String>,
+    pub language: Option<String>,
+    pub visibility: String,
+    pub topics: Vec<String>,
+    pub archived: bool,
+    pub fork: bool,
 }
 
 /// Context for git provider operations
@@ -20,7 +115,7 @@ impl GitProviderContext {
     }
 }
 
-/// Trait for git hosting provider integrations (GitHub, GitLab, etc.)
+/// Trait for git hosting provider integrations (GitHub, GitLab, Gitea, Bitbucket, etc.)
 #[async_trait::async_trait]
 pub trait GitProvider: Send + Sync {
     /// Unique identifier for this provider (e.g., "github", "gitlab")
@@ -28,7 +123,6 @@ pub trait GitProvider: Send + Sync {
     fn id(&self) -> &'static str;
 
     /// Human-readable display name (e.g., "GitHub", "GitLab")
-    #[allow(dead_code)]
     fn display_name(&self) -> &'static str;
 
     /// Fetch repository metadata (default branch, description, etc.)
@@ -39,35 +133,76 @@ pub trait GitProvider: Send + Sync {
         let metadata = self.get_repo_metadata(ctx).await?;
         Ok(metadata.default_branch)
     }
+
+    /// List the repositories of an organization, user or group, following every page
+    /// Returns None when repositories can't be listed through this provider
+    async fn list_repositories(
+        &self,
+        _filter: &RepositoryFilter,
+    ) -> Result<Option<Vec<RepositoryListing>>> {
+        Ok(None)
+    }
+
+    /// Open a pull request
+    /// Returns None when pull requests can't be created through this provider
+    async fn create_pull_request(
+        &self,
+        _ctx: &GitProviderContext,
+        _request: &NewPullRequest,
+    ) -> Result<Option<PullRequest>> {
+        Ok(None)
+    }
 }
 
 /// Factory function to create a git provider
+/// The repository's host picks the credential profile, falling back to the provider's tokens
 pub async fn create_git_provider(
     provider: &str,
-    _provider_id: &str,
+    provider_id: &str,
+) -> Result<Box<dyn GitProvider>> {
+    use crate::commands::tokens::cached_tokens;
+    use crate::git::PlainGitProvider;
+    use crate::util::git::{provider_api_base_url, resolve_provider_token};
+
+    if provider == "git" {
+        return Ok(Box::new(PlainGitProvider::new()));
+    }
+    let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
+    let token = resolve_provider_token(&tokens, provider, provider_id).await?;
+    let base_url = provider_api_base_url(&tokens, provider, provider_id)?;
+    build_git_provider(provider, token, base_url)
+}
+
+/// Create a git provider for an organization, user or group rather than a repository,
+/// e.g. to list its repositories; `host` picks the credential profile like a provider_id host
+pub async fn create_namespace_git_provider(
+    provider: &str,
+    host: Option<&str>,
+    namespace: &str,
+) -> Result<Box<dyn GitProvider>> {
+    use crate::commands::tokens::cached_tokens;
+    use crate::util::git::{host_api_base_url, resolve_namespace_token};
+
+    let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
+    let token = resolve_namespace_token(&tokens, provider, host, namespace).await?;
+    let base_url = host_api_base_url(&tokens, provider, host)?;
+    build_git_provider(provider, token, base_url)
+}
+
+fn build_git_provider(
+    provider: &str,
+    token: String,
+    base_url: String,
 ) -> Result<Box<dyn GitProvider>> {
-    use crate::commands::tokens::get_token_value;
-    use crate::git::{GitHubGitProvider, GitLabGitProvider};
+    use crate::git::{
+        BitbucketGitProvider, GitHubGitProvider, GitLabGitProvider, GiteaGitProvider,
+    };
 
     match provider {
-        "github" => {
-            let token = get_token_value("github_token")
-                .map_err(|e| anyhow::anyhow!("Failed to access GitHub token: {}", e))?
-                .ok_or_else(|| anyhow::anyhow!("GitHub token not configured"))?;
-
-            let provider = GitHubGitProvider::new(token)?;
-            Ok(Box::new(provider))
-        }
-        "gitlab" => {
-            let token = get_token_value("gitlab_token")
-                .map_err(|e| anyhow::anyhow!("Failed to access GitLab token: {}", e))?
-                .ok_or_else(|| anyhow::anyhow!("GitLab token not configured"))?;
-
-            let base_url = get_token_value("gitlab_instance_url").ok().flatten();
-
-            let provider = GitLabGitProvider::new(token, base_url).await?;
-            Ok(Box::new(provider))
-        }
+        "github" => Ok(Box::new(GitHubGitProvider::new(token, base_url)?)),
+        "gitlab" => Ok(Box::new(GitLabGitProvider::new(token, Some(base_url))?)),
+        "gitea" => Ok(Box::new(GiteaGitProvider::new(token, base_url)?)),
+        "bitbucket" => Ok(Box::new(BitbucketGitProvider::new(token, base_url)?)),
         _ => Err(anyhow::anyhow!("Unsupported git provider: {}", provider)),
     }
 }
@@ -0,0 +1,162 @@
+use super::git_provider::{parse_timestamp, GitProvider, GitProviderContext, RepoMetadata};
+use crate::util::rate_limit;
+use anyhow::Result;
+use serde::Deserialize;
+
+/// Gitea/Forgejo-specific configuration
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct GiteaGitConfig {
+    pub owner: String,
+    pub repo: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaRepository {
+    full_name: Option<String>,
+    default_branch: Option<String>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    internal: bool,
+    /// Empty when Gitea hasn't detected one
+    #[serde(default)]
+    language: String,
+    #[serde(default)]
+    topics: Vec<String>,
+    /// Kilobytes
+    size: Option<i64>,
+    /// Gitea has no last-push time; pushes update this
+    updated_at: Option<String>,
+    permissions: Option<GiteaPermissions>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaPermissions {
+    #[serde(default)]
+    push: bool,
+}
+
+/// Gitea and Forgejo share the same REST API (`/api/v1`)
+#[derive(Clone)]
+pub struct GiteaGitProvider {
+    http_client: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl GiteaGitProvider {
+    pub fn new(token: String, base_url: String) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http_client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl GitProvider for GiteaGitProvider {
+    fn id(&self) -> &'static str {
+        "gitea"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Gitea"
+    }
+
+    async fn get_repo_metadata(&self, ctx: &GitProviderContext) -> Result<RepoMetadata> {
+        let cfg: GiteaGitConfig = ctx.cfg()?;
+        let url = format!("{}/api/v1/repos/{}/{}", self.base_url, cfg.owner, cfg.repo);
+
+        let response = rate_limit::send(
+            self.http_client
+                .get(&url)
+                .header("Authorization", format!("token {}", self.token)),
+        )
+        .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("Gitea returned {} for {}", response.status(), url);
+        }
+        let repo_info: GiteaRepository = response.json().await?;
+
+        let visibility = if repo_info.internal {
+            "internal"
+        } else if repo_info.private {
+            "private"
+        } else {
+            "public"
+        };
+
+        Ok(RepoMetadata {
+            default_branch: repo_info
+                .default_branch
+                .filter(|branch| !branch.is_empty())
+                .unwrap_or_else(|| "main".to_string()),
+            full_name: repo_info.full_name,
+            archived: repo_info.archived,
+            disabled: false,
+            visibility: Some(visibility.to_string()),
+            language: Some(repo_info.language).filter(|language| !language.is_empty()),
+            topics: repo_info.topics,
+            size_kb: repo_info.size,
+            pushed_at: repo_info.updated_at.as_deref().and_then(parse_timestamp),
+            can_push: repo_info.permissions.map(|permissions| permissions.push),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{serve, StubResponse};
+
+    #[tokio::test]
+    async fn test_get_repo_metadata() {
+        let (base_url, _) = serve(|request| {
+            let authorized = request.header("Authorization") == Some("token gitea-token");
+            match request.url.as_str() {
+                "/api/v1/repos/acme/widgets" if authorized => StubResponse::ok(
+                    r#"{"full_name": "acme/widgets", "default_branch": "develop", "archived": true, "private": true,
+                        "language": "Go", "topics": ["infra"], "size": 2048,
+                        "updated_at": "2024-05-01T12:00:00Z", "permissions": {"push": false}}"#,
+                ),
+                // Renamed: Gitea redirects to the new name
+                "/api/v1/repos/acme/gadgets" => {
+                    StubResponse::status(307, "").header("Location", "/api/v1/repos/acme/widgets")
+                }
+                _ => StubResponse::not_found(),
+            }
+        });
+
+        let provider =
+            GiteaGitProvider::new("gitea-token".to_string(), format!("{}/", base_url)).unwrap();
+        let ctx = |repo: &str| GitProviderContext {
+            provider_cfg: serde_json::json!({ "owner": "acme", "repo": repo }),
+        };
+
+        assert_eq!(
+            provider
+                .fetch_default_branch(&ctx("widgets"))
+                .await
+                .unwrap(),
+            "develop"
+        );
+        let metadata = provider.get_repo_metadata(&ctx("widgets")).await.unwrap();
+        assert!(metadata.archived);
+        assert_eq!(metadata.visibility.as_deref(), Some("private"));
+        assert_eq!(metadata.language.as_deref(), Some("Go"));
+        assert_eq!(metadata.topics, vec!["infra".to_string()]);
+        assert_eq!(metadata.size_kb, Some(2048));
+        assert_eq!(metadata.pushed_at, Some(1_714_564_800_000));
+        assert_eq!(metadata.can_push, Some(false));
+
+        let renamed = provider.get_repo_metadata(&ctx("gadgets")).await.unwrap();
+        assert_eq!(renamed.full_name.as_deref(), Some("acme/widgets"));
+        assert!(provider.get_repo_metadata(&ctx("missing")).await.is_err());
+    }
+}
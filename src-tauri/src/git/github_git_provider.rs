@@ -1,6 +1,9 @@
-use super::git_provider::{GitProvider, GitProviderContext, RepoMetadata};
+use super::git_provider::{
+    parse_timestamp, GitProvider, GitProviderContext, RepoMetadata, RepositoryFilter,
+    RepositoryListing,
+};
+use crate::util::rate_limit;
 use anyhow::Result;
-use octocrab::Octocrab;
 
 /// GitHub-specific configuration
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -11,16 +14,150 @@ pub struct GitHubGitConfig {
 
 #[derive(Clone)]
 pub struct GitHubGitProvider {
-    octocrab: Octocrab,
+    http_client: reqwest::Client,
+    api_base_url: String,
+    token: String,
 }
 
 impl GitHubGitProvider {
-    pub fn new(token: String) -> Result<Self> {
-        let octocrab = Octocrab::builder().personal_token(token).build()?;
-        Ok(Self { octocrab })
+    pub fn new(token: String, api_base_url: String) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http_client,
+            api_base_url: api_base_url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    /// GET an API URL within the host's rate limit budget
+    async fn get(&self, url: &str) -> Result<reqwest::Response> {
+        rate_limit::send(
+            self.http_client
+                .get(url)
+                .bearer_auth(&self.token)
+                .header("Accept", "application/vnd.github+json"),
+        )
+        .await
+    }
+
+    /// Login of the user the token belongs to; None for tokens that aren't a user's, such as
+    /// GitHub App installation tokens
+    async fn authenticated_login(&self) -> Result<Option<String>> {
+        let response = self.get(&format!("{}/user", self.api_base_url)).await?;
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+        let user: GitHubUser = response.json().await?;
+        Ok(Some(user.login))
+    }
+
+    /// Route listing a user's repositories: all of their own, private ones included, when the
+    /// token is theirs; only public ones for anyone else
+    async fn user_repos_url(&self, filter: &RepositoryFilter) -> Result<String> {
+        let own = self
+            .authenticated_login()
+            .await?
+            .is_some_and(|login| login.eq_ignore_ascii_case(&filter.namespace));
+        if own {
+            return Ok(format!(
+                "{}/user/repos?affiliation=owner&per_page=100",
+                self.api_base_url
+            ));
+        }
+        if filter
+            .visibility
+            .as_deref()
+            .is_some_and(|visibility| !visibility.eq_ignore_ascii_case("public"))
+        {
+            anyhow::bail!(
+                "GitHub only lists the public repositories of {}, a user other than the token's",
+                filter.namespace
+            );
+        }
+        Ok(format!(
+            "{}/users/{}/repos?type=owner&per_page=100",
+            self.api_base_url, filter.namespace
+        ))
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+#[derive(serde::Deserialize)]
+struct GitHubRepo {
+    full_name: String,
+    default_branch: Option<String>,
+    language: Option<String>,
+    #[serde(default)]
+    private: bool,
+    /// Only reported by newer API versions; older ones just have `private`
+    visibility: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    disabled: bool,
+    #[serde(default)]
+    fork: bool,
+    /// Kilobytes
+    size: Option<i64>,
+    pushed_at: Option<String>,
+    /// Only reported for authenticated requests
+    permissions: Option<GitHubPermissions>,
+}
+
+#[derive(serde::Deserialize)]
+struct GitHubPermissions {
+    #[serde(default)]
+    push: bool,
+}
+
+impl GitHubRepo {
+    fn visibility(&self) -> String {
+        self.visibility
+            .clone()
+            .unwrap_or_else(|| if self.private { "private" } else { "public" }.to_string())
+    }
+}
+
+impl From<GitHubRepo> for RepositoryListing {
+    fn from(repo: GitHubRepo) -> Self {
+        let visibility = repo.visibility();
+        Self {
+            full_name: repo.full_name,
+            default_branch: repo.default_branch,
+            language: repo.language,
+            visibility,
+            topics: repo.topics,
+            archived: repo.archived,
+            fork: repo.fork,
+        }
     }
 }
 
+/// URL of the next page from a `Link` header, if there is one
+fn next_page_url(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let link = headers.get(reqwest::header::LINK)?.to_str().ok()?;
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == "rel=\"next\"")
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
 #[async_trait::async_trait]
 impl GitProvider for GitHubGitProvider {
     fn id(&self) -> &'static str {
@@ -33,12 +170,154 @@ impl GitProvider for GitHubGitProvider {
 
     async fn get_repo_metadata(&self, ctx: &GitProviderContext) -> Result<RepoMetadata> {
         let cfg: GitHubGitConfig = ctx.cfg()?;
-        let repo_info = self.octocrab.repos(&cfg.owner, &cfg.repo).get().await?;
+        let url = format!("{}/repos/{}/{}", self.api_base_url, cfg.owner, cfg.repo);
+
+        let response = self.get(&url).await?;
+        if !response.status().is_success() {
+            anyhow::bail!("GitHub returned {} for {}", response.status(), url);
+        }
+        let repo_info: GitHubRepo = response.json().await?;
 
         Ok(RepoMetadata {
+            visibility: Some(repo_info.visibility()),
+            full_name: Some(repo_info.full_name),
             default_branch: repo_info
                 .default_branch
                 .unwrap_or_else(|| "main".to_string()),
+            archived: repo_info.archived,
+            disabled: repo_info.disabled,
+            language: repo_info.language,
+            topics: repo_info.topics,
+            size_kb: repo_info.size,
+            pushed_at: repo_info.pushed_at.as_deref().and_then(parse_timestamp),
+            can_push: repo_info.permissions.map(|permissions| permissions.push),
         })
     }
+
+    async fn list_repositories(
+        &self,
+        filter: &RepositoryFilter,
+    ) -> Result<Option<Vec<RepositoryListing>>> {
+        // Organizations and users list their repositories under different routes
+        let mut url = format!(
+            "{}/orgs/{}/repos?type=all&per_page=100",
+            self.api_base_url, filter.namespace
+        );
+        let mut first_page = true;
+        let mut repositories = Vec::new();
+
+        loop {
+            let response = self.get(&url).await?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND && first_page {
+                first_page = false;
+                url = self.user_repos_url(filter).await?;
+                continue;
+            }
+            if !response.status().is_success() {
+                anyhow::bail!("GitHub returned {} for {}", response.status(), url);
+            }
+            // Past the first page there's no going back to the other route
+            first_page = false;
+
+            let next = next_page_url(response.headers());
+            let page: Vec<GitHubRepo> = response.json().await?;
+            repositories.extend(
+                page.into_iter()
+                    .map(RepositoryListing::from)
+                    .filter(|repository| filter.matches(repository)),
+            );
+
+            match next {
+                Some(next) => url = next,
+                None => break,
+            }
+        }
+
+        Ok(Some(repositories))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{serve, StubResponse};
+
+    #[tokio::test]
+    async fn test_list_repositories() {
+        // The Link header needs the server's own address
+        let next_page = std::sync::Arc::new(std::sync::OnceLock::<String>::new());
+        let link = next_page.clone();
+        let (base_url, _) = serve(move |request| {
+            match request.url.as_str() {
+            // Not an organization
+            "/orgs/octo/repos?type=all&per_page=100" => StubResponse::status(404, "{}"),
+            "/users/octo/repos?type=owner&per_page=100" => StubResponse::ok(
+                r#"[{"full_name": "octo/api", "default_branch": "main", "language": "Rust", "private": false, "topics": ["service"], "archived": false, "fork": false},
+                    {"full_name": "octo/old", "default_branch": "master", "language": "Rust", "private": false, "topics": ["service"], "archived": true, "fork": false}]"#,
+            )
+            .header("Link", link.get().unwrap()),
+            "/users/octo/repos?type=owner&per_page=100&page=2" => StubResponse::ok(
+                r#"[{"full_name": "octo/web", "default_branch": "main", "language": "TypeScript", "visibility": "internal", "topics": ["service"], "archived": false, "fork": false},
+                    {"full_name": "octo/lib", "default_branch": "main", "language": "rust", "visibility": "private", "topics": ["Service"], "archived": false, "fork": true}]"#,
+            ),
+            _ => StubResponse::status(500, "{}"),
+        }
+        });
+        next_page
+            .set(format!(
+                "<{}/users/octo/repos?type=owner&per_page=100&page=2>; rel=\"next\"",
+                base_url
+            ))
+            .unwrap();
+
+        let provider = GitHubGitProvider::new("token".to_string(), base_url).unwrap();
+        let filter = RepositoryFilter {
+            namespace: "octo".to_string(),
+            topic: Some("service".to_string()),
+            language: Some("Rust".to_string()),
+            archived: Some(false),
+            ..Default::default()
+        };
+        let repositories = provider.list_repositories(&filter).await.unwrap().unwrap();
+
+        let names: Vec<&str> = repositories
+            .iter()
+            .map(|repository| repository.full_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["octo/api", "octo/lib"]);
+        assert_eq!(repositories[1].visibility, "private");
+        assert!(repositories[1].fork);
+    }
+
+    #[tokio::test]
+    async fn test_list_own_user_repositories() {
+        let (base_url, _) = serve(|request| match request.url.as_str() {
+            "/orgs/octo/repos?type=all&per_page=100"
+            | "/orgs/other/repos?type=all&per_page=100" => StubResponse::status(404, "{}"),
+            "/user" => StubResponse::ok(r#"{"login": "Octo"}"#),
+            "/user/repos?affiliation=owner&per_page=100" => StubResponse::ok(
+                r#"[{"full_name": "octo/secret", "default_branch": "main", "private": true}]"#,
+            ),
+            _ => StubResponse::status(500, "{}"),
+        });
+        let provider = GitHubGitProvider::new("token".to_string(), base_url).unwrap();
+
+        // The token's own user lists its private repositories too
+        let filter = RepositoryFilter {
+            namespace: "octo".to_string(),
+            visibility: Some("private".to_string()),
+            ..Default::default()
+        };
+        let repositories = provider.list_repositories(&filter).await.unwrap().unwrap();
+        assert_eq!(repositories.len(), 1);
+        assert_eq!(repositories[0].full_name, "octo/secret");
+
+        // Another user's private repositories can't be listed, rather than silently none
+        let filter = RepositoryFilter {
+            namespace: "other".to_string(),
+            visibility: Some("private".to_string()),
+            ..Default::default()
+        };
+        assert!(provider.list_repositories(&filter).await.is_err());
+    }
 }
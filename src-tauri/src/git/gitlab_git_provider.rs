@@ -1,8 +1,9 @@
-use super::git_provider::{GitProvider, GitProviderContext, RepoMetadata};
+use super::git_provider::{
+    parse_timestamp, GitProvider, GitProviderContext, RepoMetadata, RepositoryFilter,
+    RepositoryListing,
+};
+use crate::util::rate_limit;
 use anyhow::Result;
-use gitlab::api::projects::Project;
-use gitlab::api::AsyncQuery;
-use gitlab::{AsyncGitlab, GitlabBuilder};
 use serde::Deserialize;
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -12,30 +13,97 @@ pub struct GitLabGitConfig {
 
 #[derive(Debug, Deserialize)]
 struct GitLabProject {
+    path_with_namespace: String,
     default_branch: Option<String>,
+    #[serde(default)]
+    archived: bool,
+    visibility: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    /// Only reported with `statistics=true` to members with at least Reporter access
+    statistics: Option<GitLabStatistics>,
+    /// GitLab has no last-push time; any push updates this
+    last_activity_at: Option<String>,
+    permissions: Option<GitLabPermissions>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabStatistics {
+    /// Bytes
+    repository_size: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabPermissions {
+    project_access: Option<GitLabAccess>,
+    group_access: Option<GitLabAccess>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabAccess {
+    access_level: u32,
+}
+
+/// Developer, the lowest access level allowed to push to unprotected branches
+const DEVELOPER_ACCESS_LEVEL: u32 = 30;
+
+/// Whether the token's access level allows pushing; unknown when neither the project nor its
+/// group reports one (admins, and tokens GitLab omits memberships for)
+fn can_push(permissions: GitLabPermissions) -> Option<bool> {
+    let levels: Vec<u32> = [permissions.project_access, permissions.group_access]
+        .into_iter()
+        .flatten()
+        .map(|access| access.access_level)
+        .collect();
+    if levels.is_empty() {
+        return None;
+    }
+    Some(levels.iter().any(|level| *level >= DEVELOPER_ACCESS_LEVEL))
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabListedProject {
+    path_with_namespace: String,
+    default_branch: Option<String>,
+    #[serde(default)]
+    visibility: String,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    archived: bool,
+    /// Only reported when the token can see the source project
+    forked_from_project: Option<serde_json::Value>,
 }
 
 #[derive(Clone)]
 pub struct GitLabGitProvider {
-    client: AsyncGitlab,
+    http_client: reqwest::Client,
+    api_base_url: String,
+    token: String,
 }
 
 impl GitLabGitProvider {
-    pub async fn new(token: String, base_url: Option<String>) -> Result<Self> {
-        let url = base_url.unwrap_or_else(|| "https://gitlab.com".to_string());
-
-        // GitlabBuilder expects URL without protocol prefix (case-insensitive)
-        let url = if let Ok(parsed) = reqwest::Url::parse(&url) {
-            // Extract host from properly parsed URL
-            parsed.host_str().unwrap_or("gitlab.com").to_string()
-        } else {
-            // Fallback: strip protocol case-insensitively
-            url.trim_start_matches(|c: char| !c.is_alphanumeric() && c != '.')
-                .to_string()
-        };
+    pub fn new(token: String, base_url: Option<String>) -> Result<Self> {
+        let base_url = base_url.unwrap_or_else(|| "https://gitlab.com".to_string());
+        let http_client = reqwest::Client::builder()
+            .user_agent(format!("maestro/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http_client,
+            api_base_url: format!("{}/api/v4", base_url.trim_end_matches('/')),
+            token,
+        })
+    }
 
-        let client = GitlabBuilder::new(url, token).build_async().await?;
-        Ok(Self { client })
+    /// GET an API URL within the host's rate limit budget
+    async fn get(&self, url: &str, query: &[(&str, String)]) -> Result<reqwest::Response> {
+        rate_limit::send(
+            self.http_client
+                .get(url)
+                .query(query)
+                .header("PRIVATE-TOKEN", &self.token),
+        )
+        .await
     }
 }
 
@@ -52,11 +120,180 @@ impl GitProvider for GitLabGitProvider {
     async fn get_repo_metadata(&self, ctx: &GitProviderContext) -> Result<RepoMetadata> {
         let cfg: GitLabGitConfig = ctx.cfg()?;
 
-        let endpoint = Project::builder().project(cfg.project_id).build()?;
-        let project: GitLabProject = endpoint.query_async(&self.client).await?;
+        // Project paths are sent as a single URL-encoded ID
+        let url = format!(
+            "{}/projects/{}",
+            self.api_base_url,
+            cfg.project_id.replace('/', "%2F")
+        );
+
+        let response = self
+            .get(&url, &[("statistics", "true".to_string())])
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("GitLab returned {} for {}", response.status(), url);
+        }
+        let project: GitLabProject = response.json().await?;
+
+        // The project itself doesn't report languages; the largest share is the primary one
+        let language = match self.get(&format!("{}/languages", url), &[]).await {
+            Ok(response) if response.status().is_success() => response
+                .json::<std::collections::HashMap<String, f64>>()
+                .await
+                .ok()
+                .and_then(|languages| {
+                    languages
+                        .into_iter()
+                        .max_by(|a, b| a.1.total_cmp(&b.1))
+                        .map(|(language, _)| language)
+                }),
+            _ => None,
+        };
+
+        let can_push = project.permissions.and_then(can_push);
 
         Ok(RepoMetadata {
             default_branch: project.default_branch.unwrap_or_else(|| "main".to_string()),
+            full_name: Some(project.path_with_namespace),
+            archived: project.archived,
+            disabled: false,
+            visibility: project.visibility,
+            language,
+            topics: project.topics,
+            size_kb: project
+                .statistics
+                .map(|statistics| statistics.repository_size / 1024),
+            pushed_at: project
+                .last_activity_at
+                .as_deref()
+                .and_then(parse_timestamp),
+            can_push,
         })
     }
+
+    async fn list_repositories(
+        &self,
+        filter: &RepositoryFilter,
+    ) -> Result<Option<Vec<RepositoryListing>>> {
+        // GitLab filters everything but forks server-side, including the language through
+        // with_programming_language; listed projects don't report their language, so it isn't
+        // checked again here
+        let local_filter = RepositoryFilter {
+            language: None,
+            ..filter.clone()
+        };
+        let mut query = vec![("per_page", "100".to_string())];
+        if let Some(topic) = &filter.topic {
+            query.push(("topic", topic.clone()));
+        }
+        if let Some(language) = &filter.language {
+            query.push(("with_programming_language", language.clone()));
+        }
+        if let Some(visibility) = &filter.visibility {
+            query.push(("visibility", visibility.to_lowercase()));
+        }
+        if let Some(archived) = filter.archived {
+            query.push(("archived", archived.to_string()));
+        }
+
+        // Groups (including their subgroups) first, then users
+        let namespace = filter.namespace.trim_matches('/');
+        let mut url = format!(
+            "{}/groups/{}/projects",
+            self.api_base_url,
+            namespace.replace('/', "%2F")
+        );
+        let mut route_query = query.clone();
+        route_query.push(("include_subgroups", "true".to_string()));
+        let mut user_route = Some((
+            format!("{}/users/{}/projects", self.api_base_url, namespace),
+            query,
+        ));
+
+        let mut page = "1".to_string();
+        let mut repositories = Vec::new();
+        loop {
+            let mut page_query = route_query.clone();
+            page_query.push(("page", page.clone()));
+
+            let response = self.get(&url, &page_query).await?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                if let Some((user_url, user_query)) = user_route.take() {
+                    url = user_url;
+                    route_query = user_query;
+                    continue;
+                }
+            }
+            if !response.status().is_success() {
+                anyhow::bail!("GitLab returned {} for {}", response.status(), url);
+            }
+            // Past the first page there's no going back to the other route
+            user_route = None;
+
+            // x-next-page is empty on the last page
+            let next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+            let projects: Vec<GitLabListedProject> = response.json().await?;
+            repositories.extend(
+                projects
+                    .into_iter()
+                    .map(|project| RepositoryListing {
+                        full_name: project.path_with_namespace,
+                        default_branch: project.default_branch,
+                        language: None,
+                        visibility: project.visibility,
+                        topics: project.topics,
+                        archived: project.archived,
+                        fork: project.forked_from_project.is_some(),
+                    })
+                    .filter(|repository| local_filter.matches(repository)),
+            );
+
+            match next_page {
+                Some(next) => page = next,
+                None => break,
+            }
+        }
+
+        Ok(Some(repositories))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_push() {
+        let permissions = |json: &str| serde_json::from_str::<GitLabPermissions>(json).unwrap();
+
+        assert_eq!(
+            can_push(permissions(
+                r#"{"project_access": {"access_level": 30}, "group_access": null}"#
+            )),
+            Some(true)
+        );
+        assert_eq!(
+            can_push(permissions(
+                r#"{"project_access": {"access_level": 20}, "group_access": {"access_level": 40}}"#
+            )),
+            Some(true)
+        );
+        assert_eq!(
+            can_push(permissions(
+                r#"{"project_access": null, "group_access": {"access_level": 20}}"#
+            )),
+            Some(false)
+        );
+        assert_eq!(
+            can_push(permissions(
+                r#"{"project_access": null, "group_access": null}"#
+            )),
+            None
+        );
+    }
 }
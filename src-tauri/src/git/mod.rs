@@ -1,16 +1,28 @@
+pub(crate) mod applicability;
+mod bitbucket_git_provider;
+pub(crate) mod catalog;
+pub(crate) mod checkout;
 pub(crate) mod diff;
 pub(crate) mod git_provider;
+mod gitea_git_provider;
+pub(crate) mod github_app;
 mod github_git_provider;
 mod gitlab_git_provider;
+mod plain_git_provider;
 pub(crate) mod service;
 
+pub use bitbucket_git_provider::BitbucketGitProvider;
 pub(crate) use diff::{
     get_committed_diff, get_committed_file_diff, get_worktree_diff, get_worktree_file_diff,
     ModifiedFilesResponse,
 };
-pub use git_provider::GitProviderContext;
+pub use git_provider::{
+    GitProviderContext, NewPullRequest, PullRequest, RepoMetadata, RepositoryFilter,
+};
+pub use gitea_git_provider::GiteaGitProvider;
 pub use github_git_provider::GitHubGitProvider;
 pub use gitlab_git_provider::GitLabGitProvider;
+pub use plain_git_provider::PlainGitProvider;
 pub(crate) use service::GitService;
 
 /// Check if a commit has been pushed to the remote
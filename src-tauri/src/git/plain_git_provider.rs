@@ -0,0 +1,81 @@
+use super::git_provider::{GitProvider, GitProviderContext, RepoMetadata};
+use super::GitService;
+use anyhow::Result;
+
+/// Plain git remote configuration
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct PlainGitConfig {
+    /// Clone URL or local path, as stored in the repository's provider_id
+    pub url: String,
+}
+
+/// Repositories on a git remote with no hosting API (internal git servers, bare repos, mirrors)
+/// Metadata comes from the remote itself
+#[derive(Clone, Default)]
+pub struct PlainGitProvider;
+
+impl PlainGitProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl GitProvider for PlainGitProvider {
+    fn id(&self) -> &'static str {
+        "git"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Git"
+    }
+
+    async fn get_repo_metadata(&self, ctx: &GitProviderContext) -> Result<RepoMetadata> {
+        let cfg: PlainGitConfig = ctx.cfg()?;
+
+        let default_branch =
+            tokio::task::spawn_blocking(move || GitService::remote_default_branch(&cfg.url))
+                .await??;
+
+        Ok(RepoMetadata {
+            // Empty repositories have no HEAD yet
+            default_branch: default_branch.unwrap_or_else(|| "main".to_string()),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_default_branch_from_remote_head() {
+        let dir = std::env::temp_dir().join(format!("maestro-plain-git-{}", uuid::Uuid::new_v4()));
+        let repo = git2::Repository::init_bare(&dir).unwrap();
+
+        // One commit on "trunk", with HEAD pointing at it
+        let signature = git2::Signature::now("Maestro", "maestro@example.com").unwrap();
+        let tree_id = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(
+            Some("refs/heads/trunk"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )
+        .unwrap();
+        repo.set_head("refs/heads/trunk").unwrap();
+
+        let provider = PlainGitProvider::new();
+        let ctx = GitProviderContext {
+            provider_cfg: serde_json::json!({ "url": dir.to_string_lossy() }),
+        };
+        let branch = provider.fetch_default_branch(&ctx).await;
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(branch.unwrap(), "trunk");
+    }
+}
@@ -1,5 +1,8 @@
-use git2::{Error as Git2Error, FetchOptions, PushOptions, RemoteCallbacks, Repository};
+use git2::{Error as Git2Error, FetchOptions, Oid, PushOptions, RemoteCallbacks, Repository};
 use std::path::Path;
+use std::process::{Command, Output};
+
+use super::checkout::CloneStrategy;
 
 pub(crate) struct GitService;
 
@@ -22,6 +25,39 @@ fn configure_credentials<'a>(callbacks: &mut RemoteCallbacks<'a>, auth: Option<G
     }
 }
 
+/// Credential helper answering with the username and token of a `git_command`
+const ENV_CREDENTIAL_HELPER: &str = "credential.helper=!f() { test \"$1\" = get && \
+    echo username=\"$MAESTRO_GIT_USERNAME\" && echo password=\"$MAESTRO_GIT_PASSWORD\"; }; f";
+
+/// Object ids per `fetch_missing_objects_cli` request, keeping the command line short
+const FETCH_OBJECTS_CHUNK: usize = 1000;
+
+/// A git CLI command that fails rather than prompting for credentials
+/// With `auth`, HTTPS credentials come from the environment through a one-off credential helper,
+/// so the token never appears in arguments, remote URLs or the user's credential store
+pub(crate) fn git_command(auth: Option<&GitAuth<'_>>) -> Command {
+    let mut command = Command::new("git");
+    command.env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(auth) = auth {
+        command
+            .args(["-c", "credential.helper=", "-c", ENV_CREDENTIAL_HELPER])
+            .env("MAESTRO_GIT_USERNAME", auth.username)
+            .env("MAESTRO_GIT_PASSWORD", auth.password);
+    }
+    command
+}
+
+fn check_git_output(operation: &str, output: &Output) -> anyhow::Result<()> {
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            operation,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
 impl GitService {
     pub(crate) fn open(path: &Path) -> Result<Repository, Git2Error> {
         Repository::open(path)
@@ -105,6 +141,131 @@ impl GitService {
         Ok(())
     }
 
+    /// Clone with the git CLI, which unlike libgit2 makes partial and shallow clones
+    /// Without `auth`, SSH remotes use ssh-agent as with `clone_repo`
+    pub(crate) fn clone_repo_cli(
+        url: &str,
+        path: &Path,
+        strategy: CloneStrategy,
+        auth: Option<GitAuth<'_>>,
+    ) -> anyhow::Result<()> {
+        let output = git_command(auth.as_ref())
+            .arg("clone")
+            .args(strategy.clone_args())
+            .arg("--")
+            .arg(url)
+            .arg(path)
+            .output()?;
+        check_git_output("clone", &output)
+    }
+
+    /// Fetch with the git CLI, into a clone made by `clone_repo_cli`
+    pub(crate) fn fetch_cli(
+        repo_path: &Path,
+        remote_name: &str,
+        refspecs: &[&str],
+        strategy: CloneStrategy,
+        auth: Option<GitAuth<'_>>,
+    ) -> anyhow::Result<()> {
+        let output = git_command(auth.as_ref())
+            .arg("fetch")
+            .args(strategy.fetch_args())
+            .arg(remote_name)
+            .args(refspecs)
+            .current_dir(repo_path)
+            .output()?;
+        check_git_output("fetch", &output)
+    }
+
+    /// Whether a clone is partial or shallow, so only the git CLI may fetch into it
+    pub(crate) fn needs_cli(repo: &Repository) -> bool {
+        repo.is_shallow()
+            || repo
+                .config()
+                .and_then(|config| config.get_bool("remote.origin.promisor"))
+                .unwrap_or(false)
+    }
+
+    /// Blobs at `rev` as (path, id), limited to `paths` unless empty, listed with the git CLI,
+    /// which fetches the trees a treeless clone is missing
+    pub(crate) fn tree_blobs_cli(
+        repo: &Repository,
+        rev: &str,
+        paths: &[&str],
+        auth: Option<&GitAuth<'_>>,
+    ) -> anyhow::Result<Vec<(String, Oid)>> {
+        let output = git_command(auth)
+            .args(["ls-tree", "-r", "-z", "--full-tree", rev, "--"])
+            .args(paths)
+            .current_dir(repo.path())
+            .output()?;
+        check_git_output("ls-tree", &output)?;
+
+        let mut blobs = Vec::new();
+        for entry in output.stdout.split(|&b| b == 0).filter(|e| !e.is_empty()) {
+            let entry = String::from_utf8_lossy(entry);
+            // <mode> SP <type> SP <id> TAB <path>
+            let Some((info, path)) = entry.split_once('\t') else {
+                continue;
+            };
+            let mut fields = info.split(' ');
+            if fields.nth(1) != Some("blob") {
+                continue;
+            }
+            if let Some(id) = fields.next().and_then(|id| Oid::from_str(id).ok()) {
+                blobs.push((path.to_string(), id));
+            }
+        }
+        Ok(blobs)
+    }
+
+    /// Fetch the objects a partial clone is missing in one request, so libgit2 can read them
+    /// FETCH_HEAD is left alone, as this isn't a fetch of the remote's branches
+    pub(crate) fn fetch_missing_objects_cli(
+        repo: &Repository,
+        ids: &[Oid],
+        auth: Option<&GitAuth<'_>>,
+    ) -> anyhow::Result<()> {
+        let odb = repo.odb()?;
+        let missing: Vec<String> = ids
+            .iter()
+            .filter(|id| !odb.exists(**id))
+            .map(Oid::to_string)
+            .collect();
+        for chunk in missing.chunks(FETCH_OBJECTS_CHUNK) {
+            let output = git_command(auth)
+                .args([
+                    "fetch",
+                    "--no-tags",
+                    "--no-write-fetch-head",
+                    "--recurse-submodules=no",
+                    "origin",
+                ])
+                .args(chunk)
+                .current_dir(repo.path())
+                .output()?;
+            check_git_output("fetch", &output)?;
+        }
+        Ok(())
+    }
+
+    /// Branch a remote's HEAD points at, read without cloning
+    /// Returns None when the remote advertises no HEAD (e.g. an empty repository)
+    pub(crate) fn remote_default_branch(url: &str) -> Result<Option<String>, Git2Error> {
+        let mut remote = git2::Remote::create_detached(url)?;
+        let mut callbacks = RemoteCallbacks::new();
+        configure_credentials(&mut callbacks, None);
+
+        let connection = remote.connect_auth(git2::Direction::Fetch, Some(callbacks), None)?;
+        match connection.default_branch() {
+            Ok(head) => Ok(head
+                .as_str()
+                .map(|name| name.trim_start_matches("refs/heads/").to_string())),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     pub(crate) fn rev_parse(repo: &Repository, spec: &str) -> Result<String, Git2Error> {
         let obj = repo.revparse_single(spec)?;
         Ok(obj.id().to_string())
@@ -113,6 +274,7 @@ impl GitService {
     pub(crate) fn has_uncommitted_changes(repo: &Repository) -> Result<bool, Git2Error> {
         use git2::Status;
         let statuses = repo.statuses(None)?;
+        let skipped = super::checkout::skip_worktree_paths(repo)?;
 
         let has_changes = statuses.iter().any(|entry| {
             let status = entry.status();
@@ -121,6 +283,11 @@ impl GitService {
                 return false;
             }
 
+            // Files left out by a sparse checkout aren't deleted
+            if entry.path().is_some_and(|path| skipped.contains(path)) {
+                return false;
+            }
+
             true
         });
 
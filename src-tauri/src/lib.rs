@@ -9,9 +9,11 @@ pub mod ci;
 mod commands;
 mod db;
 mod git;
+mod maintenance;
 mod sourcegraph;
 pub mod types;
 mod util;
+mod webhooks;
 
 fn build_menu(app: &AppHandle<Wry>) -> tauri::Result<()> {
     // Create About menu item
@@ -104,6 +106,7 @@ fn build_menu(app: &AppHandle<Wry>) -> tauri::Result<()> {
 pub struct Paths {
     pub admin_repo_dir: PathBuf,
     pub worktree_dir: PathBuf,
+    pub ci_log_dir: PathBuf,
     pub db_path: PathBuf,
 }
 
@@ -200,6 +203,7 @@ pub fn run() {
             let paths = Paths {
                 admin_repo_dir: base_dir.join("repos"),
                 worktree_dir: base_dir.join("executions"),
+                ci_log_dir: base_dir.join("ci-logs"),
                 db_path: base_dir.join("maestro.db"),
             };
 
@@ -208,6 +212,7 @@ pub fn run() {
                 .expect("Failed to create admin repo directory");
             std::fs::create_dir_all(&paths.worktree_dir)
                 .expect("Failed to create worktree directory");
+            std::fs::create_dir_all(&paths.ci_log_dir).expect("Failed to create CI log directory");
             if let Some(db_parent) = paths.db_path.parent() {
                 std::fs::create_dir_all(db_parent).expect("Failed to create database directory");
             }
@@ -224,17 +229,46 @@ pub fn run() {
             commands::executor::reconcile_on_startup(&store)
                 .expect("Failed to reconcile execution states");
 
+            // Read webhook listener settings before the store moves into managed state
+            let webhook_port = match (store.get_webhook_enabled(), store.get_webhook_port()) {
+                (Ok(true), Ok(port)) => Some(port),
+                (Ok(true), Err(e)) => {
+                    log::warn!("Invalid webhook settings: {}", e);
+                    None
+                }
+                _ => None,
+            };
+
             // Manage both store and paths as state
             app.manage(Mutex::new(store));
             app.manage(paths);
 
+            // Resume polling for any CI still being watched
+            ci::start_ci_watcher(app.handle().clone());
+
+            // Keep default branches and other provider metadata fresh in the background
+            commands::metadata_sync::start_metadata_sync(app.handle().clone());
+
+            // Garbage collect clones, prune orphans and enforce the disk budget on a schedule
+            maintenance::start_clone_maintenance(app.handle().clone());
+
+            // Resume the local webhook listener if it was enabled
+            if let Some(port) = webhook_port {
+                if let Err(e) = webhooks::start_webhook_server(app.handle().clone(), port) {
+                    log::warn!("Failed to start webhook listener: {}", e);
+                }
+            }
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::db::create_repository,
             commands::db::update_repository_name,
+            commands::db::update_repository_checkout,
             commands::db::sync_repository_metadata,
             commands::db::get_repository,
+            commands::db::get_repository_renames,
+            commands::db::import_repositories,
             commands::db::find_repository,
             commands::db::get_all_repositories,
             commands::db::create_promptset,
@@ -244,6 +278,11 @@ pub fn run() {
             commands::db::update_promptset_validation,
             commands::db::update_promptset_auto_validate,
             commands::db::update_promptset_repositories,
+            commands::db::update_promptset_repository_query,
+            commands::db::update_promptset_applicability,
+            commands::db::update_promptset_checkout,
+            commands::db::update_promptset_repository_tag_expression,
+            commands::db::get_repository_query_snapshots,
             commands::db::create_prompt_revision,
             commands::db::get_prompt_revision,
             commands::db::find_prompt_revision_by_prefix,
@@ -258,6 +297,12 @@ pub fn run() {
             commands::db::delete_promptset,
             commands::db::delete_repository,
             commands::db::delete_prompt_revision,
+            commands::tags::add_repository_tags,
+            commands::tags::remove_repository_tag,
+            commands::tags::get_repository_tags,
+            commands::tags::get_tag_counts,
+            commands::tags::find_repositories_by_tags,
+            commands::tags::import_repository_tags,
             commands::executor::execute_promptset,
             commands::executor::prepare_executions,
             commands::executor::execute_prompt,
@@ -265,6 +310,7 @@ pub fn run() {
             commands::executor::resume_execution,
             commands::executor::commit_changes,
             commands::executor::push_commit,
+            commands::executor::create_pull_request,
             commands::executor::stop_execution,
             commands::executor::stop_validation,
             commands::executor::stop_all_executions,
@@ -275,14 +321,42 @@ pub fn run() {
             commands::executor::reconcile_stuck_ci,
             commands::ci::start_ci_check,
             commands::ci::refresh_ci_status,
+            commands::ci::get_ci_failure_logs,
+            commands::ci::get_execution_ci_checks,
+            commands::ci::get_revision_ci_check_stats,
+            commands::ci::get_ci_check_policy,
+            commands::ci::set_ci_check_policy,
+            commands::ci::delete_ci_check_policy,
+            commands::ci::get_ci_watches,
+            commands::ci::get_repository_ci_config,
+            commands::ci::set_repository_ci_config,
+            commands::ci::delete_repository_ci_config,
+            commands::webhooks::get_webhook_server_status,
+            commands::webhooks::start_webhook_server,
+            commands::webhooks::stop_webhook_server,
             commands::github::get_config_paths,
             commands::tokens::set_token,
             commands::tokens::delete_token,
             commands::tokens::get_all_tokens,
             commands::tokens::get_all_tokens_masked,
+            commands::tokens::set_credential_profile,
+            commands::tokens::delete_credential_profile,
             commands::settings::get_setting,
             commands::settings::set_setting,
             commands::settings::get_ci_stuck_threshold_minutes,
+            commands::settings::get_ci_poll_schedule,
+            commands::settings::set_ci_poll_schedule,
+            commands::settings::get_ci_watch_max_hours,
+            commands::settings::set_ci_watch_max_hours,
+            commands::settings::get_metadata_sync_interval_hours,
+            commands::settings::set_metadata_sync_interval_hours,
+            commands::metadata_sync::get_default_branch_changes,
+            commands::maintenance::get_disk_usage,
+            commands::maintenance::run_clone_maintenance,
+            commands::settings::get_clone_gc_interval_hours,
+            commands::settings::set_clone_gc_interval_hours,
+            commands::settings::get_disk_budget_gb,
+            commands::settings::set_disk_budget_gb,
             commands::settings::get_max_concurrent_executions,
             commands::settings::get_first_run_complete,
             commands::settings::set_first_run_complete,
@@ -304,10 +378,14 @@ pub fn run() {
             commands::analysis::delete_analysis,
             commands::health_check::health_check_github,
             commands::health_check::health_check_gitlab,
+            commands::health_check::health_check_gitea,
+            commands::health_check::health_check_bitbucket,
+            commands::health_check::health_check_credential_profile,
             commands::health_check::health_check_sourcegraph,
             commands::health_check::health_check_git,
             commands::health_check::health_check_amp,
             commands::health_check::health_check_amp_token,
+            commands::health_check::get_rate_limits,
             commands::app_info::get_app_info,
         ])
         .plugin(tauri_plugin_notification::init())
@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Bytes used by the files under a directory; symlinks count as links and aren't followed
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Admin clones under a directory: every directory holding a `.git`, without looking inside
+/// the clones themselves
+pub fn find_clones(dir: &Path) -> Vec<PathBuf> {
+    let mut clones = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return clones;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            continue;
+        }
+        let path = entry.path();
+        if path.join(".git").exists() {
+            clones.push(path);
+        } else {
+            clones.extend(find_clones(&path));
+        }
+    }
+    clones.sort();
+    clones
+}
+
+/// Execution worktrees, `{worktree_dir}/{promptset_id}/{execution_id}`, with their execution ids
+pub fn find_worktrees(worktree_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut worktrees = Vec::new();
+    let Ok(promptsets) = fs::read_dir(worktree_dir) else {
+        return worktrees;
+    };
+    for promptset in promptsets.flatten() {
+        let Ok(executions) = fs::read_dir(promptset.path()) else {
+            continue;
+        };
+        for execution in executions.flatten() {
+            if execution
+                .file_type()
+                .is_ok_and(|file_type| file_type.is_dir())
+            {
+                let execution_id = execution.file_name().to_string_lossy().into_owned();
+                worktrees.push((execution_id, execution.path()));
+            }
+        }
+    }
+    worktrees
+}
+
+/// Whether git still has linked worktrees registered in a clone; run `git worktree prune`
+/// first so worktrees deleted by hand don't count
+pub fn has_linked_worktrees(clone: &Path) -> bool {
+    fs::read_dir(clone.join(".git").join("worktrees"))
+        .is_ok_and(|mut entries| entries.next().is_some())
+}
+
+/// Whether a directory, or the `.git` inside it, changed within `within`
+pub fn modified_within(path: &Path, within: Duration) -> bool {
+    changed_within(path, within) || changed_within(&path.join(".git"), within)
+}
+
+/// Whether a clone was fetched within `within`, going by the FETCH_HEAD every fetch rewrites
+pub fn fetched_within(clone: &Path, within: Duration) -> bool {
+    changed_within(&clone.join(".git").join("FETCH_HEAD"), within)
+}
+
+/// A modification time in the future counts as recent
+fn changed_within(path: &Path, within: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| modified.elapsed().map_or(true, |age| age < within))
+}
+
+/// Remove the parents of a removed directory up to `root`, for as long as they are empty
+pub fn remove_empty_parents(path: &Path, root: &Path) {
+    let mut parent = path.parent();
+    while let Some(dir) = parent.filter(|dir| *dir != root && dir.starts_with(root)) {
+        // remove_dir fails on non-empty directories
+        if fs::remove_dir(dir).is_err() {
+            break;
+        }
+        parent = dir.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_clones_and_sizes() {
+        let root = std::env::temp_dir().join(format!("maestro-disk-{}", uuid::Uuid::new_v4()));
+        let clone = root.join("acme").join("widgets");
+        let nested = root.join("group").join("subgroup").join("project");
+        fs::create_dir_all(clone.join(".git").join("objects")).unwrap();
+        fs::create_dir_all(nested.join(".git")).unwrap();
+        // A directory inside a clone is never taken for another clone
+        fs::create_dir_all(clone.join("vendor").join("lib").join(".git")).unwrap();
+        fs::write(clone.join(".git").join("objects").join("pack"), [0u8; 1000]).unwrap();
+        fs::write(clone.join("README.md"), [0u8; 24]).unwrap();
+        fs::create_dir_all(root.join("empty").join("owner")).unwrap();
+
+        assert_eq!(find_clones(&root), vec![clone.clone(), nested.clone()]);
+        assert_eq!(dir_size(&clone), 1024);
+        assert_eq!(dir_size(&root.join("missing")), 0);
+
+        assert!(!has_linked_worktrees(&clone));
+        fs::create_dir_all(clone.join(".git").join("worktrees").join("exec-1")).unwrap();
+        assert!(has_linked_worktrees(&clone));
+
+        fs::remove_dir_all(&nested).unwrap();
+        remove_empty_parents(&nested, &root);
+        assert!(!root.join("group").exists());
+        remove_empty_parents(&root.join("empty").join("owner").join("repo"), &root);
+        assert!(!root.join("empty").exists());
+        assert!(root.exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_find_worktrees() {
+        let root = std::env::temp_dir().join(format!("maestro-worktrees-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("promptset-1").join("exec-1")).unwrap();
+        fs::create_dir_all(root.join("promptset-1").join("exec-2")).unwrap();
+        fs::write(root.join("promptset-1").join("notes.txt"), "").unwrap();
+
+        let mut worktrees = find_worktrees(&root);
+        worktrees.sort();
+        assert_eq!(
+            worktrees,
+            vec![
+                (
+                    "exec-1".to_string(),
+                    root.join("promptset-1").join("exec-1")
+                ),
+                (
+                    "exec-2".to_string(),
+                    root.join("promptset-1").join("exec-2")
+                ),
+            ]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
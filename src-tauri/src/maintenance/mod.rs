@@ -0,0 +1,740 @@
+mod disk;
+
+pub(crate) use disk::remove_empty_parents;
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::time::{sleep, Duration};
+
+use crate::commands::executor::get_repo_lock;
+use crate::commands::tokens::{cached_tokens, AllTokens};
+use crate::db::store::{CloneActivity, Repository, Store};
+use crate::util::git::{decode_path_separators, is_clone_url};
+use crate::util::paths::{admin_clone_path, repo_path_in};
+use crate::Paths;
+use disk::{
+    dir_size, fetched_within, find_clones, find_worktrees, has_linked_worktrees, modified_within,
+};
+
+/// Hours between scheduled `git gc` runs of each admin clone, by default weekly
+pub const DEFAULT_GC_INTERVAL_HOURS: i64 = 7 * 24;
+
+/// How often the scheduled maintenance runs
+const TICK_SECS: u64 = 60 * 60;
+
+/// Clones fetched more recently than this are never evicted: an execution may be about to
+/// add its worktree. Clones and worktrees without an owner are kept as long after their last
+/// change, as their repository or execution may not have been in the snapshot
+const EVICTION_MIN_IDLE_MS: i64 = 60 * 60 * 1000;
+
+const BYTES_PER_GB: u64 = 1024 * 1024 * 1024;
+
+lazy_static::lazy_static! {
+    static ref SCHEDULER_ACTIVE: Mutex<bool> = Mutex::new(false);
+    /// Held while a maintenance pass runs, so scheduled and manual passes don't overlap
+    static ref PASS_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Disk used by one admin clone and the execution worktrees made from it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneUsage {
+    /// Clone directory relative to the admin repo directory, e.g. "acme/widgets"
+    pub path: String,
+    /// None for a clone no repository owns any more
+    pub repository_id: Option<String>,
+    pub provider_id: Option<String>,
+    pub clone_bytes: u64,
+    pub worktree_bytes: u64,
+    pub worktree_count: usize,
+    pub last_used_at: Option<i64>,
+    pub last_gc_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsage {
+    pub clone_bytes: u64,
+    pub worktree_bytes: u64,
+    /// Worktrees whose execution was deleted, included in worktree_bytes
+    pub orphaned_worktree_bytes: u64,
+    pub budget_bytes: Option<u64>,
+    pub clones: Vec<CloneUsage>,
+}
+
+/// What a maintenance pass did
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReport {
+    /// Provider ids of the repositories whose clone was garbage collected
+    pub collected: Vec<String>,
+    /// Clones removed because no repository owns them any more
+    pub removed_orphans: Vec<String>,
+    /// Worktrees removed because their execution was deleted
+    pub removed_worktrees: usize,
+    /// Provider ids of the repositories whose clone was evicted to stay within the disk budget
+    pub evicted: Vec<String>,
+    pub freed_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+/// What maintenance needs from the database, read up front so the store isn't held while
+/// walking the disk or running git
+struct Snapshot {
+    repositories: Vec<Repository>,
+    /// Configured hosts, which tell a provider_id's host apart from its path
+    tokens: AllTokens,
+    activity: HashMap<String, CloneActivity>,
+    execution_repository_ids: HashMap<String, String>,
+    gc_interval_hours: i64,
+    budget_bytes: Option<u64>,
+}
+
+impl Snapshot {
+    fn load(store: &Mutex<Store>, tokens: AllTokens) -> Result<Self> {
+        let store = store.lock().unwrap();
+        let budget_gb = store.get_disk_budget_gb()?;
+        Ok(Snapshot {
+            repositories: store.get_all_repositories()?,
+            tokens,
+            activity: store.get_clone_activity()?,
+            execution_repository_ids: store.get_execution_repository_ids()?,
+            gc_interval_hours: store.get_clone_gc_interval_hours()?,
+            budget_bytes: (budget_gb > 0).then(|| budget_gb as u64 * BYTES_PER_GB),
+        })
+    }
+
+    /// Repositories by the path of their admin clone
+    fn repositories_by_clone(&self, admin_repo_dir: &Path) -> HashMap<PathBuf, &Repository> {
+        self.repositories
+            .iter()
+            .filter_map(|repository| {
+                Some((
+                    clone_path(&self.tokens, admin_repo_dir, repository)?,
+                    repository,
+                ))
+            })
+            .collect()
+    }
+}
+
+fn clone_path(
+    tokens: &AllTokens,
+    admin_repo_dir: &Path,
+    repository: &Repository,
+) -> Option<PathBuf> {
+    admin_clone_path(admin_repo_dir, tokens, &repository.provider_id).ok()
+}
+
+/// Whether a repository may own a clone: its provider_id doesn't parse, or puts the clone there
+/// with the current settings or with its first segment taken as a host or not, as configured
+/// instances and credential profiles come and go
+fn may_own(
+    tokens: &AllTokens,
+    admin_repo_dir: &Path,
+    repository: &Repository,
+    clone: &Path,
+) -> bool {
+    let Some(current) = clone_path(tokens, admin_repo_dir, repository) else {
+        return true;
+    };
+    if current == clone {
+        return true;
+    }
+    if is_clone_url(&repository.provider_id) {
+        return false;
+    }
+
+    let decoded = decode_path_separators(&repository.provider_id);
+    let segments: Vec<&str> = decoded.split('/').collect();
+    let Some((repo, owner)) = segments.split_last() else {
+        return false;
+    };
+    let unhosted = repo_path_in(admin_repo_dir, &owner.join("/"), repo);
+    let hosted = match owner.split_first() {
+        Some((host, rest)) if !rest.is_empty() => {
+            let host = host.to_lowercase().replace(':', "_");
+            let owner = std::iter::once(host.as_str())
+                .chain(rest.iter().copied())
+                .collect::<Vec<_>>();
+            Some(repo_path_in(admin_repo_dir, &owner.join("/"), repo))
+        }
+        _ => None,
+    };
+    unhosted == clone || hosted.as_deref() == Some(clone)
+}
+
+/// Whether any repository may own a clone right now, rather than when the snapshot was taken
+fn clone_owned(
+    store: &Mutex<Store>,
+    tokens: &AllTokens,
+    admin_repo_dir: &Path,
+    clone: &Path,
+) -> Result<bool> {
+    Ok(store
+        .lock()
+        .unwrap()
+        .get_all_repositories()?
+        .iter()
+        .any(|repository| may_own(tokens, admin_repo_dir, repository, clone)))
+}
+
+/// Owner and repo of a clone path, the key of its repository lock
+fn clone_owner_repo(admin_repo_dir: &Path, clone: &Path) -> (String, String) {
+    let relative = relative_path(admin_repo_dir, clone);
+    match relative.rsplit_once('/') {
+        Some((owner, repo)) => (owner.to_string(), repo.to_string()),
+        None => (String::new(), relative),
+    }
+}
+
+fn relative_path(admin_repo_dir: &Path, clone: &Path) -> String {
+    clone
+        .strip_prefix(admin_repo_dir)
+        .unwrap_or(clone)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn run_git(clone: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git").args(args).current_dir(clone).output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Disk used by every admin clone and execution worktree
+pub fn disk_usage(store: &Mutex<Store>, paths: &Paths, tokens: AllTokens) -> Result<DiskUsage> {
+    let snapshot = Snapshot::load(store, tokens)?;
+    let by_clone = snapshot.repositories_by_clone(&paths.admin_repo_dir);
+
+    // Worktree usage by repository id
+    let mut worktrees: HashMap<&str, (u64, usize)> = HashMap::new();
+    let mut worktree_bytes = 0;
+    let mut orphaned_worktree_bytes = 0;
+    for (execution_id, path) in find_worktrees(&paths.worktree_dir) {
+        let bytes = dir_size(&path);
+        worktree_bytes += bytes;
+        match snapshot.execution_repository_ids.get(&execution_id) {
+            Some(repository_id) => {
+                let usage = worktrees.entry(repository_id).or_default();
+                usage.0 += bytes;
+                usage.1 += 1;
+            }
+            None => orphaned_worktree_bytes += bytes,
+        }
+    }
+
+    let clones: Vec<CloneUsage> = find_clones(&paths.admin_repo_dir)
+        .into_iter()
+        .map(|clone| {
+            let repository = by_clone.get(&clone);
+            let activity = repository
+                .and_then(|repository| snapshot.activity.get(&repository.id))
+                .cloned()
+                .unwrap_or_default();
+            let (worktree_bytes, worktree_count) = repository
+                .and_then(|repository| worktrees.get(repository.id.as_str()))
+                .copied()
+                .unwrap_or_default();
+            CloneUsage {
+                path: relative_path(&paths.admin_repo_dir, &clone),
+                repository_id: repository.map(|repository| repository.id.clone()),
+                provider_id: repository.map(|repository| repository.provider_id.clone()),
+                clone_bytes: dir_size(&clone),
+                worktree_bytes,
+                worktree_count,
+                last_used_at: activity.used_at,
+                last_gc_at: activity.gc_at,
+            }
+        })
+        .collect();
+
+    Ok(DiskUsage {
+        clone_bytes: clones.iter().map(|clone| clone.clone_bytes).sum(),
+        worktree_bytes,
+        orphaned_worktree_bytes,
+        budget_bytes: snapshot.budget_bytes,
+        clones,
+    })
+}
+
+/// One maintenance pass, in order:
+/// 1. remove clones no repository owns and worktrees whose execution was deleted, unless they
+///    changed within the last hour; ownership is checked again before each removal
+/// 2. `git worktree prune` and `git gc` every clone not collected within the gc interval
+///    (every clone with `force_gc`)
+/// 3. evict the least recently used clones while clones and worktrees exceed the disk budget;
+///    clones with worktrees, or fetched within the last hour, are kept. Each clone's last use
+///    is read again under its repository lock, as an execution may have fetched it meanwhile
+///
+/// Evicted clones are cloned again on their repository's next execution
+pub fn run_maintenance(
+    store: &Mutex<Store>,
+    paths: &Paths,
+    tokens: AllTokens,
+    force_gc: bool,
+) -> Result<MaintenanceReport> {
+    let Ok(_pass) = PASS_LOCK.try_lock() else {
+        anyhow::bail!("Clone maintenance is already running");
+    };
+    let snapshot = Snapshot::load(store, tokens)?;
+    run_pass(store, paths, &snapshot, force_gc)
+}
+
+fn run_pass(
+    store: &Mutex<Store>,
+    paths: &Paths,
+    snapshot: &Snapshot,
+    force_gc: bool,
+) -> Result<MaintenanceReport> {
+    let by_clone = snapshot.repositories_by_clone(&paths.admin_repo_dir);
+    let mut report = MaintenanceReport::default();
+    let min_idle = Duration::from_millis(EVICTION_MIN_IDLE_MS as u64);
+
+    let clones = find_clones(&paths.admin_repo_dir);
+    let mut kept = Vec::new();
+    for clone in clones {
+        if by_clone.contains_key(&clone) {
+            kept.push(clone);
+            continue;
+        }
+        if modified_within(&clone, min_idle) {
+            continue;
+        }
+        let (owner, repo) = clone_owner_repo(&paths.admin_repo_dir, &clone);
+        let repo_lock = get_repo_lock(&owner, &repo);
+        let _lock = repo_lock.lock().unwrap();
+        // Owned under other settings, or added or renamed onto this path since the snapshot
+        if clone_owned(store, &snapshot.tokens, &paths.admin_repo_dir, &clone)? {
+            continue;
+        }
+        // Worktrees of this clone would break without it
+        if run_git(&clone, &["worktree", "prune"]).is_err() || has_linked_worktrees(&clone) {
+            log::warn!(
+                "[maintenance] Kept orphaned clone {}: it still has worktrees",
+                clone.display()
+            );
+            continue;
+        }
+        let bytes = dir_size(&clone);
+        match std::fs::remove_dir_all(&clone) {
+            Ok(()) => {
+                remove_empty_parents(&clone, &paths.admin_repo_dir);
+                log::info!("[maintenance] Removed orphaned clone {}", clone.display());
+                report.freed_bytes += bytes;
+                report
+                    .removed_orphans
+                    .push(relative_path(&paths.admin_repo_dir, &clone));
+            }
+            Err(e) => report.errors.push(format!(
+                "Failed to remove orphaned clone {}: {}",
+                clone.display(),
+                e
+            )),
+        }
+    }
+
+    let mut worktree_bytes = 0;
+    for (execution_id, path) in find_worktrees(&paths.worktree_dir) {
+        let bytes = dir_size(&path);
+        // An execution created since the snapshot may be setting up its worktree
+        if snapshot
+            .execution_repository_ids
+            .contains_key(&execution_id)
+            || modified_within(&path, min_idle)
+            || store
+                .lock()
+                .unwrap()
+                .get_execution(&execution_id)?
+                .is_some()
+        {
+            worktree_bytes += bytes;
+            continue;
+        }
+        match std::fs::remove_dir_all(&path) {
+            Ok(()) => {
+                remove_empty_parents(&path, &paths.worktree_dir);
+                report.freed_bytes += bytes;
+                report.removed_worktrees += 1;
+            }
+            Err(e) => {
+                worktree_bytes += bytes;
+                report.errors.push(format!(
+                    "Failed to remove worktree {}: {}",
+                    path.display(),
+                    e
+                ));
+            }
+        }
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let gc_due_before =
+        (snapshot.gc_interval_hours > 0).then(|| now - snapshot.gc_interval_hours * 60 * 60 * 1000);
+    let mut clone_sizes = Vec::new();
+    for clone in kept {
+        let repository = by_clone[&clone];
+        let activity = snapshot
+            .activity
+            .get(&repository.id)
+            .cloned()
+            .unwrap_or_default();
+        let gc_due = force_gc
+            || match (gc_due_before, activity.gc_at) {
+                (Some(before), Some(gc_at)) => gc_at < before,
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+        if gc_due {
+            let before = dir_size(&clone);
+            let (owner, repo) = clone_owner_repo(&paths.admin_repo_dir, &clone);
+            let repo_lock = get_repo_lock(&owner, &repo);
+            let result = {
+                let _lock = repo_lock.lock().unwrap();
+                run_git(&clone, &["worktree", "prune"])
+                    .and_then(|_| run_git(&clone, &["gc", "--quiet"]))
+            };
+            match result {
+                Ok(()) => {
+                    store
+                        .lock()
+                        .unwrap()
+                        .mark_repository_clone_gc(&repository.id)?;
+                    report.freed_bytes += before.saturating_sub(dir_size(&clone));
+                    report.collected.push(repository.provider_id.clone());
+                }
+                Err(e) => report
+                    .errors
+                    .push(format!("{}: {}", repository.provider_id, e)),
+            }
+        }
+        clone_sizes.push((clone.clone(), repository, activity, dir_size(&clone)));
+    }
+
+    let Some(budget_bytes) = snapshot.budget_bytes else {
+        return Ok(report);
+    };
+    let mut used_bytes = worktree_bytes + clone_sizes.iter().map(|(.., bytes)| bytes).sum::<u64>();
+    // Least recently used first; clones never fetched since tracking began count as oldest
+    clone_sizes.sort_by_key(|(_, _, activity, _)| activity.used_at.unwrap_or_default());
+    for (clone, repository, activity, bytes) in clone_sizes {
+        if used_bytes <= budget_bytes {
+            break;
+        }
+        if activity
+            .used_at
+            .is_some_and(|used_at| now - used_at < EVICTION_MIN_IDLE_MS)
+        {
+            continue;
+        }
+        let (owner, repo) = clone_owner_repo(&paths.admin_repo_dir, &clone);
+        let repo_lock = get_repo_lock(&owner, &repo);
+        let _lock = repo_lock.lock().unwrap();
+        // Used since the snapshot; a fetch that hasn't been recorded yet still rewrote FETCH_HEAD
+        if fetched_within(&clone, min_idle)
+            || store
+                .lock()
+                .unwrap()
+                .get_clone_used_at(&repository.id)?
+                .is_some_and(|used_at| now - used_at < EVICTION_MIN_IDLE_MS)
+        {
+            continue;
+        }
+        // Worktrees of this clone would break without it
+        if run_git(&clone, &["worktree", "prune"]).is_err() || has_linked_worktrees(&clone) {
+            continue;
+        }
+        match std::fs::remove_dir_all(&clone) {
+            Ok(()) => {
+                remove_empty_parents(&clone, &paths.admin_repo_dir);
+                log::info!(
+                    "[maintenance] Evicted the clone of {} ({} bytes)",
+                    repository.provider_id,
+                    bytes
+                );
+                used_bytes -= bytes;
+                report.freed_bytes += bytes;
+                report.evicted.push(repository.provider_id.clone());
+            }
+            Err(e) => report.errors.push(format!(
+                "Failed to evict the clone of {}: {}",
+                repository.provider_id, e
+            )),
+        }
+    }
+    if used_bytes > budget_bytes {
+        report.errors.push(format!(
+            "Still {} bytes over the disk budget: the remaining clones have worktrees or were used in the last hour",
+            used_bytes - budget_bytes
+        ));
+    }
+
+    Ok(report)
+}
+
+/// Start the scheduled clone maintenance (no-op if it is already running)
+/// The gc interval and disk budget are read on every pass, so changes apply without a restart
+pub fn start_clone_maintenance(app: AppHandle) {
+    {
+        let mut active = SCHEDULER_ACTIVE.lock().unwrap();
+        if *active {
+            return;
+        }
+        *active = true;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        log::info!("[maintenance] Started");
+        loop {
+            let pass_app = app.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let store = pass_app.state::<Mutex<Store>>();
+                let paths = pass_app.state::<Paths>();
+                let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
+                run_maintenance(&store, &paths, tokens, false)
+            })
+            .await;
+
+            match result {
+                Ok(Ok(report)) => {
+                    for error in &report.errors {
+                        log::warn!("[maintenance] {}", error);
+                    }
+                    if report.freed_bytes > 0 {
+                        log::info!("[maintenance] Freed {} bytes", report.freed_bytes);
+                    }
+                }
+                Ok(Err(e)) => log::error!("[maintenance] Pass failed: {}", e),
+                Err(e) => log::error!("[maintenance] Pass panicked: {}", e),
+            }
+
+            sleep(Duration::from_secs(TICK_SECS)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_git::git;
+    use std::time::SystemTime;
+
+    fn paths(root: &Path) -> Paths {
+        Paths {
+            admin_repo_dir: root.join("repos"),
+            worktree_dir: root.join("worktrees"),
+            ci_log_dir: root.join("ci-logs"),
+            db_path: root.join("maestro.db"),
+        }
+    }
+
+    fn make_clone(paths: &Paths, owner: &str, repo: &str, bytes: usize) -> PathBuf {
+        let clone = repo_path_in(&paths.admin_repo_dir, owner, repo);
+        std::fs::create_dir_all(&clone).unwrap();
+        run_git(&clone, &["init", "-q"]).unwrap();
+        std::fs::write(clone.join("data.bin"), vec![0u8; bytes]).unwrap();
+        clone
+    }
+
+    /// Make a directory and its `.git` look untouched for two hours
+    fn age(path: &Path) {
+        let two_hours_ago = SystemTime::now() - Duration::from_secs(2 * 60 * 60);
+        for path in [path.to_path_buf(), path.join(".git")] {
+            if path.exists() {
+                std::fs::File::open(&path)
+                    .unwrap()
+                    .set_modified(two_hours_ago)
+                    .unwrap();
+            }
+        }
+    }
+
+    fn hours_ago(hours: i64) -> CloneActivity {
+        CloneActivity {
+            used_at: Some(chrono::Utc::now().timestamp_millis() - hours * 60 * 60 * 1000),
+            gc_at: None,
+        }
+    }
+
+    #[test]
+    fn test_removes_orphans() {
+        let root =
+            std::env::temp_dir().join(format!("maestro-maintenance-{}", uuid::Uuid::new_v4()));
+        let paths = paths(&root);
+        let store = Mutex::new(Store::new(":memory:").unwrap());
+
+        let (execution_id, promptset_id) = {
+            let mut store = store.lock().unwrap();
+            let repository = store.create_repository("github", "acme/api").unwrap();
+            let promptset = store
+                .create_promptset("Upgrade", vec![repository.id.clone()], None, false)
+                .unwrap();
+            let revision = store
+                .create_prompt_revision(&promptset.id, "Upgrade the build", None)
+                .unwrap();
+            let execution = store
+                .create_execution(&promptset.id, &revision.id, &repository.id)
+                .unwrap();
+            (execution.id, promptset.id)
+        };
+        let owned = make_clone(&paths, "acme", "api", 10);
+        let orphan = make_clone(&paths, "acme", "gone", 10);
+        let fresh_orphan = make_clone(&paths, "acme", "fresh", 10);
+        let late = make_clone(&paths, "acme", "late", 10);
+        for clone in [&owned, &orphan, &late] {
+            age(clone);
+        }
+
+        let worktrees = paths.worktree_dir.join(&promptset_id);
+        for execution in [execution_id.as_str(), "deleted", "new"] {
+            std::fs::create_dir_all(worktrees.join(execution)).unwrap();
+        }
+        age(&worktrees.join(&execution_id));
+        age(&worktrees.join("deleted"));
+
+        let mut snapshot = Snapshot::load(&store, AllTokens::default()).unwrap();
+        snapshot.gc_interval_hours = 0;
+        // Added after the snapshot was taken
+        store
+            .lock()
+            .unwrap()
+            .create_repository("github", "acme/late")
+            .unwrap();
+
+        let report = run_pass(&store, &paths, &snapshot, false).unwrap();
+        assert_eq!(report.removed_orphans, vec!["acme/gone".to_string()]);
+        assert_eq!(report.removed_worktrees, 1);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(!orphan.exists());
+        assert!(owned.exists() && fresh_orphan.exists() && late.exists());
+        assert!(!worktrees.join("deleted").exists());
+        assert!(worktrees.join(&execution_id).exists() && worktrees.join("new").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_keeps_orphans_with_worktrees_or_another_owner() {
+        let root =
+            std::env::temp_dir().join(format!("maestro-maintenance-{}", uuid::Uuid::new_v4()));
+        let paths = paths(&root);
+        let store = Mutex::new(Store::new(":memory:").unwrap());
+        // Cloned while gitlab.internal:8443 was a configured instance, since removed
+        store
+            .lock()
+            .unwrap()
+            .create_repository("gitlab", "gitlab.internal:8443/acme/api")
+            .unwrap();
+        let hosted = make_clone(&paths, "gitlab.internal_8443/acme", "api", 10);
+
+        let busy = make_clone(&paths, "acme", "busy", 10);
+        git(&busy, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        let worktree = root.join("elsewhere").join("busy");
+        run_git(
+            &busy,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "--detach",
+                worktree.to_str().unwrap(),
+            ],
+        )
+        .unwrap();
+        let orphan = make_clone(&paths, "acme", "gone", 10);
+        for clone in [&hosted, &busy, &orphan] {
+            age(clone);
+        }
+
+        let mut snapshot = Snapshot::load(&store, AllTokens::default()).unwrap();
+        snapshot.gc_interval_hours = 0;
+
+        let report = run_pass(&store, &paths, &snapshot, false).unwrap();
+        assert_eq!(report.removed_orphans, vec!["acme/gone".to_string()]);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(hosted.exists() && busy.exists() && worktree.join(".git").exists());
+        assert!(!orphan.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used_within_budget() {
+        let root =
+            std::env::temp_dir().join(format!("maestro-maintenance-{}", uuid::Uuid::new_v4()));
+        let paths = paths(&root);
+        let store = Mutex::new(Store::new(":memory:").unwrap());
+
+        let mut repositories = Vec::new();
+        let mut clones = Vec::new();
+        for name in ["a", "b", "c", "d"] {
+            let repository = store
+                .lock()
+                .unwrap()
+                .create_repository("github", &format!("acme/{}", name))
+                .unwrap();
+            clones.push(make_clone(&paths, "acme", name, 4096));
+            repositories.push(repository);
+        }
+        for clone in &clones {
+            age(clone);
+        }
+        let total: u64 = clones.iter().map(|clone| dir_size(clone)).sum();
+
+        // a was used longest ago, then b, c and d; a and b have to go
+        let mut snapshot = Snapshot::load(&store, AllTokens::default()).unwrap();
+        snapshot.gc_interval_hours = 0;
+        snapshot.budget_bytes = Some(total - dir_size(&clones[0]) - dir_size(&clones[1]));
+        for (repository, hours) in repositories.iter().zip([5, 4, 3, 2]) {
+            snapshot
+                .activity
+                .insert(repository.id.clone(), hours_ago(hours));
+        }
+
+        let report = run_pass(&store, &paths, &snapshot, false).unwrap();
+        assert_eq!(
+            report.evicted,
+            vec!["acme/a".to_string(), "acme/b".to_string()]
+        );
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(!clones[0].exists() && !clones[1].exists());
+        assert!(clones[2].exists() && clones[3].exists());
+
+        // Since the snapshot, c was used by an execution and d fetched by one still running
+        let mut snapshot = Snapshot::load(&store, AllTokens::default()).unwrap();
+        snapshot.gc_interval_hours = 0;
+        snapshot.budget_bytes = Some(1);
+        snapshot
+            .activity
+            .insert(repositories[2].id.clone(), hours_ago(3));
+        snapshot
+            .activity
+            .insert(repositories[3].id.clone(), hours_ago(3));
+        store
+            .lock()
+            .unwrap()
+            .touch_repository_clone(&repositories[2].id)
+            .unwrap();
+        std::fs::write(clones[3].join(".git").join("FETCH_HEAD"), "").unwrap();
+
+        let report = run_pass(&store, &paths, &snapshot, false).unwrap();
+        assert!(report.evicted.is_empty());
+        assert!(report.errors[0].starts_with("Still"));
+        assert!(clones[2].exists() && clones[3].exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
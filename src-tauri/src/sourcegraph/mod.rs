@@ -1,6 +1,13 @@
+use crate::commands::tokens::AllTokens;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+/// Hard cap on the repositories one search collects, so runaway queries stop early
+pub const MAX_REPOSITORY_MATCHES: usize = 5000;
+
+/// Repositories whose metadata is fetched per GraphQL request
+const METADATA_BATCH_SIZE: usize = 100;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SourcegraphRepository {
@@ -16,6 +23,35 @@ pub struct SourcegraphRepository {
     pub external_repository: ExternalRepository,
 }
 
+impl SourcegraphRepository {
+    /// Maestro provider and provider_id of the repository, if its code host type is supported
+    /// Repositories on the provider's configured instance drop the host ("owner/repo"); others
+    /// keep it ("ghe.example.com/owner/repo") so the host's credential profile applies
+    pub fn provider_repository(&self, tokens: &AllTokens) -> Option<(&'static str, String)> {
+        use crate::util::git::{
+            bitbucket_instance_url, gitea_instance_url, github_web_base_url, gitlab_instance_url,
+            instance_host,
+        };
+
+        let (provider, instance_url) = match self.external_repository.service_type.as_str() {
+            "github" => ("github", Some(github_web_base_url(tokens))),
+            "gitlab" => ("gitlab", Some(gitlab_instance_url(tokens))),
+            "gitea" => ("gitea", gitea_instance_url(tokens).ok()),
+            "bitbucketServer" => ("bitbucket", bitbucket_instance_url(tokens).ok()),
+            _ => return None,
+        };
+
+        let (host, path) = self.name.split_once('/')?;
+        let on_instance = instance_url
+            .map(|url| instance_host(&url))
+            .is_some_and(|instance| instance.eq_ignore_ascii_case(host));
+        Some((
+            provider,
+            if on_instance { path } else { &self.name }.to_string(),
+        ))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExternalRepository {
@@ -102,153 +138,373 @@ impl SourcegraphClient {
             .ok_or_else(|| anyhow::anyhow!("GraphQL response contained no data"))
     }
 
+    /// Search repositories matching a Sourcegraph query, following the streaming search API
+    /// until it is exhausted or `limit` (at most MAX_REPOSITORY_MATCHES) repositories are found
+    /// Queries matching files (`file:gradle-wrapper.properties`) are narrowed with `select:repo`
+    /// so each repository is reported once; `on_progress` gets the running count of repositories
     pub async fn search_repositories(
         &self,
         query: &str,
         limit: i32,
+        mut on_progress: impl FnMut(usize) + Send,
     ) -> Result<RepositorySearchResult> {
-        // Use the search() API which supports full Sourcegraph search syntax.
-        // For queries like "file:gradle-wrapper.properties", the API returns
-        // FileMatch results which we need to extract repositories from.
+        let limit = (limit.max(1) as usize).min(MAX_REPOSITORY_MATCHES);
+
+        let mut search_query = query.trim().to_string();
+        if !search_query.contains("select:") {
+            search_query.push_str(" select:repo");
+        }
+        if !search_query.contains("count:") {
+            search_query.push_str(" count:all");
+        }
+
+        let url = format!("{}/.api/search/stream", self.endpoint.trim_end_matches('/'));
+        let mut response = self
+            .http_client
+            .get(&url)
+            .query(&[
+                ("q", search_query.as_str()),
+                ("v", "V3"),
+                ("t", "standard"),
+                ("display", &limit.to_string()),
+            ])
+            .header("Authorization", format!("token {}", self.access_token))
+            .header("Accept", "text/event-stream")
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "Search request failed with status {}: {}",
+                status,
+                error_text
+            ));
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Match {
+            repository: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct Progress {
+            #[serde(default)]
+            skipped: Vec<Skipped>,
+        }
+
+        #[derive(Deserialize)]
+        struct Skipped {
+            reason: String,
+        }
+
+        let mut stream = EventStream::default();
+        let mut names: Vec<String> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut limit_hit = false;
+
+        'stream: while let Some(chunk) = response.chunk().await? {
+            for (event, data) in stream.feed(&chunk) {
+                match event.as_str() {
+                    "matches" => {
+                        let found = names.len();
+                        for name in serde_json::from_str::<Vec<Match>>(&data)?
+                            .into_iter()
+                            .filter_map(|m| m.repository)
+                        {
+                            if seen.contains(&name) {
+                                continue;
+                            }
+                            if names.len() == limit {
+                                limit_hit = true;
+                                break;
+                            }
+                            seen.insert(name.clone());
+                            names.push(name);
+                        }
+                        if names.len() > found {
+                            on_progress(names.len());
+                        }
+                        if limit_hit {
+                            // Dropping the response ends the search on the server
+                            break 'stream;
+                        }
+                    }
+                    "progress" => {
+                        // Matches skipped for hitting a search limit or timeout leave the
+                        // result incomplete
+                        let progress: Progress = serde_json::from_str(&data)?;
+                        if progress.skipped.iter().any(|skipped| {
+                            skipped.reason.contains("limit") || skipped.reason.contains("timedout")
+                        }) {
+                            limit_hit = true;
+                        }
+                    }
+                    "alert" => log::warn!("[sourcegraph] Search alert for '{}': {}", query, data),
+                    "error" => {
+                        return Err(anyhow::anyhow!("Search failed for '{}': {}", query, data));
+                    }
+                    "done" => break 'stream,
+                    _ => {}
+                }
+            }
+        }
+
+        if limit_hit {
+            log::warn!(
+                "[sourcegraph] Search for '{}' stopped at {} repositories",
+                query,
+                names.len()
+            );
+        }
+
+        let repositories = self.repository_metadata(&names).await?;
+        Ok(RepositorySearchResult {
+            total_count: repositories.len() as i32,
+            repositories,
+            has_next_page: limit_hit,
+        })
+    }
+
+    /// Metadata of repositories by name, in the order given; unknown names are left out
+    async fn repository_metadata(&self, names: &[String]) -> Result<Vec<SourcegraphRepository>> {
         let graphql_query = r#"
-			query SearchRepositories($query: String!) {
-				search(query: $query, version: V3, patternType: standard) {
-					results {
-						results {
-							__typename
-							... on FileMatch {
-								repository {
-									id
-									name
-									description
-									url
-									language
-									stars
-									isPrivate
-									isFork
-									isArchived
-									externalRepository {
-										serviceType
-										serviceID
-									}
-								}
-							}
-							... on Repository {
-								id
-								name
-								description
-								url
-								language
-								stars
-								isPrivate
-								isFork
-								isArchived
-								externalRepository {
-									serviceType
-									serviceID
-								}
-							}
+			query RepositoryMetadata($names: [String!], $first: Int!) {
+				repositories(names: $names, first: $first) {
+					nodes {
+						id
+						name
+						description
+						url
+						language
+						stars
+						isPrivate
+						isFork
+						isArchived
+						externalRepository {
+							serviceType
+							serviceID
 						}
-						matchCount
-						limitHit
 					}
 				}
 			}
 		"#;
 
-        // Add count:N to limit results
-        let search_query = format!("{} count:{}", query, limit);
-
-        let variables = serde_json::json!({
-            "query": search_query,
-        });
-
         #[derive(Deserialize)]
-        #[serde(rename_all = "camelCase")]
         struct Response {
-            search: SearchResults,
+            repositories: Nodes,
         }
 
         #[derive(Deserialize)]
-        #[serde(rename_all = "camelCase")]
-        struct SearchResults {
-            results: SearchResultsData,
+        struct Nodes {
+            nodes: Vec<SourcegraphRepository>,
         }
 
-        #[derive(Deserialize)]
-        #[serde(rename_all = "camelCase")]
-        struct SearchResultsData {
-            results: Vec<SearchResult>,
-            match_count: i32,
-            limit_hit: bool,
+        let mut by_name = std::collections::HashMap::new();
+        for batch in names.chunks(METADATA_BATCH_SIZE) {
+            let variables = serde_json::json!({
+                "names": batch,
+                "first": batch.len(),
+            });
+            let response: Response = self.query(graphql_query, variables).await?;
+            by_name.extend(
+                response
+                    .repositories
+                    .nodes
+                    .into_iter()
+                    .map(|repository| (repository.name.clone(), repository)),
+            );
         }
 
-        #[derive(Deserialize)]
-        #[serde(tag = "__typename")]
-        enum SearchResult {
-            #[serde(rename_all = "camelCase")]
-            FileMatch { repository: SourcegraphRepository },
-            #[serde(rename_all = "camelCase")]
-            Repository {
-                id: String,
-                name: String,
-                description: Option<String>,
-                url: String,
-                language: Option<String>,
-                stars: i32,
-                is_private: bool,
-                is_fork: bool,
-                is_archived: bool,
-                external_repository: ExternalRepository,
-            },
-            #[serde(other)]
-            Other,
-        }
-
-        let response: Response = self.query(graphql_query, variables).await?;
-
-        // Extract repositories from polymorphic results and deduplicate by name
-        let mut seen_repos = std::collections::HashSet::new();
-        let mut repositories = Vec::new();
-
-        for result in response.search.results.results {
-            let repo = match result {
-                SearchResult::FileMatch { repository } => repository,
-                SearchResult::Repository {
-                    id,
-                    name,
-                    description,
-                    url,
-                    language,
-                    stars,
-                    is_private,
-                    is_fork,
-                    is_archived,
-                    external_repository,
-                } => SourcegraphRepository {
-                    id,
-                    name,
-                    description,
-                    url,
-                    language,
-                    stars,
-                    is_private,
-                    is_fork,
-                    is_archived,
-                    external_repository,
-                },
-                SearchResult::Other => continue,
-            };
+        Ok(names
+            .iter()
+            .filter_map(|name| by_name.remove(name))
+            .collect())
+    }
+}
+
+/// Incremental parser for a text/event-stream response, yielding (event, data) pairs
+#[derive(Default)]
+struct EventStream {
+    buffer: Vec<u8>,
+    event: String,
+    data: String,
+}
+
+impl EventStream {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<(String, String)> {
+        self.buffer.extend_from_slice(chunk);
 
-            if seen_repos.insert(repo.name.clone()) {
-                repositories.push(repo);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    let event = std::mem::take(&mut self.event);
+                    events.push((
+                        if event.is_empty() {
+                            "message".to_string()
+                        } else {
+                            event
+                        },
+                        std::mem::take(&mut self.data),
+                    ));
+                }
+                self.event.clear();
+            } else if let Some(event) = line.strip_prefix("event:") {
+                self.event = event.trim().to_string();
+            } else if let Some(data) = line.strip_prefix("data:") {
+                if !self.data.is_empty() {
+                    self.data.push('\n');
+                }
+                self.data.push_str(data.strip_prefix(' ').unwrap_or(data));
             }
         }
+        events
+    }
+}
 
-        Ok(RepositorySearchResult {
-            repositories,
-            total_count: response.search.results.match_count,
-            has_next_page: response.search.results.limit_hit,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{serve, StubRequest, StubResponse};
+    use std::sync::mpsc::Receiver;
+
+    fn repository(name: &str, service_type: &str) -> SourcegraphRepository {
+        SourcegraphRepository {
+            id: "UmVwb3NpdG9yeTox".to_string(),
+            name: name.to_string(),
+            description: None,
+            url: format!("/{}", name),
+            language: None,
+            stars: 0,
+            is_private: false,
+            is_fork: false,
+            is_archived: false,
+            external_repository: ExternalRepository {
+                service_type: service_type.to_string(),
+                service_id: String::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_provider_repository() {
+        let tokens = AllTokens::default();
+        assert_eq!(
+            repository("github.com/acme/api", "github").provider_repository(&tokens),
+            Some(("github", "acme/api".to_string()))
+        );
+        assert_eq!(
+            repository("gitlab.com/group/subgroup/project", "gitlab").provider_repository(&tokens),
+            Some(("gitlab", "group/subgroup/project".to_string()))
+        );
+        // Other instances keep their host
+        assert_eq!(
+            repository("ghe.example.com/acme/api", "github").provider_repository(&tokens),
+            Some(("github", "ghe.example.com/acme/api".to_string()))
+        );
+        assert_eq!(
+            repository("svn.example.com/trunk", "other").provider_repository(&tokens),
+            None
+        );
+    }
+
+    #[test]
+    fn test_event_stream_split_chunks() {
+        let mut stream = EventStream::default();
+        assert!(stream.feed(b"event: matches\ndata: [{\"repo").is_empty());
+        assert_eq!(
+            stream.feed(b"sitory\":\"a\"}]\n\nevent: done\ndata: {}\n\n"),
+            vec![
+                (
+                    "matches".to_string(),
+                    "[{\"repository\":\"a\"}]".to_string()
+                ),
+                ("done".to_string(), "{}".to_string()),
+            ]
+        );
+    }
+
+    /// Stub Sourcegraph serving a stream of repository matches (with duplicates) and their
+    /// metadata, recording the search query it was sent
+    fn stub_sourcegraph(names: &'static [&'static str]) -> (String, Receiver<StubRequest>) {
+        serve(move |request| {
+            let body = if request.url.starts_with("/.api/search/stream") {
+                let matches: Vec<String> = names
+                    .iter()
+                    .map(|name| format!("{{\"type\":\"repo\",\"repository\":\"{}\"}}", name))
+                    .collect();
+                format!(
+                    "event: matches\ndata: [{}]\n\nevent: matches\ndata: [{}]\n\nevent: done\ndata: {{}}\n\n",
+                    matches.join(","),
+                    matches[0]
+                )
+            } else {
+                let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+                let nodes: Vec<SourcegraphRepository> = body["variables"]["names"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|name| repository(name.as_str().unwrap(), "github"))
+                    .collect();
+                serde_json::json!({ "data": { "repositories": { "nodes": nodes } } }).to_string()
+            };
+            StubResponse::ok(body)
         })
     }
+
+    #[tokio::test]
+    async fn test_search_repositories_streams_and_dedupes() {
+        let (endpoint, queries) = stub_sourcegraph(&[
+            "github.com/acme/a",
+            "github.com/acme/b",
+            "github.com/acme/c",
+        ]);
+        let client = SourcegraphClient::new(endpoint, "token".to_string()).unwrap();
+
+        let mut progress = Vec::new();
+        let result = client
+            .search_repositories("file:gradle-wrapper.properties", 50, |found| {
+                progress.push(found)
+            })
+            .await
+            .unwrap();
+        let names: Vec<&str> = result
+            .repositories
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "github.com/acme/a",
+                "github.com/acme/b",
+                "github.com/acme/c"
+            ]
+        );
+        assert!(!result.has_next_page);
+        assert_eq!(progress, vec![3]);
+
+        let url = queries
+            .try_iter()
+            .map(|request| request.url)
+            .find(|url| url.starts_with("/.api/search/stream"))
+            .unwrap();
+        assert!(url.contains("select%3Arepo"), "{}", url);
+        assert!(url.contains("count%3Aall"), "{}", url);
+
+        // The cap stops the search and flags the result as incomplete
+        let result = client
+            .search_repositories("file:gradle-wrapper.properties", 2, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(result.repositories.len(), 2);
+        assert!(result.has_next_page);
+    }
 }
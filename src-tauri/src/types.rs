@@ -9,6 +9,9 @@ pub enum ExecutionStatus {
     Completed,
     Failed,
     Cancelled,
+    /// Never ran: skipped before starting (not applicable, archived, no push access or no longer
+    /// matched by the promptset's saved query); see skip_reason
+    Skipped,
 }
 
 impl FromSql for ExecutionStatus {
@@ -19,6 +22,7 @@ impl FromSql for ExecutionStatus {
             "completed" => Ok(ExecutionStatus::Completed),
             "failed" => Ok(ExecutionStatus::Failed),
             "cancelled" => Ok(ExecutionStatus::Cancelled),
+            "skipped" => Ok(ExecutionStatus::Skipped),
             other => Err(FromSqlError::Other(
                 format!("Invalid ExecutionStatus: {}", other).into(),
             )),
@@ -34,6 +38,7 @@ impl ToSql for ExecutionStatus {
             ExecutionStatus::Completed => "completed",
             ExecutionStatus::Failed => "failed",
             ExecutionStatus::Cancelled => "cancelled",
+            ExecutionStatus::Skipped => "skipped",
         };
         Ok(ToSqlOutput::from(s))
     }
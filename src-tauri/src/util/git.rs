@@ -1,94 +1,567 @@
+use crate::commands::tokens::{AllTokens, CredentialProfile};
 use anyhow::{bail, Result};
 
-/// Parse provider_id in format "github.com/owner/repo", "gitlab.com/owner/repo", or "owner/repo"
-/// Returns (owner, repo) tuple
-pub fn parse_provider_id(provider_id: &str) -> Result<(String, String)> {
-    let trimmed = provider_id
-        .trim_start_matches("github.com/")
-        .trim_start_matches("gitlab.com/");
-    let parts: Vec<&str> = trimmed.split('/').collect();
+/// Parse provider_id in format "owner/repo" or "host/owner/repo", where the owner may be a
+/// nested namespace ("gitlab.example.com/group/subgroup/project")
+/// URL-encoded slashes, as in GitLab project IDs ("group%2Fsubgroup%2Fproject"), are decoded
+/// Plain git repositories use a clone URL or local path instead (see `parse_clone_url`)
+/// Returns (owner, repo) tuple; the owner keeps the slashes between namespace levels
+pub fn parse_provider_id(tokens: &AllTokens, provider_id: &str) -> Result<(String, String)> {
+    if is_clone_url(provider_id) {
+        return parse_clone_url(provider_id);
+    }
+
+    let decoded = decode_path_separators(provider_id);
+    let mut parts: Vec<&str> = decoded.split('/').collect();
 
-    if parts.len() != 2 {
-        bail!("Invalid provider_id format. Expected 'owner/repo', 'github.com/owner/repo', or 'gitlab.com/owner/repo', got '{}'", provider_id);
+    if provider_id_host(tokens, provider_id).is_some() {
+        parts.remove(0);
     }
 
-    if parts[0].is_empty() || parts[1].is_empty() {
+    if parts.len() < 2 {
+        bail!(
+            "Invalid provider_id format. Expected 'owner/repo' or 'host/owner/repo', got '{}'",
+            provider_id
+        );
+    }
+
+    // Segments become admin clone directories, so they must be real path components
+    if parts
+        .iter()
+        .any(|part| part.is_empty() || *part == "." || *part == "..")
+    {
         bail!("Invalid provider_id: owner and repo cannot be empty");
     }
 
-    Ok((parts[0].to_string(), parts[1].to_string()))
+    let repo = parts.pop().unwrap_or_default();
+    Ok((parts.join("/"), repo.to_string()))
+}
+
+/// Whether a provider_id is a clone URL or absolute local path rather than a hosted repository path
+/// Recognizes "scheme://...", scp-like "user@host:path" and absolute paths
+pub fn is_clone_url(provider_id: &str) -> bool {
+    let scp_like = provider_id
+        .split_once(':')
+        .is_some_and(|(user_host, _)| user_host.contains('@') && !user_host.contains('/'));
+    provider_id.contains("://") || provider_id.starts_with('/') || scp_like
+}
+
+/// Split a clone URL or local path into (owner, repo) for admin clone paths and display
+/// The owner is the host followed by the parent directories ("git.example.com/team"), or
+/// "local" for file URLs and paths; the repo drops any ".git" suffix
+/// A port stays with the host as "git.example.com_2222", since another port may serve other
+/// repositories and ':' can't appear in Windows directory names
+fn parse_clone_url(url: &str) -> Result<(String, String)> {
+    let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        (authority.rsplit('@').next().unwrap_or(authority), path)
+    } else if url.starts_with('/') {
+        ("", url)
+    } else {
+        let (user_host, path) = url.split_once(':').unwrap_or(("", url));
+        (user_host.rsplit('@').next().unwrap_or(user_host), path)
+    };
+    let host = host.replace(':', "_");
+
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if segments.iter().any(|s| *s == "." || *s == "..") {
+        bail!("Invalid clone URL: relative path segments in '{}'", url);
+    }
+
+    let repo = segments
+        .pop()
+        .map(|name| name.trim_end_matches(".git"))
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Invalid clone URL: no repository in '{}'", url))?;
+
+    if host.is_empty() && segments.is_empty() {
+        bail!("Invalid clone URL: no parent directory in '{}'", url);
+    }
+
+    let host = if host.is_empty() { "local" } else { &host };
+    let owner = std::iter::once(host)
+        .chain(segments)
+        .collect::<Vec<_>>()
+        .join("/");
+    Ok((owner, repo.to_string()))
+}
+
+/// Owner and repo of a repository's admin clone, which also key its repository lock
+/// The owner leads with the provider_id's host, if it names one, so the same owner/repo on two
+/// hosts never shares a clone ("ghe.example.com/acme", "api"); a port is kept as "host_port",
+/// as for clone URLs
+pub fn clone_owner_repo(tokens: &AllTokens, provider_id: &str) -> Result<(String, String)> {
+    let (owner, repo) = parse_provider_id(tokens, provider_id)?;
+    Ok(match provider_id_host(tokens, provider_id) {
+        Some(host) => (
+            format!("{}/{}", host.to_lowercase().replace(':', "_"), owner),
+            repo,
+        ),
+        None => (owner, repo),
+    })
+}
+
+/// Decode URL-encoded slashes ("%2F") in a provider_id
+pub fn decode_path_separators(provider_id: &str) -> String {
+    provider_id.replace("%2F", "/").replace("%2f", "/")
+}
+
+/// Leading host of a provider_id such as "ghe.example.com/owner/repo", if it has one
+/// Only known hosts count, followed by at least an owner and a repo, so a GitLab group with a
+/// '.' in its name ("my.team/sub/repo") stays part of the path
+pub fn provider_id_host<'a>(tokens: &AllTokens, provider_id: &'a str) -> Option<&'a str> {
+    if is_clone_url(provider_id) {
+        return None;
+    }
+    let host = provider_id.split('/').next()?;
+    let segments = decode_path_separators(provider_id).split('/').count();
+    (segments >= 3 && is_known_host(tokens, host)).then_some(host)
+}
+
+/// Whether a host is github.com, gitlab.com, a configured provider instance or the host of a
+/// credential profile
+fn is_known_host(tokens: &AllTokens, host: &str) -> bool {
+    let instance_urls = [
+        tokens.github_instance_url.as_ref(),
+        tokens.gitlab_instance_url.as_ref(),
+        tokens.gitea_instance_url.as_ref(),
+        tokens.bitbucket_url.as_ref(),
+    ];
+    ["github.com", "gitlab.com"]
+        .into_iter()
+        .map(str::to_string)
+        .chain(
+            instance_urls
+                .into_iter()
+                .flatten()
+                .map(|url| instance_host(url)),
+        )
+        .chain(
+            tokens
+                .credential_profiles
+                .iter()
+                .map(|profile| profile.host.clone()),
+        )
+        .any(|known| known.eq_ignore_ascii_case(host))
+}
+
+/// Check that `host` is a bare host name (and port) of a configured instance or credential
+/// profile, as it's written into provider_ids ahead of owner/repo
+pub fn require_known_host(tokens: &AllTokens, host: &str) -> Result<()> {
+    let bare = !host.is_empty()
+        && !host.contains("://")
+        && !host.contains(['/', '@'])
+        && !host.contains(char::is_whitespace);
+    if !bare || !is_known_host(tokens, host) {
+        bail!(
+            "Unknown host '{}': expected the host name of a configured instance or credential profile, such as ghe.example.com",
+            host
+        );
+    }
+    Ok(())
+}
+
+/// provider_id for a repository the provider now reports as `full_name` ("owner/repo") after a
+/// rename or transfer, keeping any host prefix; None when the path is unchanged
+/// Paths compare case-insensitively, as providers resolve them; clone URLs are never renamed
+pub fn renamed_provider_id(
+    tokens: &AllTokens,
+    provider_id: &str,
+    full_name: &str,
+) -> Option<String> {
+    if is_clone_url(provider_id) {
+        return None;
+    }
+    let full_name = full_name.trim_matches('/');
+    let (owner, repo) = parse_provider_id(tokens, provider_id).ok()?;
+    if !full_name.contains('/') || format!("{}/{}", owner, repo).eq_ignore_ascii_case(full_name) {
+        return None;
+    }
+    Some(match provider_id_host(tokens, provider_id) {
+        Some(host) => format!("{}/{}", host, full_name),
+        None => full_name.to_string(),
+    })
+}
+
+/// Reject nested namespaces on providers that address repositories as exactly owner/repo
+fn require_flat_owner(provider: &str, owner: &str) -> Result<()> {
+    if matches!(provider, "github" | "gitea" | "bitbucket") && owner.contains('/') {
+        bail!(
+            "Nested namespaces are only supported for GitLab repositories, got '{}' for {}",
+            owner,
+            provider
+        );
+    }
+    Ok(())
+}
+
+/// Credential profile configured for the host in a repository's provider_id
+pub fn credential_profile<'a>(
+    tokens: &'a AllTokens,
+    provider: &str,
+    provider_id: &str,
+) -> Option<&'a CredentialProfile> {
+    host_credential_profile(tokens, provider, provider_id_host(tokens, provider_id))
+}
+
+/// Credential profile configured for a host, if a host is given
+pub fn host_credential_profile<'a>(
+    tokens: &'a AllTokens,
+    provider: &str,
+    host: Option<&str>,
+) -> Option<&'a CredentialProfile> {
+    tokens.credential_profile(provider, host?)
+}
+
+/// Access token for a repository: its host's credential profile, else a GitHub App
+/// installation token when an app is configured, else the provider's token
+pub async fn resolve_provider_token(
+    tokens: &AllTokens,
+    provider: &str,
+    provider_id: &str,
+) -> Result<String> {
+    if let Some(profile) = credential_profile(tokens, provider, provider_id) {
+        return Ok(profile.token.clone());
+    }
+
+    if let Some(app) = github_app(tokens, provider)? {
+        let (owner, repo) = parse_provider_id(tokens, provider_id)?;
+        return app.installation_token(&owner, Some(&repo)).await;
+    }
+
+    provider_token(tokens, provider)
+}
+
+/// Access token for an organization, user or group on a host, resolved like a repository's
+/// (a GitHub App uses its installation on the account)
+pub async fn resolve_namespace_token(
+    tokens: &AllTokens,
+    provider: &str,
+    host: Option<&str>,
+    namespace: &str,
+) -> Result<String> {
+    if let Some(profile) = host_credential_profile(tokens, provider, host) {
+        return Ok(profile.token.clone());
+    }
+
+    if let Some(app) = github_app(tokens, provider)? {
+        return app.installation_token(namespace, None).await;
+    }
+
+    provider_token(tokens, provider)
+}
+
+/// The configured GitHub App, for GitHub repositories
+fn github_app(
+    tokens: &AllTokens,
+    provider: &str,
+) -> Result<Option<crate::git::github_app::GitHubApp>> {
+    use crate::git::github_app::{GitHubApp, GitHubAppCredentials};
+
+    if provider != "github" {
+        return Ok(None);
+    }
+    GitHubAppCredentials::from_tokens(tokens)
+        .map(|credentials| GitHubApp::new(credentials, github_api_base_url(tokens)))
+        .transpose()
+}
+
+/// The provider's own token from Settings
+fn provider_token(tokens: &AllTokens, provider: &str) -> Result<String> {
+    let token_key = match provider {
+        "github" => "github_token",
+        "gitlab" => "gitlab_token",
+        "gitea" => "gitea_token",
+        "bitbucket" => "bitbucket_token",
+        _ => bail!("Unsupported provider: {}", provider),
+    };
+
+    tokens
+        .value(token_key)
+        .map_err(|e| anyhow::anyhow!("Failed to access {} token: {}", provider, e))?
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} token not configured. Please set it in Settings.",
+                provider
+            )
+        })
+}
+
+/// SSH clone URL of a repository: the host of its web base URL, on its credential profile's
+/// SSH port if one is set
+pub fn provider_ssh_url(
+    tokens: &AllTokens,
+    provider: &str,
+    provider_id: &str,
+    owner: &str,
+    repo: &str,
+) -> Result<String> {
+    let web_base_url = provider_web_base_url(tokens, provider, provider_id)?;
+    let ssh_port =
+        credential_profile(tokens, provider, provider_id).and_then(|profile| profile.ssh_port);
+    ssh_clone_url(
+        provider,
+        &instance_host(&web_base_url),
+        ssh_port,
+        owner,
+        repo,
+    )
+}
+
+/// SSH clone URL on `host`, whatever port its web UI uses
+/// Without a port, Bitbucket Server/Data Center is reached on 7999, its default, and the other
+/// providers on 22
+fn ssh_clone_url(
+    provider: &str,
+    host: &str,
+    ssh_port: Option<u16>,
+    owner: &str,
+    repo: &str,
+) -> Result<String> {
+    let host = host.split(':').next().unwrap_or(host);
+    match (provider, ssh_port) {
+        ("github" | "gitlab" | "gitea", None) => Ok(format!("git@{}:{}/{}.git", host, owner, repo)),
+        ("bitbucket", None) => Ok(format!("ssh://git@{}:7999/{}/{}.git", host, owner, repo)),
+        ("github" | "gitlab" | "gitea" | "bitbucket", Some(port)) => Ok(format!(
+            "ssh://git@{}:{}/{}/{}.git",
+            host, port, owner, repo
+        )),
+        _ => bail!("Unsupported provider: {}", provider),
+    }
+}
+
+/// Web base URL of a repository's host: its credential profile, else the provider's instance
+pub fn provider_web_base_url(
+    tokens: &AllTokens,
+    provider: &str,
+    provider_id: &str,
+) -> Result<String> {
+    host_web_base_url(tokens, provider, provider_id_host(tokens, provider_id))
+}
+
+/// API base URL of a repository's host
+/// Only GitHub serves its API elsewhere; the other providers add their API path to the web base URL
+pub fn provider_api_base_url(
+    tokens: &AllTokens,
+    provider: &str,
+    provider_id: &str,
+) -> Result<String> {
+    host_api_base_url(tokens, provider, provider_id_host(tokens, provider_id))
+}
+
+/// Web base URL of a host's credential profile, else of the provider's instance
+pub fn host_web_base_url(tokens: &AllTokens, provider: &str, host: Option<&str>) -> Result<String> {
+    if let Some(profile) = host_credential_profile(tokens, provider, host) {
+        return Ok(profile.base_url.trim_end_matches('/').to_string());
+    }
+
+    match provider {
+        "github" => Ok(github_web_base_url(tokens)),
+        "gitlab" => Ok(gitlab_instance_url(tokens)),
+        "gitea" => gitea_instance_url(tokens),
+        "bitbucket" => bitbucket_instance_url(tokens),
+        _ => bail!("Unsupported provider: {}", provider),
+    }
+}
+
+/// API base URL of a host's credential profile, else of the provider's instance
+pub fn host_api_base_url(tokens: &AllTokens, provider: &str, host: Option<&str>) -> Result<String> {
+    if let Some(profile) = host_credential_profile(tokens, provider, host) {
+        return Ok(profile_api_base_url(profile));
+    }
+
+    match provider {
+        "github" => Ok(github_api_base_url(tokens)),
+        _ => host_web_base_url(tokens, provider, host),
+    }
+}
+
+/// API base URL of a credential profile: its api_url, else derived from its web base URL
+pub fn profile_api_base_url(profile: &CredentialProfile) -> String {
+    let web_base_url = profile.base_url.trim_end_matches('/');
+    match (&profile.api_url, profile.provider.as_str()) {
+        (Some(api_url), _) => api_url.trim_end_matches('/').to_string(),
+        (None, "github") => default_github_api_url(web_base_url),
+        (None, _) => web_base_url.to_string(),
+    }
+}
+
+/// Host (with port, if any) of a self-hosted instance URL such as "https://gitea.example.com:3000"
+pub fn instance_host(instance_url: &str) -> String {
+    match reqwest::Url::parse(instance_url) {
+        Ok(parsed) if parsed.host_str().is_some() => {
+            let host = parsed.host_str().unwrap_or_default();
+            match parsed.port() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host.to_string(),
+            }
+        }
+        _ => instance_url
+            .trim_start_matches(|c: char| !c.is_alphanumeric() && c != '.')
+            .trim_end_matches('/')
+            .to_string(),
+    }
+}
+
+/// Web base URL of GitHub (github_instance_url token for GitHub Enterprise Server, else github.com)
+pub fn github_web_base_url(tokens: &AllTokens) -> String {
+    tokens
+        .github_instance_url
+        .as_ref()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| "https://github.com".to_string())
+}
+
+/// REST API base URL of GitHub (github_api_url token, else derived from the web base URL)
+pub fn github_api_base_url(tokens: &AllTokens) -> String {
+    tokens
+        .github_api_url
+        .as_ref()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| default_github_api_url(&github_web_base_url(tokens)))
+}
+
+/// github.com serves its API from api.github.com; Enterprise Server from {instance}/api/v3
+fn default_github_api_url(web_base_url: &str) -> String {
+    if instance_host(web_base_url) == "github.com" {
+        "https://api.github.com".to_string()
+    } else {
+        format!("{}/api/v3", web_base_url.trim_end_matches('/'))
+    }
+}
+
+/// Base URL of the configured GitLab instance (gitlab_instance_url token, else gitlab.com)
+pub fn gitlab_instance_url(tokens: &AllTokens) -> String {
+    tokens
+        .gitlab_instance_url
+        .as_ref()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| "https://gitlab.com".to_string())
+}
+
+/// Base URL of the configured Gitea/Forgejo instance (no default; these are always self-hosted)
+pub fn gitea_instance_url(tokens: &AllTokens) -> Result<String> {
+    required_instance_url(&tokens.gitea_instance_url, "Gitea")
+}
+
+/// Base URL of the configured Bitbucket Server/Data Center instance, including any context path
+pub fn bitbucket_instance_url(tokens: &AllTokens) -> Result<String> {
+    required_instance_url(&tokens.bitbucket_url, "Bitbucket")
+}
+
+fn required_instance_url(url: &Option<String>, name: &str) -> Result<String> {
+    let url = url
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("{} instance URL not configured", name))?;
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+/// Username for HTTPS git operations with a provider's access token
+/// Bitbucket checks the token against the account it belongs to; GitHub App installation
+/// tokens go with "x-access-token"; the others accept any username
+pub fn https_username(tokens: &AllTokens, provider: &str, provider_id: &str) -> Result<String> {
+    use crate::git::github_app::GitHubAppCredentials;
+
+    let profile = credential_profile(tokens, provider, provider_id);
+    if let Some(username) = profile.and_then(|profile| profile.username.clone()) {
+        return Ok(username);
+    }
+
+    match provider {
+        "github" if profile.is_none() && GitHubAppCredentials::from_tokens(tokens).is_some() => {
+            Ok("x-access-token".to_string())
+        }
+        "bitbucket" => tokens
+            .bitbucket_username
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Bitbucket username not configured")),
+        _ => Ok("oauth2".to_string()),
+    }
 }
 
 /// Git remote configuration for HTTPS authentication
 #[derive(Debug)]
 pub struct GitRemoteConfig {
     pub url: String,
-    pub username: &'static str,
+    pub username: String,
 }
 
-/// Build HTTPS git remote URL for a provider
-/// - GitHub: https://github.com/owner/repo.git
-/// - GitLab: https://{instance}/owner/repo.git (uses gitlab_instance_url token or defaults to gitlab.com)
-pub fn build_https_remote(provider: &str, owner: &str, repo: &str) -> Result<GitRemoteConfig> {
-    use crate::commands::tokens::get_token_value;
+/// Build HTTPS git remote URL for a repository, on its host's credential profile when it has one
+/// - GitHub: {instance}/owner/repo.git (uses github_instance_url token or defaults to github.com)
+/// - GitLab: {instance}/group/repo.git (uses gitlab_instance_url token or defaults to gitlab.com)
+/// - Gitea/Forgejo: {instance}/owner/repo.git (uses gitea_instance_url token)
+/// - Bitbucket Server/Data Center: {instance}/scm/project/repo.git (uses bitbucket_url token)
+pub fn build_https_remote(
+    tokens: &AllTokens,
+    provider: &str,
+    provider_id: &str,
+) -> Result<GitRemoteConfig> {
+    if !matches!(provider, "github" | "gitlab" | "gitea" | "bitbucket") {
+        bail!("Unsupported provider for HTTPS remote: {}", provider);
+    }
+
+    let (owner, repo) = parse_provider_id(tokens, provider_id)?;
+    require_flat_owner(provider, &owner)?;
+    let web_base_url = provider_web_base_url(tokens, provider, provider_id)?;
 
     let url = match provider {
-        "github" => format!("https://github.com/{}/{}.git", owner, repo),
-        "gitlab" => {
-            let instance_url = get_token_value("gitlab_instance_url")
-                .ok()
-                .flatten()
-                .unwrap_or_else(|| "https://gitlab.com".to_string());
-
-            let host = if let Ok(parsed) = reqwest::Url::parse(&instance_url) {
-                parsed.host_str().unwrap_or("gitlab.com").to_string()
-            } else {
-                instance_url
-                    .trim_start_matches(|c: char| !c.is_alphanumeric() && c != '.')
-                    .trim_end_matches('/')
-                    .to_string()
-            };
-
-            format!("https://{}/{}/{}.git", host, owner, repo)
-        }
-        _ => bail!("Unsupported provider for HTTPS remote: {}", provider),
+        "bitbucket" => format!("{}/scm/{}/{}.git", web_base_url, owner, repo),
+        _ => format!("{}/{}/{}.git", web_base_url, owner, repo),
     };
 
     Ok(GitRemoteConfig {
         url,
-        username: "oauth2",
+        username: https_username(tokens, provider, provider_id)?,
     })
 }
 
 /// Build provider_cfg JSON for CI/Git provider context
-/// - GitHub: {"owner": "...", "repo": "..."}
-/// - GitLab: {"project_id": "owner/repo", "slug": "owner/repo", "web_base_url": "..."}
-pub fn build_provider_cfg(provider: &str, provider_id: &str) -> Result<serde_json::Value> {
-    let (owner, repo) = parse_provider_id(provider_id)?;
+/// - GitHub: {"owner": "...", "repo": "...", "web_base_url": "..."}
+/// - GitLab: {"project_id": "group/subgroup/repo", "slug": "group/subgroup/repo", "web_base_url": "..."}
+/// - Gitea/Forgejo: {"owner": "...", "repo": "...", "web_base_url": "..."}
+/// - Bitbucket: {"project_key": "...", "repo_slug": "...", "web_base_url": "..."}
+/// - Plain git: {"url": "..."} (the provider_id is the clone URL)
+pub fn build_provider_cfg(
+    tokens: &AllTokens,
+    provider: &str,
+    provider_id: &str,
+) -> Result<serde_json::Value> {
+    if provider == "git" {
+        if !is_clone_url(provider_id) {
+            bail!(
+                "Plain git repositories need a clone URL or absolute path, got '{}'",
+                provider_id
+            );
+        }
+        return Ok(serde_json::json!({ "url": provider_id }));
+    }
+
+    let (owner, repo) = parse_provider_id(tokens, provider_id)?;
+    require_flat_owner(provider, &owner)?;
+    let web_base_url = provider_web_base_url(tokens, provider, provider_id)?;
 
     match provider {
-        "github" => Ok(serde_json::json!({
+        "github" | "gitea" => Ok(serde_json::json!({
             "owner": owner,
             "repo": repo,
+            "web_base_url": web_base_url,
         })),
         "gitlab" => {
-            use crate::commands::tokens::get_token_value;
-
-            // GitLab API accepts project_id as either numeric ID or "owner/repo" slug
+            // GitLab API accepts project_id as either numeric ID or the full path; the client
+            // URL-encodes the path, so it's passed with plain slashes
             let slug = format!("{}/{}", owner, repo);
 
-            // Get custom GitLab instance URL if configured
-            let web_base_url = get_token_value("gitlab_instance_url")
-                .ok()
-                .flatten()
-                .unwrap_or_else(|| "https://gitlab.com".to_string());
-
             Ok(serde_json::json!({
                 "project_id": slug.clone(),
                 "slug": slug,
                 "web_base_url": web_base_url,
             }))
         }
+        "bitbucket" => Ok(serde_json::json!({
+            "project_key": owner,
+            "repo_slug": repo,
+            "web_base_url": web_base_url,
+        })),
         _ => bail!("Unsupported provider: {}", provider),
     }
 }
@@ -109,26 +582,272 @@ pub fn maestro_branch_name(promptset_id: &str, revision_id: &str, execution_id:
 mod tests {
     use super::*;
 
+    /// Tokens for a self-hosted GitLab and Gitea, and a GitHub Enterprise Server profile
+    fn self_hosted() -> AllTokens {
+        AllTokens {
+            gitlab_instance_url: Some("https://gitlab.example.com".to_string()),
+            gitea_instance_url: Some("http://gitea.example.com:3000".to_string()),
+            credential_profiles: vec![CredentialProfile {
+                name: "ghe".to_string(),
+                provider: "github".to_string(),
+                host: "ghe.example.com".to_string(),
+                base_url: "https://ghe.example.com".to_string(),
+                token: "ghe-token".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_require_known_host() {
+        let tokens = self_hosted();
+        assert!(require_known_host(&tokens, "gitlab.example.com").is_ok());
+        assert!(require_known_host(&tokens, "GitLab.Example.com").is_ok());
+        assert!(require_known_host(&tokens, "gitea.example.com:3000").is_ok());
+        assert!(require_known_host(&tokens, "ghe.example.com").is_ok());
+        assert!(require_known_host(&tokens, "https://gitlab.example.com").is_err());
+        assert!(require_known_host(&tokens, "gitlab.example.com/acme").is_err());
+        assert!(require_known_host(&tokens, "ghe.unknown.com").is_err());
+    }
+
     #[test]
     fn test_parse_provider_id_valid() {
-        let (owner, repo) = parse_provider_id("owner/repo").unwrap();
+        let (owner, repo) = parse_provider_id(&self_hosted(), "owner/repo").unwrap();
         assert_eq!(owner, "owner");
         assert_eq!(repo, "repo");
     }
 
     #[test]
     fn test_parse_provider_id_with_github_prefix() {
-        let (owner, repo) = parse_provider_id("github.com/owner/repo").unwrap();
+        let (owner, repo) = parse_provider_id(&self_hosted(), "github.com/owner/repo").unwrap();
         assert_eq!(owner, "owner");
         assert_eq!(repo, "repo");
     }
 
+    #[test]
+    fn test_parse_provider_id_with_self_hosted_prefix() {
+        let (owner, repo) =
+            parse_provider_id(&self_hosted(), "gitea.example.com:3000/owner/repo").unwrap();
+        assert_eq!(owner, "owner");
+        assert_eq!(repo, "repo");
+    }
+
+    #[test]
+    fn test_provider_id_host() {
+        let tokens = self_hosted();
+        assert_eq!(
+            provider_id_host(&tokens, "ghe.example.com/owner/repo"),
+            Some("ghe.example.com")
+        );
+        assert_eq!(
+            provider_id_host(&tokens, "gitea.example.com:3000/owner/repo"),
+            Some("gitea.example.com:3000")
+        );
+        assert_eq!(provider_id_host(&tokens, "owner/repo"), None);
+        assert_eq!(provider_id_host(&tokens, "group/subgroup/project"), None);
+        assert_eq!(
+            provider_id_host(&tokens, "gitlab.com/group%2Fproject"),
+            Some("gitlab.com")
+        );
+
+        // Hosts nobody configured are the first level of the path, like a dotted GitLab group
+        assert_eq!(provider_id_host(&tokens, "my.team/sub/repo"), None);
+        assert_eq!(
+            provider_id_host(&tokens, "gitlab.internal:8443/owner/repo"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_renamed_provider_id() {
+        assert_eq!(
+            renamed_provider_id(&self_hosted(), "acme/widgets", "acme-corp/widgets"),
+            Some("acme-corp/widgets".to_string())
+        );
+        assert_eq!(
+            renamed_provider_id(
+                &self_hosted(),
+                "ghe.example.com/acme/widgets",
+                "acme/gadgets"
+            ),
+            Some("ghe.example.com/acme/gadgets".to_string())
+        );
+        assert_eq!(
+            renamed_provider_id(
+                &self_hosted(),
+                "gitlab.com/group%2Fproject",
+                "group/sub/project"
+            ),
+            Some("gitlab.com/group/sub/project".to_string())
+        );
+        assert_eq!(
+            renamed_provider_id(&self_hosted(), "acme/widgets", "Acme/Widgets"),
+            None
+        );
+        assert_eq!(
+            renamed_provider_id(&self_hosted(), "group%2Fproject", "group/project"),
+            None
+        );
+        assert_eq!(
+            renamed_provider_id(
+                &self_hosted(),
+                "https://git.example.com/team/repo.git",
+                "team/other"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_instance_host() {
+        assert_eq!(
+            instance_host("https://gitea.example.com/"),
+            "gitea.example.com"
+        );
+        assert_eq!(instance_host("http://localhost:3000"), "localhost:3000");
+        assert_eq!(instance_host("codeberg.org/"), "codeberg.org");
+    }
+
+    #[test]
+    fn test_default_github_api_url() {
+        assert_eq!(
+            default_github_api_url("https://github.com"),
+            "https://api.github.com"
+        );
+        assert_eq!(
+            default_github_api_url("https://ghe.example.com/"),
+            "https://ghe.example.com/api/v3"
+        );
+    }
+
+    #[test]
+    fn test_parse_provider_id_nested_namespace() {
+        let (owner, repo) = parse_provider_id(
+            &self_hosted(),
+            "gitlab.example.com/group/subgroup/team/project",
+        )
+        .unwrap();
+        assert_eq!(owner, "group/subgroup/team");
+        assert_eq!(repo, "project");
+
+        let (owner, repo) = parse_provider_id(&self_hosted(), "group/subgroup/project").unwrap();
+        assert_eq!(owner, "group/subgroup");
+        assert_eq!(repo, "project");
+    }
+
+    #[test]
+    fn test_parse_provider_id_dotted_group() {
+        let tokens = self_hosted();
+        let (owner, repo) = parse_provider_id(&tokens, "my.team/sub/repo").unwrap();
+        assert_eq!(owner, "my.team/sub");
+        assert_eq!(repo, "repo");
+
+        let (owner, repo) =
+            parse_provider_id(&tokens, "gitlab.example.com/my.team/sub/repo").unwrap();
+        assert_eq!(owner, "my.team/sub");
+        assert_eq!(repo, "repo");
+
+        // The group isn't taken for a host, so a rename keeps it in the path
+        assert_eq!(
+            renamed_provider_id(&tokens, "my.team/sub/repo", "my.team/sub/renamed"),
+            Some("my.team/sub/renamed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_provider_id_url_encoded() {
+        let (owner, repo) =
+            parse_provider_id(&self_hosted(), "group%2Fsubgroup%2Fproject").unwrap();
+        assert_eq!(owner, "group/subgroup");
+        assert_eq!(repo, "project");
+
+        let (owner, repo) =
+            parse_provider_id(&self_hosted(), "gitlab.example.com/group%2fproject").unwrap();
+        assert_eq!(owner, "group");
+        assert_eq!(repo, "project");
+    }
+
     #[test]
     fn test_parse_provider_id_invalid_format() {
-        assert!(parse_provider_id("invalid").is_err());
-        assert!(parse_provider_id("too/many/parts").is_err());
-        assert!(parse_provider_id("/repo").is_err());
-        assert!(parse_provider_id("owner/").is_err());
+        assert!(parse_provider_id(&self_hosted(), "invalid").is_err());
+        assert!(parse_provider_id(&self_hosted(), "/repo").is_err());
+        assert!(parse_provider_id(&self_hosted(), "owner/").is_err());
+        assert!(parse_provider_id(&self_hosted(), "group//project").is_err());
+        assert!(parse_provider_id(&self_hosted(), "group/../project").is_err());
+    }
+
+    #[test]
+    fn test_parse_clone_url() {
+        let cases = [
+            (
+                "https://git.example.com/team/widgets.git",
+                "git.example.com/team",
+                "widgets",
+            ),
+            (
+                "ssh://git@git.example.com:2222/widgets.git",
+                "git.example.com_2222",
+                "widgets",
+            ),
+            (
+                "ssh://git@git.example.com/widgets.git",
+                "git.example.com",
+                "widgets",
+            ),
+            (
+                "git@git.example.com:team/widgets.git",
+                "git.example.com/team",
+                "widgets",
+            ),
+            ("file:///srv/git/widgets.git", "local/srv/git", "widgets"),
+            ("/srv/git/widgets", "local/srv/git", "widgets"),
+        ];
+        for (url, owner, repo) in cases {
+            assert!(is_clone_url(url), "{}", url);
+            assert_eq!(
+                parse_provider_id(&self_hosted(), url).unwrap(),
+                (owner.to_string(), repo.to_string()),
+                "{}",
+                url
+            );
+        }
+
+        assert!(!is_clone_url("gitlab.example.com:8443/group/project"));
+        assert!(parse_provider_id(&self_hosted(), "file:///srv/git/../widgets.git").is_err());
+        assert!(parse_provider_id(&self_hosted(), "https://git.example.com/").is_err());
+
+        let cfg = build_provider_cfg(&AllTokens::default(), "git", "file:///srv/git/widgets.git")
+            .unwrap();
+        assert_eq!(cfg["url"], "file:///srv/git/widgets.git");
+        assert!(build_provider_cfg(&AllTokens::default(), "git", "team/widgets").is_err());
+    }
+
+    #[test]
+    fn test_build_provider_cfg_nested_gitlab() {
+        let cfg = build_provider_cfg(
+            &AllTokens::default(),
+            "gitlab",
+            "gitlab.com/group/subgroup/team/project",
+        )
+        .unwrap();
+        assert_eq!(cfg["project_id"], "group/subgroup/team/project");
+        assert_eq!(cfg["slug"], "group/subgroup/team/project");
+
+        let remote = build_https_remote(
+            &AllTokens::default(),
+            "gitlab",
+            "group%2Fsubgroup%2Fproject",
+        )
+        .unwrap();
+        assert_eq!(remote.url, "https://gitlab.com/group/subgroup/project.git");
+
+        assert!(build_provider_cfg(
+            &AllTokens::default(),
+            "github",
+            "github.com/group/subgroup/project"
+        )
+        .is_err());
     }
 
     #[test]
@@ -143,25 +862,166 @@ mod tests {
 
     #[test]
     fn test_build_https_remote_github() {
-        let remote = build_https_remote("github", "sourcegraph", "maestro").unwrap();
+        let remote =
+            build_https_remote(&AllTokens::default(), "github", "sourcegraph/maestro").unwrap();
         assert_eq!(remote.url, "https://github.com/sourcegraph/maestro.git");
         assert_eq!(remote.username, "oauth2");
     }
 
     #[test]
     fn test_build_https_remote_gitlab_default() {
-        let remote = build_https_remote("gitlab", "myorg", "myrepo").unwrap();
+        let remote =
+            build_https_remote(&AllTokens::default(), "gitlab", "gitlab.com/myorg/myrepo").unwrap();
         assert_eq!(remote.url, "https://gitlab.com/myorg/myrepo.git");
         assert_eq!(remote.username, "oauth2");
     }
 
+    #[test]
+    fn test_build_https_remote_gitlab_port() {
+        let tokens = AllTokens {
+            gitlab_instance_url: Some("http://gitlab.internal:8443/".to_string()),
+            ..Default::default()
+        };
+        let remote = build_https_remote(&tokens, "gitlab", "group%2Fproject").unwrap();
+        assert_eq!(remote.url, "http://gitlab.internal:8443/group/project.git");
+
+        let tokens = AllTokens {
+            credential_profiles: vec![CredentialProfile {
+                name: "gitlab-internal".to_string(),
+                provider: "gitlab".to_string(),
+                host: "gitlab.example.com:8443".to_string(),
+                base_url: "https://gitlab.example.com:8443".to_string(),
+                token: "internal-token".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let remote = build_https_remote(
+            &tokens,
+            "gitlab",
+            "gitlab.example.com:8443/group/sub/project",
+        )
+        .unwrap();
+        assert_eq!(
+            remote.url,
+            "https://gitlab.example.com:8443/group/sub/project.git"
+        );
+    }
+
     #[test]
     fn test_build_https_remote_unsupported() {
-        let result = build_https_remote("bitbucket", "owner", "repo");
+        let result = build_https_remote(&AllTokens::default(), "sourcehut", "owner/repo");
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
             .to_string()
             .contains("Unsupported provider"));
     }
+
+    #[test]
+    fn test_ssh_clone_url() {
+        assert_eq!(
+            ssh_clone_url("github", "ghe.example.com:8443", None, "acme", "api").unwrap(),
+            "git@ghe.example.com:acme/api.git"
+        );
+        assert_eq!(
+            ssh_clone_url("bitbucket", "bitbucket.example.com", None, "WID", "widgets").unwrap(),
+            "ssh://git@bitbucket.example.com:7999/WID/widgets.git"
+        );
+        assert_eq!(
+            ssh_clone_url(
+                "bitbucket",
+                "bitbucket.example.com:7990",
+                Some(22),
+                "WID",
+                "widgets"
+            )
+            .unwrap(),
+            "ssh://git@bitbucket.example.com:22/WID/widgets.git"
+        );
+        assert_eq!(
+            ssh_clone_url("gitea", "gitea.example.com", Some(2222), "acme", "api").unwrap(),
+            "ssh://git@gitea.example.com:2222/acme/api.git"
+        );
+        assert!(ssh_clone_url("git", "example.com", None, "acme", "api").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_credential_profile_resolution() {
+        let tokens = AllTokens {
+            github_token: Some("github-token".to_string()),
+            gitlab_token: Some("gitlab-token".to_string()),
+            credential_profiles: vec![
+                CredentialProfile {
+                    name: "ghe".to_string(),
+                    provider: "github".to_string(),
+                    host: "ghe.example.com".to_string(),
+                    base_url: "https://ghe.example.com".to_string(),
+                    token: "ghe-token".to_string(),
+                    ..Default::default()
+                },
+                CredentialProfile {
+                    name: "gitlab-internal".to_string(),
+                    provider: "gitlab".to_string(),
+                    host: "gitlab.internal:8443".to_string(),
+                    base_url: "https://gitlab.internal:8443".to_string(),
+                    token: "internal-token".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        // Host-prefixed provider IDs pick the profile for their provider and host
+        let profile = credential_profile(&tokens, "github", "GHE.example.com/acme/api").unwrap();
+        assert_eq!(profile.name, "ghe");
+        assert_eq!(
+            credential_profile(&tokens, "gitlab", "gitlab.internal:8443/group/sub/api")
+                .unwrap()
+                .name,
+            "gitlab-internal"
+        );
+        assert!(credential_profile(&tokens, "gitlab", "ghe.example.com/acme/api").is_none());
+        assert!(credential_profile(&tokens, "github", "acme/api").is_none());
+        assert!(host_credential_profile(&tokens, "github", None).is_none());
+        assert!(host_credential_profile(&tokens, "github", Some("unknown.example.com")).is_none());
+
+        assert_eq!(
+            resolve_namespace_token(&tokens, "github", Some("ghe.example.com"), "acme")
+                .await
+                .unwrap(),
+            "ghe-token"
+        );
+        assert_eq!(
+            resolve_namespace_token(&tokens, "gitlab", Some("gitlab.internal:8443"), "group")
+                .await
+                .unwrap(),
+            "internal-token"
+        );
+
+        // Without a host, or on an unknown one, the provider's own token is used
+        assert_eq!(
+            resolve_namespace_token(&tokens, "github", None, "acme")
+                .await
+                .unwrap(),
+            "github-token"
+        );
+        assert_eq!(
+            resolve_namespace_token(&tokens, "gitlab", Some("gitlab.other.com"), "group")
+                .await
+                .unwrap(),
+            "gitlab-token"
+        );
+        assert!(
+            resolve_namespace_token(&tokens, "gitea", Some("gitea.example.com"), "acme")
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            resolve_provider_token(&tokens, "github", "unknown.example.com/acme/api")
+                .await
+                .unwrap(),
+            "github-token"
+        );
+    }
 }
@@ -1,2 +1,8 @@
 pub mod git;
 pub mod paths;
+pub mod rate_limit;
+pub mod tags;
+#[cfg(test)]
+pub(crate) mod test_git;
+#[cfg(test)]
+pub(crate) mod test_server;
@@ -1,10 +1,33 @@
+use crate::commands::tokens::AllTokens;
+use crate::util::git::clone_owner_repo;
 use crate::Paths;
+use anyhow::Result;
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 
-/// Construct path to admin repository clone
-/// Format: {admin_repo_dir}/{owner}/{repo}
-pub fn admin_repo_path(paths: &Paths, owner: &str, repo: &str) -> PathBuf {
-    paths.admin_repo_dir.join(owner).join(repo)
+/// Construct path to a repository's admin clone
+/// Format: {admin_repo_dir}/{host}/{owner}/{repo}, without {host} when the provider_id has none
+pub fn admin_repo_path(paths: &Paths, tokens: &AllTokens, provider_id: &str) -> Result<PathBuf> {
+    admin_clone_path(&paths.admin_repo_dir, tokens, provider_id)
+}
+
+/// Construct path to a repository's admin clone under a given admin repository directory
+pub fn admin_clone_path(
+    admin_repo_dir: &Path,
+    tokens: &AllTokens,
+    provider_id: &str,
+) -> Result<PathBuf> {
+    let (owner, repo) = clone_owner_repo(tokens, provider_id)?;
+    Ok(repo_path_in(admin_repo_dir, &owner, &repo))
+}
+
+/// Construct path to a repository under a directory, one level per namespace segment
+/// Format: {dir}/{group}/{subgroup}/.../{repo}
+pub fn repo_path_in(dir: &Path, owner: &str, repo: &str) -> PathBuf {
+    owner
+        .split('/')
+        .fold(dir.to_path_buf(), |path, segment| path.join(segment))
+        .join(repo)
 }
 
 /// Construct path to execution worktree
@@ -18,6 +41,29 @@ pub fn worktree_path(worktree_dir: &Path, promptset_id: &str, execution_id: &str
     worktree_dir.join(promptset_id).join(execution_id)
 }
 
+/// Construct path to a cached CI log
+/// Format: {ci_log_dir}/{execution_id}/{commit_sha}/{sanitized_context}-{context_hash}.log
+/// The hash (8 hex digits of the raw context's SHA-256) keeps contexts that sanitize alike apart
+pub fn ci_log_path(paths: &Paths, execution_id: &str, commit_sha: &str, context: &str) -> PathBuf {
+    let file_name: String = context
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let hash = hex::encode(Sha256::digest(context.as_bytes()));
+
+    paths
+        .ci_log_dir
+        .join(execution_id)
+        .join(commit_sha)
+        .join(format!("{}-{}.log", file_name, &hash[..8]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,10 +73,54 @@ mod tests {
         let paths = Paths {
             admin_repo_dir: PathBuf::from("/test/repos"),
             worktree_dir: PathBuf::from("/test/executions"),
+            ci_log_dir: PathBuf::from("/test/ci-logs"),
             db_path: PathBuf::from("/test/maestro.db"),
         };
-        let path = admin_repo_path(&paths, "owner", "repo");
+        let tokens = AllTokens::default();
+        let path = admin_repo_path(&paths, &tokens, "owner/repo").unwrap();
         assert_eq!(path, PathBuf::from("/test/repos/owner/repo"));
+
+        let path = admin_repo_path(&paths, &tokens, "group/subgroup/team/project").unwrap();
+        assert_eq!(
+            path,
+            PathBuf::from("/test/repos")
+                .join("group")
+                .join("subgroup")
+                .join("team")
+                .join("project")
+        );
+    }
+
+    #[test]
+    fn test_admin_repo_path_per_host() {
+        let paths = Paths {
+            admin_repo_dir: PathBuf::from("/test/repos"),
+            worktree_dir: PathBuf::from("/test/executions"),
+            ci_log_dir: PathBuf::from("/test/ci-logs"),
+            db_path: PathBuf::from("/test/maestro.db"),
+        };
+        let tokens = AllTokens {
+            github_instance_url: Some("https://ghe.example.com".to_string()),
+            gitlab_instance_url: Some("https://gitlab.internal:8443".to_string()),
+            ..Default::default()
+        };
+
+        // The same owner/repo on three hosts gets three clones
+        let clones = [
+            "acme/api",
+            "ghe.example.com/acme/api",
+            "gitlab.internal:8443/acme/api",
+        ]
+        .map(|provider_id| admin_repo_path(&paths, &tokens, provider_id).unwrap());
+        assert_eq!(clones[0], PathBuf::from("/test/repos/acme/api"));
+        assert_eq!(
+            clones[1],
+            PathBuf::from("/test/repos/ghe.example.com/acme/api")
+        );
+        assert_eq!(
+            clones[2],
+            PathBuf::from("/test/repos/gitlab.internal_8443/acme/api")
+        );
     }
 
     #[test]
@@ -38,6 +128,7 @@ mod tests {
         let paths = Paths {
             admin_repo_dir: PathBuf::from("/test/repos"),
             worktree_dir: PathBuf::from("/test/executions"),
+            ci_log_dir: PathBuf::from("/test/ci-logs"),
             db_path: PathBuf::from("/test/maestro.db"),
         };
         let path = execution_worktree_path(&paths, "promptset-123", "exec-456");
@@ -46,4 +137,23 @@ mod tests {
             PathBuf::from("/test/executions/promptset-123/exec-456")
         );
     }
+
+    #[test]
+    fn test_ci_log_path_sanitizes_context() {
+        let paths = Paths {
+            admin_repo_dir: PathBuf::from("/test/repos"),
+            worktree_dir: PathBuf::from("/test/executions"),
+            ci_log_dir: PathBuf::from("/test/ci-logs"),
+            db_path: PathBuf::from("/test/maestro.db"),
+        };
+        let path = ci_log_path(&paths, "exec-456", "abc123", "check:build / test");
+        assert_eq!(
+            path,
+            PathBuf::from("/test/ci-logs/exec-456/abc123/check_build___test-88ed36cf.log")
+        );
+
+        // Contexts that sanitize to the same name still get their own file
+        let other = ci_log_path(&paths, "exec-456", "abc123", "check:build_/_test");
+        assert_ne!(path, other);
+    }
 }
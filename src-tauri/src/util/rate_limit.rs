@@ -0,0 +1,371 @@
+use anyhow::Result;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+/// Share of a host's limit held back from background requests: once fewer requests remain,
+/// they wait for the reset so polling never spends the last of the user's budget
+const RESERVE_PERCENT: u64 = 5;
+
+/// Longest a request waits for its host's budget to recover before failing with `RateLimited`
+const MAX_WAIT_SECS: i64 = 30;
+
+/// Back-off after a rate-limited response that doesn't say how long to wait
+const DEFAULT_RETRY_SECS: i64 = 60;
+
+lazy_static::lazy_static! {
+    static ref BUDGETS: Mutex<HashMap<String, RateLimitBudget>> = Mutex::new(HashMap::new());
+}
+
+/// Whom a request is for: foreground requests may spend a host's reserve, background ones
+/// (the CI watcher, metadata sync) leave it for the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    Foreground,
+    Background,
+}
+
+tokio::task_local! {
+    static PRIORITY: Priority;
+}
+
+/// Run background work, sending its provider API requests with `Priority::Background`
+/// Requests go through provider clients shared with commands, so the priority follows the task
+pub async fn send_background<F: Future>(work: F) -> F::Output {
+    PRIORITY.scope(Priority::Background, work).await
+}
+
+/// Priority of requests sent from the current task; foreground outside `send_background`
+fn current_priority() -> Priority {
+    PRIORITY
+        .try_with(|priority| *priority)
+        .unwrap_or(Priority::Foreground)
+}
+
+/// Last known API budget of a provider host, shared by every client talking to it
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitBudget {
+    /// Host (with port, if any)
+    pub host: String,
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// When the current window resets (ms since epoch)
+    pub reset_at: Option<i64>,
+    /// Set after a rate-limited response: no requests before this time (ms since epoch)
+    pub retry_at: Option<i64>,
+    /// Until when background requests to the host are held back (ms since epoch), if they are
+    pub throttled_until: Option<i64>,
+    pub updated_at: i64,
+}
+
+impl RateLimitBudget {
+    fn blocked_until(&self, now: i64, priority: Priority) -> Option<i64> {
+        if let Some(retry_at) = self.retry_at.filter(|retry_at| *retry_at > now) {
+            return Some(retry_at);
+        }
+        let reserve = match priority {
+            Priority::Foreground => 0,
+            Priority::Background => RESERVE_PERCENT,
+        };
+        match (self.limit, self.remaining, self.reset_at) {
+            (Some(limit), Some(remaining), Some(reset_at))
+                if reset_at > now && remaining <= limit * reserve / 100 =>
+            {
+                Some(reset_at)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A host's budget is spent; the request was held back or rejected by the host
+#[derive(Debug, Clone)]
+pub struct RateLimited {
+    pub host: String,
+    /// Earliest time to try again (ms since epoch)
+    pub retry_at: i64,
+}
+
+impl std::fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let retry_at = chrono::DateTime::from_timestamp_millis(self.retry_at)
+            .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            .unwrap_or_default();
+        write!(
+            f,
+            "API rate limit reached for {}, retry after {}",
+            self.host, retry_at
+        )
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Rate limit behind an error, if the request failed for that reason
+pub fn as_rate_limited(error: &anyhow::Error) -> Option<&RateLimited> {
+    error.downcast_ref::<RateLimited>()
+}
+
+/// Budget key of a URL: its host, with the port when it has one
+pub fn host_key(url: &reqwest::Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+/// Send a provider API request within the budget of its host, at the current task's priority
+/// Waits when the budget recovers within MAX_WAIT_SECS, otherwise fails with `RateLimited`
+/// without sending; responses update the budget and fail with `RateLimited` when rejected
+pub async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    let host = host_key(request.url());
+
+    wait_for_budget(&host, current_priority()).await?;
+    let response = client.execute(request).await?;
+    record(&host, response.status(), response.headers())?;
+    Ok(response)
+}
+
+/// Wait until a host's budget allows another request, or fail if that takes too long
+pub async fn wait_for_budget(host: &str, priority: Priority) -> Result<(), RateLimited> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let blocked_until = BUDGETS
+        .lock()
+        .unwrap()
+        .get(host)
+        .and_then(|budget| budget.blocked_until(now, priority));
+
+    let Some(until) = blocked_until else {
+        return Ok(());
+    };
+    if until - now > MAX_WAIT_SECS * 1000 {
+        return Err(RateLimited {
+            host: host.to_string(),
+            retry_at: until,
+        });
+    }
+
+    log::info!(
+        "[rate_limit] Waiting {}ms for the {} API budget",
+        until - now,
+        host
+    );
+    tokio::time::sleep(std::time::Duration::from_millis((until - now) as u64)).await;
+    Ok(())
+}
+
+/// Update a host's budget from the rate-limit headers of a response
+/// Reads GitHub/Gitea `x-ratelimit-*`, GitLab `ratelimit-*` and `retry-after`; a 429, or a 403
+/// with the budget spent or a retry-after, fails with `RateLimited`
+pub fn record(host: &str, status: StatusCode, headers: &HeaderMap) -> Result<(), RateLimited> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let header = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| headers.get(*name)?.to_str().ok())
+            .map(|value| value.trim().to_string())
+    };
+    let number = |names: &[&str]| header(names).and_then(|value| value.parse::<i64>().ok());
+
+    let limit = number(&["x-ratelimit-limit", "ratelimit-limit"]);
+    let remaining = number(&["x-ratelimit-remaining", "ratelimit-remaining"]);
+    let reset_at = number(&["x-ratelimit-reset", "ratelimit-reset"]).map(|reset| {
+        // Epoch seconds (GitHub, GitLab), or seconds from now (IETF draft headers)
+        if reset > 1_000_000_000 {
+            reset * 1000
+        } else {
+            now + reset * 1000
+        }
+    });
+    let retry_after = header(&["retry-after"]).and_then(|value| match value.parse::<i64>() {
+        Ok(secs) => Some(now + secs * 1000),
+        Err(_) => chrono::DateTime::parse_from_rfc2822(&value)
+            .ok()
+            .map(|date| date.timestamp_millis()),
+    });
+
+    let rejected = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && (remaining == Some(0) || retry_after.is_some()));
+
+    let mut budgets = BUDGETS.lock().unwrap();
+    let budget = budgets
+        .entry(host.to_string())
+        .or_insert_with(|| RateLimitBudget {
+            host: host.to_string(),
+            ..Default::default()
+        });
+    budget.updated_at = now;
+    if limit.is_some() || remaining.is_some() {
+        budget.limit = limit.map(|limit| limit.max(0) as u64);
+        budget.remaining = remaining.map(|remaining| remaining.max(0) as u64);
+        budget.reset_at = reset_at;
+    }
+    budget.retry_at = if rejected {
+        Some(
+            retry_after
+                .or(reset_at.filter(|_| remaining == Some(0)))
+                .unwrap_or(now + DEFAULT_RETRY_SECS * 1000),
+        )
+    } else {
+        retry_after
+    };
+
+    match budget.retry_at {
+        Some(retry_at) if rejected => {
+            log::warn!(
+                "[rate_limit] {} rejected a request with {}, backing off",
+                host,
+                status
+            );
+            Err(RateLimited {
+                host: host.to_string(),
+                retry_at,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Current budget of every host seen so far
+pub fn budgets() -> Vec<RateLimitBudget> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut budgets: Vec<RateLimitBudget> = BUDGETS
+        .lock()
+        .unwrap()
+        .values()
+        .map(|budget| RateLimitBudget {
+            throttled_until: budget.blocked_until(now, Priority::Background),
+            ..budget.clone()
+        })
+        .collect();
+    budgets.sort_by(|a, b| a.host.cmp(&b.host));
+    budgets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{serve, StubResponse};
+    use reqwest::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&str, String)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                    HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn budget(host: &str) -> RateLimitBudget {
+        budgets().into_iter().find(|b| b.host == host).unwrap()
+    }
+
+    #[test]
+    fn test_record_headers() {
+        let reset = chrono::Utc::now().timestamp() + 600;
+
+        // GitHub: plenty left
+        record(
+            "github.test",
+            StatusCode::OK,
+            &headers(&[
+                ("x-ratelimit-limit", "5000".to_string()),
+                ("x-ratelimit-remaining", "4000".to_string()),
+                ("x-ratelimit-reset", reset.to_string()),
+            ]),
+        )
+        .unwrap();
+        let github = budget("github.test");
+        assert_eq!(github.remaining, Some(4000));
+        assert_eq!(github.reset_at, Some(reset * 1000));
+        assert_eq!(github.throttled_until, None);
+
+        // GitLab: inside the reserve, so requests hold off until the reset
+        record(
+            "gitlab.test",
+            StatusCode::OK,
+            &headers(&[
+                ("RateLimit-Limit", "2000".to_string()),
+                ("RateLimit-Remaining", "50".to_string()),
+                ("RateLimit-Reset", reset.to_string()),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(budget("gitlab.test").throttled_until, Some(reset * 1000));
+    }
+
+    #[test]
+    fn test_record_rejection() {
+        let err = record(
+            "secondary.test",
+            StatusCode::FORBIDDEN,
+            &headers(&[("retry-after", "120".to_string())]),
+        )
+        .unwrap_err();
+        let wait = err.retry_at - chrono::Utc::now().timestamp_millis();
+        assert!(wait > 110_000 && wait <= 120_000, "{}", wait);
+        assert!(budget("secondary.test").throttled_until.is_some());
+
+        // Other errors aren't rate limits
+        assert!(record("plain.test", StatusCode::FORBIDDEN, &HeaderMap::new()).is_ok());
+        assert!(record(
+            "busy.test",
+            StatusCode::TOO_MANY_REQUESTS,
+            &HeaderMap::new()
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_holds_back_spent_budget() {
+        let reset = (chrono::Utc::now().timestamp() + 3600).to_string();
+        let (base_url, requests) = serve(move |_| {
+            StubResponse::ok("{}")
+                .header("x-ratelimit-limit", "60")
+                .header("x-ratelimit-remaining", "0")
+                .header("x-ratelimit-reset", &reset)
+        });
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/", base_url);
+
+        // The last request of the window goes through; the next one is held back locally
+        assert!(send(client.get(&url)).await.is_ok());
+        let err = send(client.get(&url)).await.unwrap_err();
+        let limited = as_rate_limited(&err).unwrap();
+        assert_eq!(limited.host, base_url.trim_start_matches("http://"));
+        assert_eq!(requests.try_iter().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reserve_holds_back_background_requests() {
+        let reset = (chrono::Utc::now().timestamp() + 3600).to_string();
+        let (base_url, requests) = serve(move |_| {
+            StubResponse::ok("{}")
+                .header("x-ratelimit-limit", "100")
+                .header("x-ratelimit-remaining", "3")
+                .header("x-ratelimit-reset", &reset)
+        });
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/", base_url);
+
+        // Inside the reserve, the user's requests still go through but polling waits
+        assert!(send(client.get(&url)).await.is_ok());
+        assert!(send(client.get(&url)).await.is_ok());
+        let err = send_background(send(client.get(&url))).await.unwrap_err();
+        assert!(as_rate_limited(&err).is_some());
+        assert_eq!(requests.try_iter().count(), 2);
+    }
+}
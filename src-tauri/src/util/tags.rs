@@ -0,0 +1,197 @@
+use anyhow::Result;
+use std::collections::HashSet;
+
+/// Normalize a repository tag: trimmed and lowercase, e.g. "Team:Payments" -> "team:payments"
+/// Tags can't contain whitespace, parentheses, '!' or '*', which tag expressions reserve
+pub fn normalize_tag(tag: &str) -> Result<String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        anyhow::bail!("Tags can't be empty");
+    }
+    if let Some(c) = tag
+        .chars()
+        .find(|c| c.is_whitespace() || matches!(c, '(' | ')' | '!' | '*'))
+    {
+        anyhow::bail!("Tag '{}' contains '{}'", tag, c);
+    }
+    if matches!(tag.as_str(), "and" | "or" | "not") {
+        anyhow::bail!("'{}' is reserved in tag expressions", tag);
+    }
+    Ok(tag)
+}
+
+/// Boolean expression over repository tags, e.g. `team:payments and (tier:1 or tier:2) and not lang:php`
+/// Terms match tags exactly, or with '*' wildcards (`team:*`); adjacent terms are and-ed,
+/// and `!` is a shorthand for `not`. Keywords and tags are case-insensitive
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagExpression {
+    Tag(String),
+    Not(Box<TagExpression>),
+    And(Box<TagExpression>, Box<TagExpression>),
+    Or(Box<TagExpression>, Box<TagExpression>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Term(String),
+}
+
+fn tokenize(expression: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut term = String::new();
+    let flush = |term: &mut String, tokens: &mut Vec<Token>| {
+        if term.is_empty() {
+            return;
+        }
+        tokens.push(match term.to_lowercase().as_str() {
+            "and" | "&&" => Token::And,
+            "or" | "||" => Token::Or,
+            "not" => Token::Not,
+            lower => Token::Term(lower.to_string()),
+        });
+        term.clear();
+    };
+
+    for c in expression.chars() {
+        match c {
+            '(' | ')' | '!' => {
+                flush(&mut term, &mut tokens);
+                tokens.push(match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    _ => Token::Not,
+                });
+            }
+            c if c.is_whitespace() => flush(&mut term, &mut tokens),
+            c => term.push(c),
+        }
+    }
+    flush(&mut term, &mut tokens);
+    tokens
+}
+
+struct Parser {
+    tokens: std::iter::Peekable<std::vec::IntoIter<Token>>,
+}
+
+impl Parser {
+    fn or(&mut self) -> Result<TagExpression> {
+        let mut expression = self.and()?;
+        while self.tokens.next_if_eq(&Token::Or).is_some() {
+            expression = TagExpression::Or(Box::new(expression), Box::new(self.and()?));
+        }
+        Ok(expression)
+    }
+
+    fn and(&mut self) -> Result<TagExpression> {
+        let mut expression = self.not()?;
+        loop {
+            if self.tokens.next_if_eq(&Token::And).is_none()
+                && !matches!(
+                    self.tokens.peek(),
+                    Some(Token::Term(_) | Token::Not | Token::Open)
+                )
+            {
+                return Ok(expression);
+            }
+            expression = TagExpression::And(Box::new(expression), Box::new(self.not()?));
+        }
+    }
+
+    fn not(&mut self) -> Result<TagExpression> {
+        match self.tokens.next() {
+            Some(Token::Not) => Ok(TagExpression::Not(Box::new(self.not()?))),
+            Some(Token::Open) => {
+                let expression = self.or()?;
+                if self.tokens.next() != Some(Token::Close) {
+                    anyhow::bail!("Missing ')' in tag expression");
+                }
+                Ok(expression)
+            }
+            Some(Token::Term(term)) => Ok(TagExpression::Tag(term)),
+            Some(Token::Close) => anyhow::bail!("Unexpected ')' in tag expression"),
+            Some(Token::And | Token::Or) | None => {
+                anyhow::bail!("Expected a tag in tag expression")
+            }
+        }
+    }
+}
+
+impl TagExpression {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(expression).into_iter().peekable(),
+        };
+        if parser.tokens.peek().is_none() {
+            anyhow::bail!("Tag expression is empty");
+        }
+        let parsed = parser.or()?;
+        if parser.tokens.next().is_some() {
+            anyhow::bail!("Unexpected ')' in tag expression");
+        }
+        parsed.validate()?;
+        Ok(parsed)
+    }
+
+    fn validate(&self) -> Result<()> {
+        match self {
+            TagExpression::Tag(term) => glob::Pattern::new(term)
+                .map(|_| ())
+                .map_err(|e| anyhow::anyhow!("Invalid tag pattern '{}': {}", term, e)),
+            TagExpression::Not(inner) => inner.validate(),
+            TagExpression::And(left, right) | TagExpression::Or(left, right) => {
+                left.validate()?;
+                right.validate()
+            }
+        }
+    }
+
+    /// Whether a repository with these (normalized) tags matches
+    pub fn matches(&self, tags: &HashSet<String>) -> bool {
+        match self {
+            TagExpression::Tag(term) if term.contains('*') => glob::Pattern::new(term)
+                .map(|pattern| tags.iter().any(|tag| pattern.matches(tag)))
+                .unwrap_or(false),
+            TagExpression::Tag(term) => tags.contains(term),
+            TagExpression::Not(inner) => !inner.matches(tags),
+            TagExpression::And(left, right) => left.matches(tags) && right.matches(tags),
+            TagExpression::Or(left, right) => left.matches(tags) || right.matches(tags),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_expression() {
+        let tags: HashSet<String> = ["team:payments", "tier:1", "lang:go"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let matches = |expression: &str| TagExpression::parse(expression).unwrap().matches(&tags);
+
+        assert!(matches("team:payments"));
+        assert!(matches("Team:Payments AND (tier:1 OR tier:2)"));
+        assert!(matches("team:payments lang:go"));
+        assert!(matches("tier:2 or lang:go and team:*"));
+        assert!(matches("!lang:php"));
+        assert!(!matches("not lang:go"));
+        assert!(!matches("team:search or (tier:1 and lang:java)"));
+
+        assert!(TagExpression::parse("").is_err());
+        assert!(TagExpression::parse("(tier:1 or tier:2").is_err());
+        assert!(TagExpression::parse("tier:1)").is_err());
+        assert!(TagExpression::parse("tier:1 and").is_err());
+
+        assert_eq!(normalize_tag(" Tier:1 ").unwrap(), "tier:1");
+        assert!(normalize_tag("team payments").is_err());
+        assert!(normalize_tag("or").is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Run git in `dir` with a fixed identity, failing the test if it fails; returns trimmed stdout
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args([
+            "-c",
+            "user.name=Maestro",
+            "-c",
+            "user.email=maestro@example.com",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {}: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver};
+
+/// A request received by a stub server
+#[derive(Debug, Clone)]
+pub struct StubRequest {
+    pub method: String,
+    /// Path and query, e.g. `/api/v4/projects?page=2`
+    pub url: String,
+    pub body: String,
+    headers: Vec<(String, String)>,
+}
+
+impl StubRequest {
+    /// The URL without its query
+    pub fn path(&self) -> &str {
+        self.url.split('?').next().unwrap_or_default()
+    }
+
+    /// Value of a header, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A canned response of a stub server
+pub struct StubResponse {
+    status: u16,
+    body: String,
+    headers: Vec<(String, String)>,
+}
+
+impl StubResponse {
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::status(200, body)
+    }
+
+    pub fn status(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn not_found() -> Self {
+        Self::status(404, "not found")
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Serve HTTP on a local port for the rest of the test, answering each request with `handler`
+/// Returns the base URL, without a trailing slash, and every request served, in order; a
+/// request is logged before it is answered, so it can be read once its response arrives
+pub fn serve<F>(handler: F) -> (String, Receiver<StubRequest>)
+where
+    F: Fn(&StubRequest) -> StubResponse + Send + 'static,
+{
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let port = server.server_addr().to_ip().unwrap().port();
+    let (tx, rx) = channel();
+
+    std::thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let mut body = String::new();
+            let _ = request.as_reader().read_to_string(&mut body);
+            let received = StubRequest {
+                method: request.method().as_str().to_string(),
+                url: request.url().to_string(),
+                body,
+                headers: request
+                    .headers()
+                    .iter()
+                    .map(|h| (h.field.as_str().to_string(), h.value.as_str().to_string()))
+                    .collect(),
+            };
+
+            let stub = handler(&received);
+            let _ = tx.send(received);
+
+            let mut response =
+                tiny_http::Response::from_string(stub.body).with_status_code(stub.status);
+            for (name, value) in &stub.headers {
+                response = response.with_header(
+                    tiny_http::Header::from_bytes(name.as_str(), value.as_str()).unwrap(),
+                );
+            }
+            let _ = request.respond(response);
+        }
+    });
+
+    (format!("http://127.0.0.1:{}", port), rx)
+}
+
+/// Serve canned bodies by path, whatever the query; anything else is a 404
+pub fn serve_routes(
+    routes: HashMap<&'static str, &'static str>,
+) -> (String, Receiver<StubRequest>) {
+    serve(move |request| match routes.get(request.path()) {
+        Some(body) => StubResponse::ok(*body),
+        None => StubResponse::not_found(),
+    })
+}
@@ -0,0 +1,218 @@
+use super::WebhookEvent;
+use crate::ci::{map_check_conclusion, map_commit_status_state, CiCheck};
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+
+/// Verify the `X-Hub-Signature-256` header against the configured webhook secret
+pub fn verify_signature(secret: &str, body: &[u8], signature: Option<&str>) -> Result<()> {
+    let signature = signature.context("Missing X-Hub-Signature-256 header")?;
+    let hex_digest = signature
+        .strip_prefix("sha256=")
+        .context("Unsupported signature format, expected sha256=<hex>")?;
+    let expected = hex::decode(hex_digest).context("Signature is not valid hex")?;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| anyhow::anyhow!("Invalid webhook secret: {}", e))?;
+    mac.update(body);
+    mac.verify_slice(&expected)
+        .map_err(|_| anyhow::anyhow!("Signature mismatch"))
+}
+
+#[derive(Deserialize)]
+struct Repository {
+    full_name: String,
+}
+
+#[derive(Deserialize)]
+struct CheckRunPayload {
+    check_run: CheckRun,
+    repository: Option<Repository>,
+}
+
+#[derive(Deserialize)]
+struct CheckRun {
+    id: u64,
+    name: String,
+    head_sha: String,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StatusPayload {
+    id: Option<u64>,
+    sha: String,
+    context: String,
+    state: String,
+    description: Option<String>,
+    target_url: Option<String>,
+    repository: Option<Repository>,
+}
+
+#[derive(Deserialize)]
+struct PullRequestPayload {
+    action: String,
+    pull_request: PullRequest,
+    repository: Option<Repository>,
+}
+
+#[derive(Deserialize)]
+struct PullRequest {
+    head: PullRequestHead,
+}
+
+#[derive(Deserialize)]
+struct PullRequestHead {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    sha: String,
+}
+
+/// Parse a GitHub webhook by its `X-GitHub-Event` type
+/// Returns None for event types maestro doesn't track (e.g. `ping`)
+pub fn parse_event(event_type: &str, body: &[u8]) -> Result<Option<WebhookEvent>> {
+    match event_type {
+        "check_run" => {
+            let payload: CheckRunPayload =
+                serde_json::from_slice(body).context("Invalid check_run payload")?;
+            let run = payload.check_run;
+
+            // Same context/external_id scheme as GitHubCiProvider::get_check_runs
+            Ok(Some(WebhookEvent::Check {
+                repo_path: payload.repository.map(|r| r.full_name),
+                commit_sha: run.head_sha,
+                check: CiCheck {
+                    provider: "github".to_string(),
+                    context: format!("check:{}", run.name),
+                    state: map_check_conclusion(run.conclusion.as_deref()),
+                    name: run.name,
+                    target_url: run.html_url,
+                    description: None,
+                    external_id: Some(run.id.to_string()),
+                    raw_json: None,
+                },
+            }))
+        }
+        "status" => {
+            let payload: StatusPayload =
+                serde_json::from_slice(body).context("Invalid status payload")?;
+
+            // Same context/external_id scheme as GitHubCiProvider::get_commit_statuses
+            Ok(Some(WebhookEvent::Check {
+                repo_path: payload.repository.map(|r| r.full_name),
+                commit_sha: payload.sha,
+                check: CiCheck {
+                    provider: "github".to_string(),
+                    context: format!("status:{}", payload.context),
+                    name: payload.context,
+                    state: map_commit_status_state(&payload.state),
+                    target_url: payload.target_url,
+                    description: payload.description,
+                    external_id: payload.id.map(|id| id.to_string()),
+                    raw_json: None,
+                },
+            }))
+        }
+        "pull_request" => {
+            let payload: PullRequestPayload =
+                serde_json::from_slice(body).context("Invalid pull_request payload")?;
+
+            Ok(Some(WebhookEvent::ChangeRequest {
+                repo_path: payload.repository.map(|r| r.full_name),
+                branch: payload.pull_request.head.ref_name,
+                commit_sha: payload.pull_request.head.sha,
+                action: payload.action,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CiStatus;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let body = br#"{"zen":"Keep it logically awesome."}"#;
+        let signature = sign("s3cret", body);
+
+        assert!(verify_signature("s3cret", body, Some(&signature)).is_ok());
+        assert!(verify_signature("other", body, Some(&signature)).is_err());
+        assert!(verify_signature("s3cret", b"tampered", Some(&signature)).is_err());
+        assert!(verify_signature("s3cret", body, None).is_err());
+        assert!(verify_signature("s3cret", body, Some("sha1=abc")).is_err());
+    }
+
+    #[test]
+    fn test_parse_check_run() {
+        let body = br#"{
+            "action": "completed",
+            "check_run": {
+                "id": 42,
+                "name": "lint",
+                "head_sha": "abc123",
+                "status": "completed",
+                "conclusion": "failure",
+                "html_url": "https://github.com/acme/widgets/runs/42"
+            },
+            "repository": { "full_name": "acme/widgets" }
+        }"#;
+
+        match parse_event("check_run", body).unwrap() {
+            Some(WebhookEvent::Check {
+                repo_path,
+                commit_sha,
+                check,
+            }) => {
+                assert_eq!(repo_path.as_deref(), Some("acme/widgets"));
+                assert_eq!(commit_sha, "abc123");
+                assert_eq!(check.context, "check:lint");
+                assert_eq!(check.state, CiStatus::Failed);
+                assert_eq!(check.external_id.as_deref(), Some("42"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_status_and_pull_request() {
+        let body = br#"{"id": 7, "sha": "abc123", "context": "ci/jenkins", "state": "pending"}"#;
+        match parse_event("status", body).unwrap() {
+            Some(WebhookEvent::Check { check, .. }) => {
+                assert_eq!(check.context, "status:ci/jenkins");
+                assert_eq!(check.state, CiStatus::Pending);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let body = br#"{
+            "action": "synchronize",
+            "pull_request": { "head": { "ref": "maestro/abc", "sha": "def456" } }
+        }"#;
+        match parse_event("pull_request", body).unwrap() {
+            Some(WebhookEvent::ChangeRequest {
+                branch,
+                commit_sha,
+                action,
+                ..
+            }) => {
+                assert_eq!(branch, "maestro/abc");
+                assert_eq!(commit_sha, "def456");
+                assert_eq!(action, "synchronize");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        assert!(parse_event("ping", b"{}").unwrap().is_none());
+    }
+}
@@ -0,0 +1,177 @@
+use super::WebhookEvent;
+use crate::ci::{map_gitlab_status, CiCheck};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Verify the `X-Gitlab-Token` header against the configured secret token
+pub fn verify_token(secret: &str, token: Option<&str>) -> Result<()> {
+    let token = token.context("Missing X-Gitlab-Token header")?;
+
+    // Constant-time comparison so the secret can't be probed byte by byte
+    let matches = token.len() == secret.len()
+        && token
+            .bytes()
+            .zip(secret.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0;
+
+    if matches {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Token mismatch"))
+    }
+}
+
+#[derive(Deserialize)]
+struct Project {
+    path_with_namespace: String,
+    web_url: String,
+}
+
+#[derive(Deserialize)]
+struct PipelinePayload {
+    object_attributes: PipelineAttributes,
+    project: Project,
+}
+
+#[derive(Deserialize)]
+struct PipelineAttributes {
+    id: u64,
+    sha: String,
+    status: String,
+    #[serde(rename = "ref")]
+    ref_name: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MergeRequestPayload {
+    object_attributes: MergeRequestAttributes,
+    project: Project,
+}
+
+#[derive(Deserialize)]
+struct MergeRequestAttributes {
+    source_branch: String,
+    action: Option<String>,
+    last_commit: LastCommit,
+}
+
+#[derive(Deserialize)]
+struct LastCommit {
+    id: String,
+}
+
+/// Parse a GitLab webhook by its `X-Gitlab-Event` type
+/// Returns None for event types maestro doesn't track
+pub fn parse_event(event_type: &str, body: &[u8]) -> Result<Option<WebhookEvent>> {
+    match event_type {
+        "Pipeline Hook" => {
+            let payload: PipelinePayload =
+                serde_json::from_slice(body).context("Invalid pipeline payload")?;
+            let pipeline = payload.object_attributes;
+            let target_url = pipeline.url.clone().unwrap_or_else(|| {
+                format!(
+                    "{}/-/pipelines/{}",
+                    payload.project.web_url.trim_end_matches('/'),
+                    pipeline.id
+                )
+            });
+
+            // Same context/external_id scheme as GitLabCiProvider::get_pipeline_status
+            Ok(Some(WebhookEvent::Check {
+                repo_path: Some(payload.project.path_with_namespace),
+                commit_sha: pipeline.sha,
+                check: CiCheck {
+                    provider: "gitlab".to_string(),
+                    context: format!("gitlab:pipeline:{}", pipeline.id),
+                    name: format!("Pipeline #{}", pipeline.id),
+                    state: map_gitlab_status(&pipeline.status),
+                    target_url: Some(target_url),
+                    description: pipeline.ref_name,
+                    external_id: Some(pipeline.id.to_string()),
+                    raw_json: None,
+                },
+            }))
+        }
+        "Merge Request Hook" => {
+            let payload: MergeRequestPayload =
+                serde_json::from_slice(body).context("Invalid merge_request payload")?;
+            let mr = payload.object_attributes;
+
+            Ok(Some(WebhookEvent::ChangeRequest {
+                repo_path: Some(payload.project.path_with_namespace),
+                branch: mr.source_branch,
+                commit_sha: mr.last_commit.id,
+                action: mr.action.unwrap_or_default(),
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CiStatus;
+
+    #[test]
+    fn test_verify_token() {
+        assert!(verify_token("s3cret", Some("s3cret")).is_ok());
+        assert!(verify_token("s3cret", Some("s3cres")).is_err());
+        assert!(verify_token("s3cret", Some("s3cret-longer")).is_err());
+        assert!(verify_token("s3cret", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_pipeline() {
+        let body = br#"{
+            "object_kind": "pipeline",
+            "object_attributes": { "id": 99, "ref": "maestro/abc", "sha": "abc123", "status": "success" },
+            "project": { "path_with_namespace": "acme/widgets", "web_url": "https://gitlab.com/acme/widgets" }
+        }"#;
+
+        match parse_event("Pipeline Hook", body).unwrap() {
+            Some(WebhookEvent::Check {
+                repo_path,
+                commit_sha,
+                check,
+            }) => {
+                assert_eq!(repo_path.as_deref(), Some("acme/widgets"));
+                assert_eq!(commit_sha, "abc123");
+                assert_eq!(check.context, "gitlab:pipeline:99");
+                assert_eq!(check.state, CiStatus::Passed);
+                assert_eq!(
+                    check.target_url.as_deref(),
+                    Some("https://gitlab.com/acme/widgets/-/pipelines/99")
+                );
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        assert!(parse_event("Push Hook", b"{}").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_merge_request() {
+        let body = br#"{
+            "object_kind": "merge_request",
+            "object_attributes": { "source_branch": "maestro/abc", "action": "update", "last_commit": { "id": "def456" } },
+            "project": { "path_with_namespace": "acme/widgets", "web_url": "https://gitlab.com/acme/widgets" }
+        }"#;
+
+        match parse_event("Merge Request Hook", body).unwrap() {
+            Some(WebhookEvent::ChangeRequest {
+                branch,
+                commit_sha,
+                action,
+                ..
+            }) => {
+                assert_eq!(branch, "maestro/abc");
+                assert_eq!(commit_sha, "def456");
+                assert_eq!(action, "update");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::Result;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::WebhookEvent;
+use crate::ci::{check_ci_once, record_checks, watch_execution, CiCheck, CiContext};
+use crate::commands::executor_events;
+use crate::commands::tokens::{cached_tokens, AllTokens};
+use crate::db::store::{CiCheckRecord, Execution, ExecutionUpdates, Repository, Store};
+use crate::types::CiStatus;
+
+/// Apply a verified webhook event to the executions it refers to
+/// Returns the ids of the executions that were updated
+pub async fn apply_event(app: &AppHandle, event: WebhookEvent) -> Result<Vec<String>> {
+    let tokens = cached_tokens().map_err(anyhow::Error::msg)?;
+    match event {
+        WebhookEvent::Check {
+            repo_path,
+            commit_sha,
+            check,
+        } => {
+            let targets = {
+                let store = app.state::<Mutex<Store>>();
+                let store = store.lock().unwrap();
+                matching_executions(
+                    &tokens,
+                    &store,
+                    store.find_executions_by_commit_sha(&commit_sha)?,
+                    repo_path.as_deref(),
+                )?
+            };
+
+            let mut updated = Vec::new();
+            for (execution, repository) in targets {
+                // Record the reported check right away, then reconcile the full picture
+                record_checks(
+                    app,
+                    &execution.id,
+                    &commit_sha,
+                    std::slice::from_ref(&check),
+                );
+                refresh_execution_ci(app, &execution, &repository, &commit_sha).await?;
+                updated.push(execution.id);
+            }
+            Ok(updated)
+        }
+        WebhookEvent::ChangeRequest {
+            repo_path,
+            branch,
+            commit_sha,
+            action,
+        } => {
+            // Only opened/updated change requests imply a fresh CI run for the head commit
+            if !matches!(
+                action.as_str(),
+                "opened" | "reopened" | "synchronize" | "open" | "reopen" | "update"
+            ) {
+                return Ok(Vec::new());
+            }
+
+            let store = app.state::<Mutex<Store>>();
+            let store = store.lock().unwrap();
+            let targets = matching_executions(
+                &tokens,
+                &store,
+                store.find_executions_by_branch(&branch)?,
+                repo_path.as_deref(),
+            )?;
+
+            let mut updated = Vec::new();
+            for (execution, _) in targets {
+                let awaiting_ci = matches!(
+                    execution.ci_status,
+                    None | Some(CiStatus::NotConfigured) | Some(CiStatus::NotPushed)
+                );
+                if execution.commit_sha.as_deref() != Some(commit_sha.as_str()) || !awaiting_ci {
+                    continue;
+                }
+
+                store.update_execution(
+                    &execution.id,
+                    ExecutionUpdates {
+                        ci_status: Some(CiStatus::Pending),
+                        ci_checked_at: Some(chrono::Utc::now().timestamp_millis()),
+                        ..Default::default()
+                    },
+                )?;
+                watch_execution(&store, &execution.id, &commit_sha)?;
+                executor_events::emit_execution_ci(
+                    app,
+                    &execution.id,
+                    "pending",
+                    execution.ci_url.as_deref(),
+                );
+                updated.push(execution.id);
+            }
+            Ok(updated)
+        }
+    }
+}
+
+/// Pair executions with their repositories, keeping only those in the payload's repository
+fn matching_executions(
+    tokens: &AllTokens,
+    store: &Store,
+    executions: Vec<Execution>,
+    repo_path: Option<&str>,
+) -> Result<Vec<(Execution, Repository)>> {
+    let mut matched = Vec::new();
+    for execution in executions {
+        let Some(repository) = store.get_repository(&execution.repository_id)? else {
+            continue;
+        };
+        if repo_matches(tokens, &repository, repo_path) {
+            matched.push((execution, repository));
+        }
+    }
+    Ok(matched)
+}
+
+fn repo_matches(tokens: &AllTokens, repository: &Repository, repo_path: Option<&str>) -> bool {
+    let Some(repo_path) = repo_path else {
+        return true;
+    };
+    match crate::util::git::parse_provider_id(tokens, &repository.provider_id) {
+        Ok((owner, repo)) => format!("{}/{}", owner, repo).eq_ignore_ascii_case(repo_path),
+        Err(_) => false,
+    }
+}
+
+/// Recompute an execution's overall CI status after a webhook
+/// Polls the provider once for the full set of checks; if that fails, aggregates the
+/// checks recorded so far
+async fn refresh_execution_ci(
+    app: &AppHandle,
+    execution: &Execution,
+    repository: &Repository,
+    commit_sha: &str,
+) -> Result<()> {
+    let store = app.state::<Mutex<Store>>();
+    let provider = crate::ci::provider::create_repository_ci_provider(&store, repository).await;
+
+    let (status, ci_url) = match provider {
+        Ok((provider, provider_cfg)) => {
+            let ctx = CiContext {
+                commit_sha: commit_sha.to_string(),
+                branch: execution
+                    .branch
+                    .clone()
+                    .unwrap_or_else(|| "main".to_string()),
+                provider_cfg,
+            };
+
+            let policy = crate::commands::ci::resolve_check_policy(
+                &store,
+                provider.as_ref(),
+                &ctx,
+                &execution.promptset_id,
+                repository,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+            match check_ci_once(provider.clone(), ctx.clone(), &policy).await {
+                Ok((status, ci_url, checks)) => {
+                    record_checks(app, &execution.id, commit_sha, &checks);
+                    (status, ci_url)
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Failed to poll CI after webhook for execution {}: {}",
+                        execution.id,
+                        e
+                    );
+                    let checks = recorded_checks(app, &execution.id, commit_sha)?;
+                    (
+                        policy.aggregate(&checks),
+                        provider.get_commit_url(&ctx).ok(),
+                    )
+                }
+            }
+        }
+        Err(e) => {
+            log::warn!(
+                "Failed to create CI provider for webhook on execution {}: {}",
+                execution.id,
+                e
+            );
+            let policy = {
+                let store = app.state::<Mutex<Store>>();
+                let store = store.lock().unwrap();
+                store.resolve_ci_check_policy(&execution.promptset_id, &repository.id)?
+            };
+            let checks = recorded_checks(app, &execution.id, commit_sha)?;
+            (policy.aggregate(&checks), execution.ci_url.clone())
+        }
+    };
+
+    let Some(status) = status else {
+        return Ok(());
+    };
+
+    {
+        let store = app.state::<Mutex<Store>>();
+        let store = store.lock().unwrap();
+        store.update_execution(
+            &execution.id,
+            ExecutionUpdates {
+                ci_status: Some(status),
+                ci_checked_at: Some(chrono::Utc::now().timestamp_millis()),
+                ci_url: ci_url.clone(),
+                ..Default::default()
+            },
+        )?;
+        if status == CiStatus::Pending {
+            watch_execution(&store, &execution.id, commit_sha)?;
+        }
+    }
+
+    // Emit event (serialize status properly using serde)
+    let status_str = serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| format!("{:?}", status).to_lowercase());
+
+    executor_events::emit_execution_ci(app, &execution.id, &status_str, ci_url.as_deref());
+
+    Ok(())
+}
+
+fn recorded_checks(app: &AppHandle, execution_id: &str, commit_sha: &str) -> Result<Vec<CiCheck>> {
+    let store = app.state::<Mutex<Store>>();
+    let store = store.lock().unwrap();
+    Ok(store
+        .get_ci_checks(execution_id)?
+        .into_iter()
+        .filter(|c| c.commit_sha == commit_sha)
+        .map(to_ci_check)
+        .collect())
+}
+
+fn to_ci_check(record: CiCheckRecord) -> CiCheck {
+    CiCheck {
+        provider: record.provider,
+        context: record.context,
+        name: record.name,
+        state: record.state,
+        target_url: record.target_url,
+        description: record.description,
+        external_id: record.external_id,
+        raw_json: record
+            .raw_json
+            .and_then(|raw| serde_json::from_str(&raw).ok()),
+    }
+}
@@ -0,0 +1,31 @@
+mod github;
+mod gitlab;
+mod handler;
+mod server;
+
+pub use server::{
+    start_webhook_server, stop_webhook_server, webhook_server_status, WebhookServerStatus,
+};
+
+use crate::ci::CiCheck;
+
+/// Default port for the local webhook listener
+pub const DEFAULT_WEBHOOK_PORT: u16 = 8787;
+
+/// A verified webhook payload reduced to what maestro tracks
+#[derive(Debug, Clone)]
+pub enum WebhookEvent {
+    /// A CI check (check run, commit status or pipeline) changed state for a commit
+    Check {
+        repo_path: Option<String>,
+        commit_sha: String,
+        check: CiCheck,
+    },
+    /// A pull request or merge request was opened or updated for a branch
+    ChangeRequest {
+        repo_path: Option<String>,
+        branch: String,
+        commit_sha: String,
+        action: String,
+    },
+}
@@ -0,0 +1,366 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use super::{github, gitlab, handler, WebhookEvent};
+use crate::commands::tokens::get_token_value;
+
+/// Largest webhook payload accepted (GitHub caps deliveries at 25 MB; CI events are far smaller)
+const MAX_BODY_BYTES: u64 = 5 * 1024 * 1024;
+
+lazy_static::lazy_static! {
+    static ref WEBHOOK_SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+}
+
+/// Where the listener gets its secrets and sends the events it verified
+trait WebhookTarget {
+    /// A configured, non-empty webhook secret (`github_webhook_secret`, `gitlab_webhook_token`)
+    fn secret(&self, key: &str) -> Option<String>;
+
+    /// Apply a verified event, returning the ids of the executions it updated
+    fn apply(&self, event: WebhookEvent) -> Result<Vec<String>>;
+}
+
+impl WebhookTarget for AppHandle {
+    fn secret(&self, key: &str) -> Option<String> {
+        get_token_value(key)
+            .ok()
+            .flatten()
+            .filter(|secret| !secret.is_empty())
+    }
+
+    fn apply(&self, event: WebhookEvent) -> Result<Vec<String>> {
+        tauri::async_runtime::block_on(handler::apply_event(self, event))
+    }
+}
+
+struct RunningServer {
+    server: Arc<Server>,
+    port: u16,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub github_url: Option<String>,
+    pub gitlab_url: Option<String>,
+}
+
+/// Start the local webhook listener on 127.0.0.1:{port}
+/// Restarts the listener if it is already running on a different port
+pub fn start_webhook_server(app: AppHandle, port: u16) -> Result<()> {
+    let mut guard = WEBHOOK_SERVER.lock().unwrap();
+
+    if let Some(running) = guard.as_ref() {
+        if running.port == port {
+            return Ok(());
+        }
+        running.server.unblock();
+        *guard = None;
+    }
+
+    let server = Server::http(("127.0.0.1", port)).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to start webhook listener on 127.0.0.1:{}: {}",
+            port,
+            e
+        )
+    })?;
+    let server = Arc::new(server);
+
+    let worker = server.clone();
+    std::thread::Builder::new()
+        .name("webhook-listener".to_string())
+        .spawn(move || {
+            listen(&worker, &app);
+            log::info!("Webhook listener on port {} stopped", port);
+        })?;
+
+    log::info!("Webhook listener started on 127.0.0.1:{}", port);
+    *guard = Some(RunningServer { server, port });
+    Ok(())
+}
+
+/// Stop the webhook listener, returning whether one was running
+pub fn stop_webhook_server() -> bool {
+    let mut guard = WEBHOOK_SERVER.lock().unwrap();
+    match guard.take() {
+        Some(running) => {
+            running.server.unblock();
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn webhook_server_status() -> WebhookServerStatus {
+    let guard = WEBHOOK_SERVER.lock().unwrap();
+    let port = guard.as_ref().map(|r| r.port);
+    WebhookServerStatus {
+        running: port.is_some(),
+        port,
+        github_url: port.map(|p| format!("http://127.0.0.1:{}/webhooks/github", p)),
+        gitlab_url: port.map(|p| format!("http://127.0.0.1:{}/webhooks/gitlab", p)),
+    }
+}
+
+/// Answer requests until the server is unblocked by stop_webhook_server
+fn listen(server: &Server, target: &impl WebhookTarget) {
+    for request in server.incoming_requests() {
+        handle_request(target, request);
+    }
+}
+
+fn handle_request(target: &impl WebhookTarget, mut request: Request) {
+    let (status, body) = process_request(target, &mut request);
+
+    if status >= 400 {
+        log::warn!(
+            "Rejected webhook {} {}: {} {}",
+            request.method(),
+            request.url(),
+            status,
+            body
+        );
+    }
+
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+
+    if let Err(e) = request.respond(response) {
+        log::warn!("Failed to respond to webhook: {}", e);
+    }
+}
+
+/// Route a request to its provider, verify its signature or token and apply the event
+fn process_request(target: &impl WebhookTarget, request: &mut Request) -> (u16, serde_json::Value) {
+    if request.method() != &Method::Post {
+        return error(405, "Only POST is supported");
+    }
+
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let provider = match path.trim_end_matches('/') {
+        "/webhooks/github" => "github",
+        "/webhooks/gitlab" => "gitlab",
+        _ => return error(404, "Unknown webhook endpoint"),
+    };
+
+    let mut body = Vec::new();
+    if let Err(e) = request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut body)
+    {
+        return error(400, &format!("Failed to read body: {}", e));
+    }
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return error(413, "Payload too large");
+    }
+
+    let parsed = match provider {
+        "github" => verify_and_parse_github(target, request, &body),
+        _ => verify_and_parse_gitlab(target, request, &body),
+    };
+
+    let event = match parsed {
+        Ok(Some(event)) => event,
+        Ok(None) => return (200, json!({ "status": "ignored" })),
+        Err(response) => return response,
+    };
+
+    match target.apply(event) {
+        Ok(execution_ids) => (
+            200,
+            json!({ "status": "processed", "executions": execution_ids }),
+        ),
+        Err(e) => error(500, &format!("Failed to apply webhook: {}", e)),
+    }
+}
+
+type ParseResult = std::result::Result<Option<WebhookEvent>, (u16, serde_json::Value)>;
+
+fn verify_and_parse_github(
+    target: &impl WebhookTarget,
+    request: &Request,
+    body: &[u8],
+) -> ParseResult {
+    let secret = target
+        .secret("github_webhook_secret")
+        .ok_or_else(|| error(401, "GitHub webhook secret not configured"))?;
+
+    github::verify_signature(
+        &secret,
+        body,
+        header(request, "X-Hub-Signature-256").as_deref(),
+    )
+    .map_err(|e| error(401, &e.to_string()))?;
+
+    let event_type = header(request, "X-GitHub-Event")
+        .ok_or_else(|| error(400, "Missing X-GitHub-Event header"))?;
+
+    github::parse_event(&event_type, body).map_err(|e| error(400, &format!("{:#}", e)))
+}
+
+fn verify_and_parse_gitlab(
+    target: &impl WebhookTarget,
+    request: &Request,
+    body: &[u8],
+) -> ParseResult {
+    let secret = target
+        .secret("gitlab_webhook_token")
+        .ok_or_else(|| error(401, "GitLab webhook token not configured"))?;
+
+    gitlab::verify_token(&secret, header(request, "X-Gitlab-Token").as_deref())
+        .map_err(|e| error(401, &e.to_string()))?;
+
+    let event_type = header(request, "X-Gitlab-Event")
+        .ok_or_else(|| error(400, "Missing X-Gitlab-Event header"))?;
+
+    gitlab::parse_event(&event_type, body).map_err(|e| error(400, &format!("{:#}", e)))
+}
+
+fn header(request: &Request, name: &str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.as_str().as_str().eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str().to_string())
+}
+
+fn error(status: u16, message: &str) -> (u16, serde_json::Value) {
+    (status, json!({ "status": "error", "error": message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    const CHECK_RUN: &str = r#"{
+        "action": "completed",
+        "check_run": {
+            "id": 42,
+            "name": "lint",
+            "head_sha": "abc123",
+            "status": "completed",
+            "conclusion": "failure",
+            "html_url": "https://github.com/acme/widgets/runs/42"
+        },
+        "repository": { "full_name": "acme/widgets" }
+    }"#;
+
+    const PIPELINE: &str = r#"{
+        "object_kind": "pipeline",
+        "object_attributes": { "id": 99, "ref": "maestro/abc", "sha": "abc123", "status": "success" },
+        "project": { "path_with_namespace": "acme/widgets", "web_url": "https://gitlab.com/acme/widgets" }
+    }"#;
+
+    /// Records the events it's given instead of touching executions
+    struct Recorder {
+        events: Mutex<Vec<WebhookEvent>>,
+    }
+
+    impl WebhookTarget for Recorder {
+        fn secret(&self, key: &str) -> Option<String> {
+            match key {
+                "github_webhook_secret" => Some("gh-s3cret".to_string()),
+                "gitlab_webhook_token" => Some("gl-t0ken".to_string()),
+                _ => None,
+            }
+        }
+
+        fn apply(&self, event: WebhookEvent) -> Result<Vec<String>> {
+            self.events.lock().unwrap().push(event);
+            Ok(vec!["exec-1".to_string()])
+        }
+    }
+
+    /// Start a listener on an ephemeral port, returning its base URL
+    fn start(recorder: Arc<Recorder>) -> String {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        std::thread::spawn(move || listen(&server, recorder.as_ref()));
+        format!("http://127.0.0.1:{}", port)
+    }
+
+    fn sign(secret: &str, body: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body.as_bytes());
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[tokio::test]
+    async fn test_listener_verifies_deliveries() {
+        let recorder = Arc::new(Recorder {
+            events: Mutex::new(Vec::new()),
+        });
+        let base_url = start(recorder.clone());
+        let client = reqwest::Client::new();
+
+        let github = |signature: String| {
+            client
+                .post(format!("{}/webhooks/github", base_url))
+                .header("X-GitHub-Event", "check_run")
+                .header("X-Hub-Signature-256", signature)
+                .body(CHECK_RUN)
+                .send()
+        };
+        let response = github(sign("gh-s3cret", CHECK_RUN)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["status"], "processed");
+        assert_eq!(body["executions"], json!(["exec-1"]));
+
+        let response = github(sign("wrong", CHECK_RUN)).await.unwrap();
+        assert_eq!(response.status(), 401);
+
+        let gitlab = |token: &'static str| {
+            client
+                .post(format!("{}/webhooks/gitlab", base_url))
+                .header("X-Gitlab-Event", "Pipeline Hook")
+                .header("X-Gitlab-Token", token)
+                .body(PIPELINE)
+                .send()
+        };
+        assert_eq!(gitlab("gl-t0ken").await.unwrap().status(), 200);
+        assert_eq!(gitlab("gl-wrong").await.unwrap().status(), 401);
+
+        // Only the two authentic deliveries reached the app
+        {
+            let events = recorder.events.lock().unwrap();
+            assert_eq!(events.len(), 2);
+            match &events[0] {
+                WebhookEvent::Check { check, .. } => assert_eq!(check.context, "check:lint"),
+                other => panic!("unexpected event: {:?}", other),
+            }
+            match &events[1] {
+                WebhookEvent::Check { check, .. } => {
+                    assert_eq!(check.context, "gitlab:pipeline:99")
+                }
+                other => panic!("unexpected event: {:?}", other),
+            }
+        }
+
+        let response = client
+            .get(format!("{}/webhooks/github", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 405);
+    }
+}
@@ -6,6 +6,7 @@ import type {
 	Execution,
 	Analysis,
 	AnalysisType,
+	CiStatus,
 	HealthCheckResult,
 } from "./types"
 
@@ -67,12 +68,64 @@ export async function updateRepositoryName(id: string, name: string): Promise<vo
 }
 
 /**
- * Sync repository metadata from GitHub API (e.g., default branch)
+ * Set how a repository's admin clone is made ("full", "blobless", "treeless", "shallow:<depth>",
+ * or null for the prompt set's) and which paths its worktrees check out. A new strategy applies
+ * once the clone is recreated
+ */
+export async function updateRepositoryCheckout(
+	id: string,
+	cloneStrategy: string | null,
+	sparseCheckout: string[]
+): Promise<void> {
+	return invokeCommand<void>("update_repository_checkout", { id, cloneStrategy, sparseCheckout })
+}
+
+/**
+ * Sync repository metadata from the provider API (e.g., default branch). A rename or transfer
+ * updates the provider ID and moves the admin clone
  */
 export async function syncRepositoryMetadata(id: string): Promise<void> {
 	return invokeCommand<void>("sync_repository_metadata", { id })
 }
 
+/** A former identity of a repository renamed or transferred on its provider */
+export interface RepositoryRename {
+	id: number
+	repositoryId: string
+	oldProviderId: string
+	newProviderId: string
+	oldName: string | null
+	renamedAt: number
+}
+
+/**
+ * Rename history of a repository, newest first
+ */
+export async function getRepositoryRenames(repositoryId: string): Promise<RepositoryRename[]> {
+	return invokeCommand<RepositoryRename[]>("get_repository_renames", { repositoryId })
+}
+
+/** A repository whose default branch changed after a prompt set last ran on it */
+export interface DefaultBranchChange {
+	promptsetId: string
+	repositoryId: string
+	/** Default branch when the prompt set last ran */
+	oldBranch: string
+	newBranch: string
+	changedAt: number
+	lastRunAt: number
+}
+
+/**
+ * Repositories whose default branch changed since a prompt set last ran on them, for one
+ * prompt set or all of them
+ */
+export async function getDefaultBranchChanges(
+	promptsetId?: string | null
+): Promise<DefaultBranchChange[]> {
+	return invokeCommand<DefaultBranchChange[]>("get_default_branch_changes", { promptsetId })
+}
+
 /**
  * Get repository by ID
  */
@@ -93,6 +146,38 @@ export async function findRepository(
 	})
 }
 
+/** Filters for listing an organization's, user's or group's repositories */
+export interface RepositoryFilter {
+	/** Organization or user (GitHub), or group path such as "group/subgroup" (GitLab) */
+	namespace: string
+	topic?: string | null
+	language?: string | null
+	visibility?: "public" | "private" | "internal" | null
+	/** Only archived (true) or only active (false); both when unset */
+	archived?: boolean | null
+	/** Only forks (true) or only non-forks (false); both when unset */
+	fork?: boolean | null
+}
+
+/**
+ * Import the repositories of a GitHub organization/user or GitLab group matching a filter,
+ * optionally adding them to a prompt set. `host` selects a credential profile and prefixes
+ * the imported provider IDs
+ */
+export async function importRepositories(
+	provider: "github" | "gitlab",
+	filter: RepositoryFilter,
+	host?: string | null,
+	promptsetId?: string | null
+): Promise<Repository[]> {
+	return invokeCommand<Repository[]>("import_repositories", {
+		provider,
+		host,
+		filter,
+		promptsetId,
+	})
+}
+
 /**
  * Get all repositories
  */
@@ -100,6 +185,70 @@ export async function getAllRepositories(): Promise<Repository[]> {
 	return invokeCommand<Repository[]>("get_all_repositories")
 }
 
+// ============================================================================
+// Repository Tag Commands
+// ============================================================================
+
+export interface RepositoryTag {
+	repositoryId: string
+	tag: string
+	/** Imports only replace tags of their own source */
+	source: "manual" | "topic" | "catalog"
+	createdAt: number
+}
+
+export interface TagCount {
+	tag: string
+	repositoryCount: number
+}
+
+export interface TagImportResult {
+	repositoryId: string
+	tags: string[]
+	/** Why nothing was imported for this repository */
+	error: string | null
+}
+
+/**
+ * Tag a repository by hand; tags are normalized to lowercase
+ */
+export async function addRepositoryTags(repositoryId: string, tags: string[]): Promise<Repository> {
+	return invokeCommand<Repository>("add_repository_tags", { repositoryId, tags })
+}
+
+export async function removeRepositoryTag(repositoryId: string, tag: string): Promise<boolean> {
+	return invokeCommand<boolean>("remove_repository_tag", { repositoryId, tag })
+}
+
+export async function getRepositoryTags(repositoryId: string): Promise<RepositoryTag[]> {
+	return invokeCommand<RepositoryTag[]>("get_repository_tags", { repositoryId })
+}
+
+/**
+ * Every tag in use, with how many repositories carry it
+ */
+export async function getTagCounts(): Promise<TagCount[]> {
+	return invokeCommand<TagCount[]>("get_tag_counts")
+}
+
+/**
+ * Repositories a tag expression such as "team:payments and (tier:1 or tier:2)" matches
+ */
+export async function findRepositoriesByTags(expression: string): Promise<Repository[]> {
+	return invokeCommand<Repository[]>("find_repositories_by_tags", { expression })
+}
+
+/**
+ * Import tags from provider topics and language ("topic") or from CODEOWNERS and
+ * catalog-info.yaml in the admin clone ("catalog"), for some or all repositories
+ */
+export async function importRepositoryTags(
+	source: "topic" | "catalog",
+	repositoryIds?: string[] | null
+): Promise<TagImportResult[]> {
+	return invokeCommand<TagImportResult[]>("import_repository_tags", { source, repositoryIds })
+}
+
 /**
  * Delete repository by ID
  */
@@ -181,6 +330,93 @@ export async function updatePromptSetRepositories(
 	})
 }
 
+/**
+ * Save (or clear, with null) the Sourcegraph query a prompt set's repositories are resolved from
+ */
+export async function updatePromptSetRepositoryQuery(
+	id: string,
+	query: string | null
+): Promise<void> {
+	return invokeCommand<void>("update_promptset_repository_query", {
+		id,
+		query,
+	})
+}
+
+/**
+ * Save (or clear, with null) the tag expression, e.g. "team:payments and not tier:3", whose
+ * matching repositories are targeted alongside the prompt set's own
+ */
+export async function updatePromptSetRepositoryTagExpression(
+	id: string,
+	expression: string | null
+): Promise<void> {
+	return invokeCommand<void>("update_promptset_repository_tag_expression", {
+		id,
+		expression,
+	})
+}
+
+/**
+ * Set (or clear, with nulls) the applicability check run against each repository before executing
+ */
+export async function updatePromptSetApplicability(
+	id: string,
+	pattern: string | null,
+	pathGlob: string | null
+): Promise<void> {
+	return invokeCommand<void>("update_promptset_applicability", {
+		id,
+		pattern,
+		pathGlob,
+	})
+}
+
+/**
+ * Set the clone strategy of the prompt set's repositories that don't set their own, and the
+ * sparse checkout patterns added to theirs
+ */
+export async function updatePromptSetCheckout(
+	id: string,
+	cloneStrategy: string | null,
+	sparseCheckout: string[]
+): Promise<void> {
+	return invokeCommand<void>("update_promptset_checkout", {
+		id,
+		cloneStrategy,
+		sparseCheckout,
+	})
+}
+
+/**
+ * How a saved repository query resolved for one run of a revision
+ */
+export interface RepositoryQuerySnapshot {
+	id: number
+	promptsetId: string
+	revisionId: string
+	query: string
+	matchedRepositoryIds: string[]
+	/** Matches that weren't in the prompt set yet */
+	addedRepositoryIds: string[]
+	/** Prompt set repositories that no longer match; not run */
+	skippedRepositoryIds: string[]
+	/** Matches on code hosts Maestro doesn't support */
+	unsupportedRepositories: string[]
+	createdAt: number
+}
+
+/**
+ * Get the repository query snapshots of a revision's runs, newest first
+ */
+export async function getRepositoryQuerySnapshots(
+	revisionId: string
+): Promise<RepositoryQuerySnapshot[]> {
+	return invokeCommand<RepositoryQuerySnapshot[]>("get_repository_query_snapshots", {
+		revisionId,
+	})
+}
+
 /**
  * Delete prompt set by ID
  */
@@ -200,9 +436,13 @@ export async function getPromptSetRevisions(promptsetId: string): Promise<Prompt
 /**
  * Get all executions for a prompt set
  */
-export async function getExecutionsByPromptSet(promptsetId: string): Promise<Execution[]> {
+export async function getExecutionsByPromptSet(
+	promptsetId: string,
+	tagExpression?: string | null
+): Promise<Execution[]> {
 	return invokeCommand<Execution[]>("get_executions_by_promptset", {
 		promptsetId,
+		tagExpression,
 	})
 }
 
@@ -242,9 +482,13 @@ export async function deletePromptRevision(id: string): Promise<boolean> {
 /**
  * Get all executions for a revision
  */
-export async function getExecutionsByRevision(revisionId: string): Promise<Execution[]> {
+export async function getExecutionsByRevision(
+	revisionId: string,
+	tagExpression?: string | null
+): Promise<Execution[]> {
 	return invokeCommand<Execution[]>("get_executions_by_revision", {
 		revisionId,
+		tagExpression,
 	})
 }
 
@@ -419,10 +663,24 @@ export async function cleanupExecution(executionId: string): Promise<void> {
 export type TokenKey =
 	| "amp_token"
 	| "github_token"
+	| "github_instance_url"
+	| "github_api_url"
+	| "github_app_id"
+	| "github_app_private_key"
 	| "gitlab_token"
 	| "gitlab_instance_url"
 	| "sourcegraph_endpoint"
 	| "sourcegraph_token"
+	| "github_webhook_secret"
+	| "gitlab_webhook_token"
+	| "jenkins_url"
+	| "jenkins_username"
+	| "jenkins_api_token"
+	| "gitea_token"
+	| "gitea_instance_url"
+	| "bitbucket_url"
+	| "bitbucket_username"
+	| "bitbucket_token"
 
 /**
  * Set a token in the system keyring
@@ -441,10 +699,57 @@ export async function deleteToken(key: TokenKey): Promise<void> {
 export interface AllTokens {
 	ampToken: string | null
 	githubToken: string | null
+	githubInstanceUrl: string | null
+	githubApiUrl: string | null
+	githubAppId: string | null
+	githubAppPrivateKey: string | null
 	gitlabToken: string | null
 	gitlabInstanceUrl: string | null
 	sourcegraphEndpoint: string | null
 	sourcegraphToken: string | null
+	githubWebhookSecret: string | null
+	gitlabWebhookToken: string | null
+	jenkinsUrl: string | null
+	jenkinsUsername: string | null
+	jenkinsApiToken: string | null
+	giteaToken: string | null
+	giteaInstanceUrl: string | null
+	bitbucketUrl: string | null
+	bitbucketUsername: string | null
+	bitbucketToken: string | null
+	credentialProfiles: CredentialProfile[]
+}
+
+/**
+ * Named credentials for one provider host; repositories whose provider ID starts with
+ * the host use these instead of the provider's default tokens
+ */
+export interface CredentialProfile {
+	name: string
+	provider: "github" | "gitlab" | "gitea" | "bitbucket"
+	/** Host (with port, if any) as it appears in provider IDs */
+	host: string
+	baseUrl: string
+	apiUrl: string | null
+	username: string | null
+	/** SSH port when the host doesn't use the provider's default (22, or 7999 for Bitbucket) */
+	sshPort: number | null
+	/** Empty keeps the token of the profile being replaced */
+	token: string
+}
+
+/**
+ * Create a credential profile, or replace the one with the same name
+ */
+export async function setCredentialProfile(profile: CredentialProfile): Promise<void> {
+	return invokeCommand<void>("set_credential_profile", { profile })
+}
+
+/**
+ * Delete a credential profile from the system keyring
+ */
+export async function deleteCredentialProfile(name: string): Promise<void> {
+	return invokeCommand<void>("delete_credential_profile", { name })
 }
 
 /**
@@ -484,9 +789,18 @@ export interface SourcegraphRepository {
 export interface RepositorySearchResult {
 	repositories: SourcegraphRepository[]
 	totalCount: number
+	/** The search stopped at the limit (or the 5000 repository cap) or was otherwise incomplete */
 	hasNextPage: boolean
 }
 
+/**
+ * Payload of the "sourcegraph:search-progress" event, emitted as a search finds repositories
+ */
+export interface SourcegraphSearchProgressEvent {
+	query: string
+	repositoriesFound: number
+}
+
 /**
  * Search Sourcegraph repositories
  */
@@ -507,6 +821,7 @@ export async function searchSourcegraphRepositories(
 export interface ConfigPaths {
 	adminRepoDir: string
 	worktreeDir: string
+	ciLogDir: string
 	dbPath: string
 }
 
@@ -554,7 +869,7 @@ export async function openWorktreeWithTerminal(
 // ============================================================================
 
 /**
- * Start CI checking for an execution (spawns background polling)
+ * Start CI checking for an execution (queues it for the background CI watcher)
  */
 export async function startCiCheck(executionId: string): Promise<void> {
 	return invokeCommand<void>("start_ci_check", { executionId })
@@ -567,6 +882,182 @@ export async function refreshCiStatus(executionId: string): Promise<void> {
 	return invokeCommand<void>("refresh_ci_status", { executionId })
 }
 
+export interface CiLog {
+	provider: string
+	context: string
+	name: string
+	targetUrl: string | null
+	content: string
+	truncated: boolean
+	path: string
+	fetchedAt: number
+}
+
+/**
+ * Get trimmed logs for the failing CI checks of an execution (cached on disk)
+ */
+export async function getCiFailureLogs(
+	executionId: string,
+	refresh: boolean = false
+): Promise<CiLog[]> {
+	return invokeCommand<CiLog[]>("get_ci_failure_logs", { executionId, refresh })
+}
+
+export interface CiCheckRecord {
+	id: string
+	executionId: string
+	commitSha: string
+	provider: string
+	context: string
+	name: string
+	state: CiStatus
+	targetUrl: string | null
+	description: string | null
+	externalId: string | null
+	rawJson: string | null
+	firstSeenAt: number
+	stateChangedAt: number
+	completedAt: number | null
+	updatedAt: number
+}
+
+export interface CiCheckTransition {
+	checkId: string
+	fromState: CiStatus | null
+	toState: CiStatus
+	changedAt: number
+}
+
+export interface ExecutionCiChecks {
+	checks: CiCheckRecord[]
+	transitions: CiCheckTransition[]
+}
+
+export interface CiCheckFailureStat {
+	context: string
+	name: string
+	total: number
+	failed: number
+	passed: number
+}
+
+/**
+ * Get the individual CI checks recorded for an execution
+ */
+export async function getExecutionCiChecks(executionId: string): Promise<ExecutionCiChecks> {
+	return invokeCommand<ExecutionCiChecks>("get_execution_ci_checks", { executionId })
+}
+
+/**
+ * Get per-check failure counts across all executions of a revision
+ */
+export async function getRevisionCiCheckStats(
+	revisionId: string,
+	tagExpression?: string | null
+): Promise<CiCheckFailureStat[]> {
+	return invokeCommand<CiCheckFailureStat[]>("get_revision_ci_check_stats", {
+		revisionId,
+		tagExpression,
+	})
+}
+
+export interface CiCheckPolicy {
+	/** Glob patterns; when non-empty only matching checks gate the overall status */
+	required: string[]
+	/** Glob patterns for checks that never affect the overall status */
+	ignored: string[]
+	/** Add branch protection required checks from the provider */
+	discoverRequired: boolean
+}
+
+/**
+ * Get the CI check policy for a promptset, or a repository override when repositoryId is given
+ */
+export async function getCiCheckPolicy(
+	promptsetId: string,
+	repositoryId?: string
+): Promise<CiCheckPolicy | null> {
+	return invokeCommand<CiCheckPolicy | null>("get_ci_check_policy", {
+		promptsetId,
+		repositoryId,
+	})
+}
+
+/**
+ * Set the CI check policy for a promptset, or a repository override when repositoryId is given
+ */
+export async function setCiCheckPolicy(
+	promptsetId: string,
+	policy: CiCheckPolicy,
+	repositoryId?: string
+): Promise<void> {
+	return invokeCommand<void>("set_ci_check_policy", { promptsetId, repositoryId, policy })
+}
+
+/**
+ * Delete a CI check policy (a repository override falls back to the promptset default)
+ */
+export async function deleteCiCheckPolicy(
+	promptsetId: string,
+	repositoryId?: string
+): Promise<boolean> {
+	return invokeCommand<boolean>("delete_ci_check_policy", { promptsetId, repositoryId })
+}
+
+export interface RepositoryCiConfig {
+	repositoryId: string
+	/** CI system used instead of the git host's own ("jenkins" or "command") */
+	ciProvider: string
+	/** Provider config, e.g. `{ jobs: ["team/widgets"], multibranch: true }` for Jenkins */
+	config: Record<string, unknown>
+	updatedAt: number
+}
+
+/**
+ * Get the CI system override for a repository (null = the git host's own CI)
+ */
+export async function getRepositoryCiConfig(
+	repositoryId: string
+): Promise<RepositoryCiConfig | null> {
+	return invokeCommand<RepositoryCiConfig | null>("get_repository_ci_config", { repositoryId })
+}
+
+/**
+ * Point a repository's CI at another system (e.g. Jenkins)
+ */
+export async function setRepositoryCiConfig(
+	repositoryId: string,
+	ciProvider: string,
+	config: Record<string, unknown>
+): Promise<void> {
+	return invokeCommand<void>("set_repository_ci_config", { repositoryId, ciProvider, config })
+}
+
+/**
+ * Remove a repository's CI override, falling back to the git host's own CI
+ */
+export async function deleteRepositoryCiConfig(repositoryId: string): Promise<boolean> {
+	return invokeCommand<boolean>("delete_repository_ci_config", { repositoryId })
+}
+
+export interface CiWatch {
+	executionId: string
+	commitSha: string
+	attempts: number
+	nextPollAt: number
+	lastPolledAt: number | null
+	lastError: string | null
+	createdAt: number
+	expiredAt: number | null
+}
+
+/**
+ * List executions whose CI the background watcher is still polling
+ */
+export async function getCiWatches(): Promise<CiWatch[]> {
+	return invokeCommand<CiWatch[]>("get_ci_watches")
+}
+
 /**
  * Push committed changes to remote
  */
@@ -574,6 +1065,54 @@ export async function pushCommit(executionId: string, force: boolean = false): P
 	return invokeCommand<void>("push_commit", { executionId, force })
 }
 
+export interface PullRequest {
+	id: string
+	url: string
+}
+
+/**
+ * Open a pull request from a pushed execution branch into the default branch (Bitbucket only)
+ */
+export async function createPullRequest(
+	executionId: string,
+	title: string,
+	description?: string
+): Promise<PullRequest> {
+	return invokeCommand<PullRequest>("create_pull_request", { executionId, title, description })
+}
+
+// ============================================================================
+// Webhook Commands
+// ============================================================================
+
+export interface WebhookServerStatus {
+	running: boolean
+	port: number | null
+	githubUrl: string | null
+	gitlabUrl: string | null
+}
+
+/**
+ * Get whether the local webhook listener is running and its endpoint URLs
+ */
+export async function getWebhookServerStatus(): Promise<WebhookServerStatus> {
+	return invokeCommand<WebhookServerStatus>("get_webhook_server_status")
+}
+
+/**
+ * Start the local webhook listener (enabled across restarts until stopped)
+ */
+export async function startWebhookServer(port?: number): Promise<WebhookServerStatus> {
+	return invokeCommand<WebhookServerStatus>("start_webhook_server", { port })
+}
+
+/**
+ * Stop the local webhook listener
+ */
+export async function stopWebhookServer(): Promise<boolean> {
+	return invokeCommand<boolean>("stop_webhook_server")
+}
+
 // ============================================================================
 // Settings Commands
 // ============================================================================
@@ -593,12 +1132,83 @@ export async function setSetting(key: string, value: string): Promise<void> {
 }
 
 /**
- * Get the CI stuck threshold in minutes (how long before pending CI is marked as skipped)
+ * Get the CI grace period in minutes (how long pending CI waits for checks before asking the
+ * provider whether any will report)
  */
 export async function getCiStuckThresholdMinutes(): Promise<number> {
 	return invokeCommand<number>("get_ci_stuck_threshold_minutes", {})
 }
 
+/**
+ * Get the CI poll intervals in seconds (the last interval repeats)
+ */
+export async function getCiPollSchedule(): Promise<number[]> {
+	return invokeCommand<number[]>("get_ci_poll_schedule", {})
+}
+
+/**
+ * Set the CI poll intervals in seconds (the last interval repeats)
+ */
+export async function setCiPollSchedule(schedule: number[]): Promise<void> {
+	return invokeCommand<void>("set_ci_poll_schedule", { schedule })
+}
+
+/**
+ * Get how many hours a pending pipeline is polled before the watcher gives up on it
+ */
+export async function getCiWatchMaxHours(): Promise<number> {
+	return invokeCommand<number>("get_ci_watch_max_hours", {})
+}
+
+/**
+ * Set how many hours a pending pipeline is polled before the watcher gives up on it (at least 1)
+ */
+export async function setCiWatchMaxHours(hours: number): Promise<void> {
+	return invokeCommand<void>("set_ci_watch_max_hours", { hours })
+}
+
+/**
+ * Get the hours between background metadata syncs of each repository (0 when turned off)
+ */
+export async function getMetadataSyncIntervalHours(): Promise<number> {
+	return invokeCommand<number>("get_metadata_sync_interval_hours", {})
+}
+
+/**
+ * Set the hours between background metadata syncs of each repository; 0 turns them off
+ */
+export async function setMetadataSyncIntervalHours(hours: number): Promise<void> {
+	return invokeCommand<void>("set_metadata_sync_interval_hours", { hours })
+}
+
+/**
+ * Get the hours between scheduled gc runs of each admin clone (0 when turned off)
+ */
+export async function getCloneGcIntervalHours(): Promise<number> {
+	return invokeCommand<number>("get_clone_gc_interval_hours", {})
+}
+
+/**
+ * Set the hours between scheduled gc runs of each admin clone; 0 turns them off
+ */
+export async function setCloneGcIntervalHours(hours: number): Promise<void> {
+	return invokeCommand<void>("set_clone_gc_interval_hours", { hours })
+}
+
+/**
+ * Get the gigabytes clones and worktrees may use before clones are evicted (0 for no budget)
+ */
+export async function getDiskBudgetGb(): Promise<number> {
+	return invokeCommand<number>("get_disk_budget_gb", {})
+}
+
+/**
+ * Set the gigabytes clones and worktrees may use before clones are evicted; 0 for no budget
+ */
+export async function setDiskBudgetGb(gb: number): Promise<void> {
+	return invokeCommand<void>("set_disk_budget_gb", { gb })
+}
+
 /**
  * Get the maximum number of concurrent executions allowed
  */
@@ -706,6 +1316,27 @@ export async function healthCheckGitlab(): Promise<HealthCheckResult> {
 	return invokeCommand<HealthCheckResult>("health_check_gitlab")
 }
 
+/**
+ * Check Gitea/Forgejo token validity and get current username
+ */
+export async function healthCheckGitea(): Promise<HealthCheckResult> {
+	return invokeCommand<HealthCheckResult>("health_check_gitea")
+}
+
+/**
+ * Check Bitbucket Server/Data Center token validity and get current username
+ */
+export async function healthCheckBitbucket(): Promise<HealthCheckResult> {
+	return invokeCommand<HealthCheckResult>("health_check_bitbucket")
+}
+
+/**
+ * Check a credential profile's token against its own host and get current username
+ */
+export async function healthCheckCredentialProfile(name: string): Promise<HealthCheckResult> {
+	return invokeCommand<HealthCheckResult>("health_check_credential_profile", { name })
+}
+
 /**
  * Check Sourcegraph token validity and get current username
  */
@@ -741,6 +1372,78 @@ export async function healthCheckNodejs(): Promise<HealthCheckResult> {
 	return invokeCommand<HealthCheckResult>("health_check_nodejs")
 }
 
+/** Last known API budget of a provider host; times are ms since epoch */
+export interface RateLimitBudget {
+	host: string
+	limit: number | null
+	remaining: number | null
+	resetAt: number | null
+	retryAt: number | null
+	/** Set while requests to the host are held back */
+	throttledUntil: number | null
+	updatedAt: number
+}
+
+/**
+ * Get the API rate limit budget of every provider host contacted since startup
+ */
+export async function getRateLimits(): Promise<RateLimitBudget[]> {
+	return invokeCommand<RateLimitBudget[]>("get_rate_limits")
+}
+
+// ============================================================================
+// Maintenance Commands
+// ============================================================================
+
+/** Disk used by one admin clone and the execution worktrees made from it */
+export interface CloneUsage {
+	/** Relative to the admin repo directory, e.g. "acme/widgets" */
+	path: string
+	/** Null for a clone no repository owns any more */
+	repositoryId: string | null
+	providerId: string | null
+	cloneBytes: number
+	worktreeBytes: number
+	worktreeCount: number
+	lastUsedAt: number | null
+	lastGcAt: number | null
+}
+
+export interface DiskUsage {
+	cloneBytes: number
+	worktreeBytes: number
+	/** Worktrees whose execution was deleted, included in worktreeBytes */
+	orphanedWorktreeBytes: number
+	budgetBytes: number | null
+	clones: CloneUsage[]
+}
+
+export interface MaintenanceReport {
+	/** Provider IDs of the repositories whose clone was garbage collected */
+	collected: string[]
+	/** Clones removed because no repository owns them any more */
+	removedOrphans: string[]
+	removedWorktrees: number
+	/** Provider IDs of the repositories whose clone was evicted to fit the disk budget */
+	evicted: string[]
+	freedBytes: number
+	errors: string[]
+}
+
+/**
+ * Get the disk used by each admin clone and its execution worktrees
+ */
+export async function getDiskUsage(): Promise<DiskUsage> {
+	return invokeCommand<DiskUsage>("get_disk_usage")
+}
+
+/**
+ * Remove orphaned clones and worktrees, garbage collect every clone and enforce the disk budget
+ */
+export async function runCloneMaintenance(): Promise<MaintenanceReport> {
+	return invokeCommand<MaintenanceReport>("run_clone_maintenance")
+}
+
 // ============================================================================
 // Analysis Commands
 // ============================================================================
@@ -0,0 +1,139 @@
+import type { Repository, RepositoryProvider } from "./types"
+import { tokenStore } from "$lib/tokenStore"
+import { logger } from "$lib/logger"
+
+interface BitbucketRepository {
+	slug: string
+	name: string
+	project: {
+		key: string
+	}
+	links: {
+		self?: { href: string }[]
+	}
+	description?: string
+}
+
+interface BitbucketPage<T> {
+	values: T[]
+}
+
+/** Bitbucket Server / Data Center */
+export class BitbucketProvider implements RepositoryProvider {
+	name = "Bitbucket"
+	private token: string | null = null
+	private endpoint: string | null = null
+	private initialized = false
+	private initPromise: Promise<void> | null = null
+
+	constructor() {
+		// Don't auto-initialize in constructor
+	}
+
+	async initialize(): Promise<void> {
+		if (this.initialized) {
+			return
+		}
+
+		if (this.initPromise) {
+			return this.initPromise
+		}
+
+		this.initPromise = this.initializeToken()
+		await this.initPromise
+	}
+
+	private async initializeToken(): Promise<void> {
+		try {
+			const tokens = await tokenStore.getAllTokens()
+			// Bitbucket Server is always self-hosted, so both the token and the instance URL are required
+			if (tokens.bitbucketToken && tokens.bitbucketUrl) {
+				this.token = tokens.bitbucketToken
+				this.endpoint = tokens.bitbucketUrl.replace(/\/$/, "")
+			}
+		} finally {
+			this.initialized = true
+		}
+	}
+
+	isConfigured(): boolean {
+		return this.token !== null
+	}
+
+	private async bitbucketFetch(path: string): Promise<Response> {
+		if (!this.token || !this.endpoint) {
+			throw new Error("Bitbucket token not configured")
+		}
+
+		const url = `${this.endpoint}/rest/api/1.0${path}`
+		return fetch(url, {
+			headers: {
+				Authorization: `Bearer ${this.token}`,
+			},
+		})
+	}
+
+	private toRepository(repo: BitbucketRepository): Repository {
+		return {
+			provider: "bitbucket" as const,
+			fullName: `${repo.project.key}/${repo.slug}`,
+			name: repo.name,
+			owner: repo.project.key,
+			url:
+				repo.links.self?.[0]?.href ||
+				`${this.endpoint}/projects/${repo.project.key}/repos/${repo.slug}`,
+			description: repo.description || undefined,
+		}
+	}
+
+	async searchRepositories(query: string): Promise<Repository[]> {
+		await this.initialize()
+
+		if (!this.token) {
+			throw new Error("Bitbucket token not configured")
+		}
+
+		if (!query.trim()) {
+			return this.getUserRepositories()
+		}
+
+		try {
+			const response = await this.bitbucketFetch(`/repos?name=${encodeURIComponent(query)}&limit=20`)
+
+			if (!response.ok) {
+				const errorText = await response.text()
+				logger.error(`Bitbucket search failed: ${response.status} ${errorText}`)
+				return []
+			}
+
+			const page: BitbucketPage<BitbucketRepository> = await response.json()
+
+			return page.values.map((repo) => this.toRepository(repo))
+		} catch (error) {
+			logger.error(`Bitbucket repository search failed: ${error}`)
+			return []
+		}
+	}
+
+	async getUserRepositories(): Promise<Repository[]> {
+		await this.initialize()
+
+		if (!this.token) {
+			throw new Error("Bitbucket token not configured")
+		}
+
+		try {
+			const response = await this.bitbucketFetch("/repos?permission=REPO_WRITE&limit=50")
+
+			if (!response.ok) {
+				return []
+			}
+
+			const page: BitbucketPage<BitbucketRepository> = await response.json()
+
+			return page.values.map((repo) => this.toRepository(repo))
+		} catch (error) {
+			return []
+		}
+	}
+}
@@ -0,0 +1,133 @@
+import type { Repository, RepositoryProvider } from "./types"
+import { tokenStore } from "$lib/tokenStore"
+import { logger } from "$lib/logger"
+
+interface GiteaRepository {
+	full_name: string
+	name: string
+	owner: {
+		login: string
+	}
+	html_url: string
+	description: string
+}
+
+/** Gitea and Forgejo share the same REST API */
+export class GiteaProvider implements RepositoryProvider {
+	name = "Gitea"
+	private token: string | null = null
+	private endpoint: string | null = null
+	private initialized = false
+	private initPromise: Promise<void> | null = null
+
+	constructor() {
+		// Don't auto-initialize in constructor
+	}
+
+	async initialize(): Promise<void> {
+		if (this.initialized) {
+			return
+		}
+
+		if (this.initPromise) {
+			return this.initPromise
+		}
+
+		this.initPromise = this.initializeToken()
+		await this.initPromise
+	}
+
+	private async initializeToken(): Promise<void> {
+		try {
+			const tokens = await tokenStore.getAllTokens()
+			// Gitea is always self-hosted, so both the token and the instance URL are required
+			if (tokens.giteaToken && tokens.giteaInstanceUrl) {
+				this.token = tokens.giteaToken
+				this.endpoint = tokens.giteaInstanceUrl.replace(/\/$/, "")
+			}
+		} finally {
+			this.initialized = true
+		}
+	}
+
+	isConfigured(): boolean {
+		return this.token !== null
+	}
+
+	private async giteaFetch(path: string): Promise<Response> {
+		if (!this.token || !this.endpoint) {
+			throw new Error("Gitea token not configured")
+		}
+
+		const url = `${this.endpoint}/api/v1${path}`
+		return fetch(url, {
+			headers: {
+				Authorization: `token ${this.token}`,
+			},
+		})
+	}
+
+	private toRepository(repo: GiteaRepository): Repository {
+		return {
+			provider: "gitea" as const,
+			fullName: repo.full_name,
+			name: repo.name,
+			owner: repo.owner.login,
+			url: repo.html_url,
+			description: repo.description || undefined,
+		}
+	}
+
+	async searchRepositories(query: string): Promise<Repository[]> {
+		await this.initialize()
+
+		if (!this.token) {
+			throw new Error("Gitea token not configured")
+		}
+
+		if (!query.trim()) {
+			return this.getUserRepositories()
+		}
+
+		try {
+			const response = await this.giteaFetch(
+				`/repos/search?q=${encodeURIComponent(query)}&limit=20&sort=updated&order=desc`
+			)
+
+			if (!response.ok) {
+				const errorText = await response.text()
+				logger.error(`Gitea search failed: ${response.status} ${errorText}`)
+				return []
+			}
+
+			const result: { data: GiteaRepository[] } = await response.json()
+
+			return result.data.map((repo) => this.toRepository(repo))
+		} catch (error) {
+			logger.error(`Gitea repository search failed: ${error}`)
+			return []
+		}
+	}
+
+	async getUserRepositories(): Promise<Repository[]> {
+		await this.initialize()
+
+		if (!this.token) {
+			throw new Error("Gitea token not configured")
+		}
+
+		try {
+			const response = await this.giteaFetch("/user/repos?limit=50")
+
+			if (!response.ok) {
+				return []
+			}
+
+			const data: GiteaRepository[] = await response.json()
+
+			return data.map((repo) => this.toRepository(repo))
+		} catch (error) {
+			return []
+		}
+	}
+}
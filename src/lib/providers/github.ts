@@ -1,8 +1,21 @@
 import { Octokit } from "@octokit/rest"
 import type { Repository, RepositoryProvider } from "./types"
 import { tokenStore } from "$lib/tokenStore"
+import type { AllTokens } from "$lib/ipc"
 import { logger } from "$lib/logger"
 
+/** REST API base URL: explicit override, GitHub Enterprise Server's /api/v3, or api.github.com */
+function githubApiUrl(tokens: AllTokens): string {
+	if (tokens.githubApiUrl) {
+		return tokens.githubApiUrl.replace(/\/$/, "")
+	}
+	const instanceUrl = tokens.githubInstanceUrl?.replace(/\/$/, "")
+	if (instanceUrl && new URL(instanceUrl).host !== "github.com") {
+		return `${instanceUrl}/api/v3`
+	}
+	return "https://api.github.com"
+}
+
 export class GitHubProvider implements RepositoryProvider {
 	name = "GitHub"
 	private octokit: Octokit | null = null
@@ -30,7 +43,7 @@ export class GitHubProvider implements RepositoryProvider {
 		try {
 			const tokens = await tokenStore.getAllTokens()
 			if (tokens.githubToken) {
-				this.octokit = new Octokit({ auth: tokens.githubToken })
+				this.octokit = new Octokit({ auth: tokens.githubToken, baseUrl: githubApiUrl(tokens) })
 			}
 		} finally {
 			this.initialized = true
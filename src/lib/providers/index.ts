@@ -1,5 +1,7 @@
 import { GitHubProvider } from "./github"
 import { GitLabProvider } from "./gitlab"
+import { GiteaProvider } from "./gitea"
+import { BitbucketProvider } from "./bitbucket"
 import type { RepositoryProvider } from "./types"
 
 export async function getConfiguredProviders(): Promise<RepositoryProvider[]> {
@@ -17,8 +19,20 @@ export async function getConfiguredProviders(): Promise<RepositoryProvider[]> {
 		providers.push(gitlab)
 	}
 
+	const gitea = new GiteaProvider()
+	await gitea.initialize()
+	if (gitea.isConfigured()) {
+		providers.push(gitea)
+	}
+
+	const bitbucket = new BitbucketProvider()
+	await bitbucket.initialize()
+	if (bitbucket.isConfigured()) {
+		providers.push(bitbucket)
+	}
+
 	return providers
 }
 
 export * from "./types"
-export { GitHubProvider, GitLabProvider }
+export { GitHubProvider, GitLabProvider, GiteaProvider, BitbucketProvider }
@@ -1,5 +1,5 @@
 export interface Repository {
-	provider: "github" | "gitlab" | "bitbucket"
+	provider: "github" | "gitlab" | "gitea" | "bitbucket" | "git"
 	fullName: string
 	name: string
 	owner: string
@@ -106,7 +106,12 @@ export async function subscribeToExecutions() {
 			return new Map(map)
 		})
 		// Clear stats cache when execution completes so they can be refetched
-		if (status === "completed" || status === "failed" || status === "cancelled") {
+		if (
+			status === "completed" ||
+			status === "failed" ||
+			status === "cancelled" ||
+			status === "skipped"
+		) {
 			clearExecutionStats(executionId)
 		}
 	})
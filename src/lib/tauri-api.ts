@@ -45,7 +45,13 @@ export const tauriApi = {
 
 		update: async (
 			id: string,
-			updates: { validationPrompt?: string | null; repositoryIds?: string[] }
+			updates: {
+				validationPrompt?: string | null
+				repositoryIds?: string[]
+				repositoryQuery?: string | null
+				repositoryTagExpression?: string | null
+				applicability?: { pattern: string | null; pathGlob: string | null }
+			}
 		) => {
 			if ("validationPrompt" in updates) {
 				await ipc.updatePromptSetValidation(id, updates.validationPrompt ?? null)
@@ -53,6 +59,19 @@ export const tauriApi = {
 			if ("repositoryIds" in updates && updates.repositoryIds) {
 				await ipc.updatePromptSetRepositories(id, updates.repositoryIds)
 			}
+			if ("repositoryQuery" in updates) {
+				await ipc.updatePromptSetRepositoryQuery(id, updates.repositoryQuery ?? null)
+			}
+			if ("repositoryTagExpression" in updates) {
+				await ipc.updatePromptSetRepositoryTagExpression(
+					id,
+					updates.repositoryTagExpression ?? null
+				)
+			}
+			if (updates.applicability) {
+				const { pattern, pathGlob } = updates.applicability
+				await ipc.updatePromptSetApplicability(id, pattern, pathGlob)
+			}
 			return tauriApi.promptSets.get(id)
 		},
 
@@ -185,5 +204,21 @@ export const tauriApi = {
 		refreshStatus: async (id: string) => {
 			await ipc.refreshCiStatus(id)
 		},
+
+		failureLogs: async (id: string, refresh: boolean = false) => {
+			return ipc.getCiFailureLogs(id, refresh)
+		},
+
+		checks: async (id: string) => {
+			return ipc.getExecutionCiChecks(id)
+		},
+
+		revisionStats: async (revisionId: string) => {
+			return ipc.getRevisionCiCheckStats(revisionId)
+		},
+
+		watches: async () => {
+			return ipc.getCiWatches()
+		},
 	},
 }
@@ -19,6 +19,18 @@ class TokenStore {
 		this.tokenCache = null
 	}
 
+	async setCredentialProfile(profile: ipc.CredentialProfile): Promise<void> {
+		await ipc.setCredentialProfile(profile)
+		// Invalidate cache
+		this.tokenCache = null
+	}
+
+	async deleteCredentialProfile(name: string): Promise<void> {
+		await ipc.deleteCredentialProfile(name)
+		// Invalidate cache
+		this.tokenCache = null
+	}
+
 	/**
 	 * Load all tokens in a single keychain access (prevents multiple prompts)
 	 */
@@ -1,6 +1,12 @@
-export type RepositoryProvider = "github" | "gitlab"
+export type RepositoryProvider = "github" | "gitlab" | "gitea" | "bitbucket" | "git"
 
-export type ExecutionStatus = "pending" | "running" | "completed" | "failed" | "cancelled"
+export type ExecutionStatus =
+	| "pending"
+	| "running"
+	| "completed"
+	| "failed"
+	| "cancelled"
+	| "skipped"
 
 export type ValidationStatus = "pending" | "running" | "passed" | "failed" | "cancelled"
 
@@ -12,16 +18,52 @@ export interface Repository {
 	providerId: string
 	name: string | null
 	defaultBranch: string | null
+	archived: boolean
+	disabled: boolean
+	/** "public", "internal" or "private" */
+	visibility: string | null
+	language: string | null
+	topics: string[]
+	sizeKb: number | null
+	pushedAt: number | null
+	/** Null when the provider doesn't report push rights */
+	canPush: boolean | null
+	/** Normalized tags such as "team:payments", sorted */
+	tags: string[]
+	/** "full", "blobless", "treeless" or "shallow:<depth>"; null for the prompt set's */
+	cloneStrategy: string | null
+	/** Sparse checkout patterns of execution worktrees; empty checks out everything */
+	sparseCheckout: string[]
 	lastSyncedAt: number | null
 	createdAt: number
 }
 
+/** How a prompt set picks and filters the repositories it runs against */
+export interface RepositoryTargeting {
+	query: string | null
+	tagExpression: string | null
+	applicabilityPattern: string | null
+	applicabilityPathGlob: string | null
+}
+
 export interface PromptSet {
 	id: string
 	name: string
 	repositoryIds: string[]
 	validationPrompt: string | null
 	autoValidate: boolean
+	/** Sourcegraph query the repositories are re-resolved from on each run */
+	repositoryQuery: string | null
+	/** Regex some file must contain at the default branch for an execution to run */
+	applicabilityPattern: string | null
+	/** Glob the files checked against applicabilityPattern must match */
+	applicabilityPathGlob: string | null
+	/** Tag expression whose matching repositories are targeted on each run */
+	repositoryTagExpression: string | null
+	/** Clone strategy of repositories that don't set their own */
+	cloneStrategy: string | null
+	/** Sparse checkout patterns added to each repository's own */
+	sparseCheckout: string[]
 	createdAt: number
 	stats?: {
 		totalExecutions: number
@@ -77,6 +119,8 @@ export interface Execution {
 	ciCheckedAt: number | null
 	ciUrl: string | null
 	errorMessage: string | null
+	/** Why a skipped execution didn't run */
+	skipReason: string | null
 	createdAt: number
 	completedAt: number | null
 	progressMessage?: string
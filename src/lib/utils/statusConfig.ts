@@ -8,6 +8,7 @@ import {
 	AlertCircle,
 	GitCommit,
 	GitBranch,
+	SkipForward,
 } from "lucide-svelte"
 import type { ExecutionStatus, ValidationStatus, CommitStatus, AnalysisStatus } from "$lib/types"
 
@@ -29,6 +30,8 @@ export function getExecutionStatusConfig(status: ExecutionStatus): StatusConfig
 			return { Icon: XCircle, class: "text-destructive" }
 		case "cancelled":
 			return { Icon: Ban, class: "text-warning" }
+		case "skipped":
+			return { Icon: SkipForward, class: "text-muted-foreground" }
 		case "pending":
 			return { Icon: Clock, class: "text-muted-foreground" }
 		default: